@@ -0,0 +1,41 @@
+// Compiles the embedded password blocklists into FST sets, see src/blocklist.
+
+use std::collections::BTreeSet;
+use std::env;
+use std::fs;
+use std::path::Path;
+
+fn build_set(sources: &[&str], min_length: usize, out_name: &str) {
+    let mut words = BTreeSet::new();
+    for source in sources {
+        println!("cargo:rerun-if-changed={}", source);
+        let content = fs::read_to_string(source).expect("blocklist source is readable");
+        for line in content.lines() {
+            let word = line.trim().to_lowercase();
+            if word.chars().count() >= min_length {
+                words.insert(word);
+            }
+        }
+    }
+
+    let set = fst::Set::from_iter(words).expect("words are sorted and unique");
+    let out_dir = env::var("OUT_DIR").unwrap();
+    fs::write(Path::new(&out_dir).join(out_name), set.as_fst().as_bytes()).unwrap();
+}
+
+fn main() {
+    build_set(
+        &["src/blocklist/data/common-passwords.txt", "src/strength/data/passwords.txt"],
+        1,
+        "common-passwords.fst",
+    );
+    build_set(
+        &[
+            "src/blocklist/data/english-words.txt",
+            "src/strength/data/english.txt",
+            "src/strength/data/names.txt",
+        ],
+        4,
+        "english-words.fst",
+    );
+}
@@ -0,0 +1,212 @@
+//! Vault health report.
+//!
+//! The command decrypts the vault and rates each password, this module groups
+//! the findings and turns them into a health score. Every entry starts at 100
+//! and loses a fixed amount per problem, the vault score is the average.
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::collections::BTreeMap;
+
+const REUSED_PENALTY: u32 = 40;
+const BREACHED_PENALTY: u32 = 50;
+const WEAK_PENALTY: u32 = 30;
+const OLD_PENALTY: u32 = 15;
+const MISSING_DETAILS_PENALTY: u32 = 5;
+
+/// One decrypted entry with the problems found by the policy and strength checks.
+pub struct AuditItem {
+    pub name: String,
+    pub password: String,
+    // None when the stored timestamp cannot be parsed
+    pub updated_at: Option<DateTime<Utc>>,
+    pub username: Option<String>,
+    pub url: Option<String>,
+    pub weaknesses: Vec<String>,
+    pub breach_count: u64,
+    // from the policy of the entry or --max-age-days, 0 disables the age check
+    pub max_age_days: usize,
+}
+
+#[derive(Serialize, Debug)]
+pub struct WeakEntry {
+    pub name: String,
+    pub reasons: Vec<String>,
+}
+
+#[derive(Serialize, Debug)]
+pub struct OldEntry {
+    pub name: String,
+    pub age_days: Option<i64>,
+    pub max_age_days: usize,
+}
+
+#[derive(Serialize, Debug)]
+pub struct MissingDetails {
+    pub name: String,
+    pub missing: Vec<String>,
+}
+
+#[derive(Serialize, Debug)]
+pub struct BreachedEntry {
+    pub name: String,
+    pub count: u64,
+}
+
+#[derive(Serialize, Debug)]
+pub struct AuditReport {
+    pub health_score: u32,
+    pub entries: usize,
+    // None when each entry follows its own policy
+    pub max_age_days: Option<usize>,
+    // groups of entries sharing one password
+    pub reused: Vec<Vec<String>>,
+    pub weak: Vec<WeakEntry>,
+    pub old: Vec<OldEntry>,
+    pub missing_details: Vec<MissingDetails>,
+    // None when no HIBP file is configured
+    pub breached: Option<Vec<BreachedEntry>>,
+}
+
+impl AuditReport {
+    /// `max_age_days` is the --max-age-days override, only reported, the items carry the limit in use.
+    pub fn build(items: &[AuditItem], max_age_days: Option<usize>, breaches_checked: bool, now: DateTime<Utc>) -> Self {
+        let mut by_password: BTreeMap<&str, Vec<String>> = BTreeMap::new();
+        for item in items {
+            by_password.entry(&item.password).or_default().push(item.name.clone());
+        }
+        let mut reused: Vec<Vec<String>> = by_password.into_values().filter(|names| names.len() > 1).collect();
+        reused.sort();
+
+        let mut report = AuditReport {
+            health_score: 100,
+            entries: items.len(),
+            max_age_days,
+            reused,
+            weak: Vec::new(),
+            old: Vec::new(),
+            missing_details: Vec::new(),
+            breached: if breaches_checked { Some(Vec::new()) } else { None },
+        };
+
+        let mut total = 0;
+        for item in items {
+            let mut score: u32 = 100;
+
+            if report.reused.iter().any(|group| group.contains(&item.name)) {
+                score = score.saturating_sub(REUSED_PENALTY);
+            }
+
+            if item.breach_count > 0 {
+                if let Some(breached) = report.breached.as_mut() {
+                    breached.push(BreachedEntry { name: item.name.clone(), count: item.breach_count });
+                }
+                score = score.saturating_sub(BREACHED_PENALTY);
+            }
+
+            if !item.weaknesses.is_empty() {
+                report.weak.push(WeakEntry { name: item.name.clone(), reasons: item.weaknesses.clone() });
+                score = score.saturating_sub(WEAK_PENALTY);
+            }
+
+            // 0 disables the age check
+            let age_days = item.updated_at.map(|updated| (now - updated).num_days());
+            if item.max_age_days > 0 && age_days.map_or(true, |days| days > item.max_age_days as i64) {
+                report.old.push(OldEntry { name: item.name.clone(), age_days, max_age_days: item.max_age_days });
+                score = score.saturating_sub(OLD_PENALTY);
+            }
+
+            let mut missing = Vec::new();
+            if item.username.as_deref().map_or(true, str::is_empty) {
+                missing.push("username".to_string());
+            }
+            if item.url.as_deref().map_or(true, str::is_empty) {
+                missing.push("url".to_string());
+            }
+            if !missing.is_empty() {
+                report.missing_details.push(MissingDetails { name: item.name.clone(), missing });
+                score = score.saturating_sub(MISSING_DETAILS_PENALTY);
+            }
+
+            total += score;
+        }
+
+        if !items.is_empty() {
+            report.health_score = (total as f64 / items.len() as f64).round() as u32;
+        }
+        report
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+
+    fn item(name: &str, password: &str, age_days: i64, now: DateTime<Utc>) -> AuditItem {
+        AuditItem {
+            name: name.to_string(),
+            password: password.to_string(),
+            updated_at: Some(now - Duration::days(age_days)),
+            username: Some("alice".to_string()),
+            url: Some("https://example.com".to_string()),
+            weaknesses: Vec::new(),
+            breach_count: 0,
+            max_age_days: 365,
+        }
+    }
+
+    #[test]
+    fn test_healthy_vault_scores_100() {
+        let now = Utc::now();
+        let items = vec![item("mail", "a", 10, now), item("bank", "b", 20, now)];
+        let report = AuditReport::build(&items, None, false, now);
+        assert_eq!(report.health_score, 100);
+        assert!(report.reused.is_empty() && report.weak.is_empty() && report.old.is_empty());
+        assert!(report.breached.is_none());
+    }
+
+    #[test]
+    fn test_findings_and_score() {
+        let now = Utc::now();
+        let mut weak = item("forum", "shared", 400, now);
+        weak.weaknesses.push("score 1 is below the minimum of 2".to_string());
+        weak.username = None;
+        let mut breached = item("shop", "other", 5, now);
+        breached.breach_count = 12;
+        let items = vec![item("mail", "shared", 10, now), weak, breached, item("bank", "unique", 1, now)];
+
+        let report = AuditReport::build(&items, None, true, now);
+        assert_eq!(report.reused, vec![vec!["mail".to_string(), "forum".to_string()]]);
+        assert_eq!(report.weak.len(), 1);
+        assert_eq!(report.old[0].name, "forum");
+        assert_eq!(report.missing_details[0].missing, vec!["username".to_string()]);
+        assert_eq!(report.breached.as_ref().unwrap()[0].count, 12);
+        // mail 60, forum 100-40-30-15-5 = 10, shop 50, bank 100
+        assert_eq!(report.health_score, 55);
+    }
+
+    #[test]
+    fn test_unknown_age_counts_as_old() {
+        let now = Utc::now();
+        let mut legacy = item("legacy", "x", 0, now);
+        legacy.updated_at = None;
+        let report = AuditReport::build(&[legacy], None, false, now);
+        assert_eq!(report.old[0].age_days, None);
+        let mut unlimited = item("new", "y", 0, now);
+        unlimited.max_age_days = 0;
+        unlimited.updated_at = None;
+        assert_eq!(AuditReport::build(&[unlimited], None, false, now).old.len(), 0);
+    }
+
+    #[test]
+    fn test_age_limit_per_entry() {
+        let now = Utc::now();
+        let mut bank = item("bank", "a", 100, now);
+        bank.max_age_days = 90;
+        let report = AuditReport::build(&[bank, item("mail", "b", 100, now)], None, false, now);
+        assert_eq!(report.old.len(), 1);
+        assert_eq!(report.old[0].name, "bank");
+        assert_eq!(report.old[0].max_age_days, 90);
+    }
+}
@@ -1,84 +1,651 @@
 use crate::{
+    config::Config::KoflGlobalConfig,
+    constants::CONS,
+    context::Context,
     db::Db::Entry,
-    utils::Utils::{get_backup_dir, get_home_dir},
+    kdf,
+    layout,
+    setting::registry::SettingsRegistry,
+    utils::Utils::get_backup_dir,
 };
-use chrono::{DateTime, Utc};
-use log::debug;
+use aes_gcm::aead::{Aead, KeyInit, Payload};
+use aes_gcm::{Aes256Gcm, Nonce};
+use chrono::{DateTime, Datelike, NaiveDateTime, Utc};
+use flate2::read::DeflateDecoder;
+use flate2::write::DeflateEncoder;
+use flate2::Compression;
+use log::{debug, info, warn};
+use rand::distributions::Alphanumeric;
+use rand::{thread_rng, Rng, RngCore};
+use rusqlite::serialize::OwnedData;
+use rusqlite::{Connection, DatabaseName};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::fmt;
+use std::ptr::NonNull;
+use std::time::Duration;
+use std::collections::HashSet;
+use std::io::{Error, ErrorKind, Read, Write};
 use std::{fs, path::PathBuf};
 
+const ARCHIVE_MAGIC: &[u8; 8] = b"KOFLBAK1";
+const ARCHIVE_EXTENSION: &str = "koflbak";
+
+/// Decides which backups survive a prune.
+///
+/// A backup is kept if it is one of the `keep_last` most recent ones, or if it is
+/// the newest backup of one of the `keep_daily` most recent days, or the newest
+/// backup of one of the `keep_weekly` most recent ISO weeks.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RetentionPolicy {
+    pub keep_last: usize,
+    pub keep_daily: usize,
+    pub keep_weekly: usize,
+}
+
+impl RetentionPolicy {
+    pub fn new(keep_last: usize) -> Self {
+        RetentionPolicy {
+            keep_last,
+            keep_daily: CONS::BACKUP_DAILY_GENERATIONS,
+            keep_weekly: CONS::BACKUP_WEEKLY_GENERATIONS,
+        }
+    }
+
+    /// Build the policy from the `backup_retention_count` setting.
+    pub fn from_settings(settings: &SettingsRegistry) -> Self {
+        let keep_last = settings.backup_retention_count().unwrap_or(10) as usize;
+        RetentionPolicy::new(keep_last)
+    }
+
+    /// Returns the indexes (into `stamps`) of the backups to keep.
+    pub fn select(&self, stamps: &[NaiveDateTime]) -> HashSet<usize> {
+        let mut order: Vec<usize> = (0..stamps.len()).collect();
+        // newest first
+        order.sort_by(|a, b| stamps[*b].cmp(&stamps[*a]));
+
+        let mut keep: HashSet<usize> = order.iter().take(self.keep_last).cloned().collect();
+
+        let mut days = HashSet::new();
+        let mut weeks = HashSet::new();
+        for idx in order {
+            let stamp = stamps[idx];
+            if days.len() < self.keep_daily && days.insert(stamp.date()) {
+                keep.insert(idx);
+            }
+            let week = stamp.iso_week();
+            if weeks.len() < self.keep_weekly && weeks.insert((week.year(), week.week())) {
+                keep.insert(idx);
+            }
+        }
+        keep
+    }
+}
+
+impl Default for RetentionPolicy {
+    fn default() -> Self {
+        RetentionPolicy::from_settings(&SettingsRegistry::new())
+    }
+}
+
+/// A single backup generation found in the backup directory.
+#[derive(Debug, Clone)]
+pub struct BackupInfo {
+    pub name: String,
+    pub path: PathBuf,
+    pub created_at: NaiveDateTime,
+    pub size: u64,
+}
+
 pub struct Backup {
     backup_dir: PathBuf,
     backup_empty: bool,
+    retention: RetentionPolicy,
+}
+
+/// What the archive key is derived from.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum KeySource {
+    /// The vault key, itself derived from the master password and the vault salt.
+    #[default]
+    Master,
+    /// A separate backup passphrase run through PBKDF2.
+    Passphrase,
+}
+
+impl fmt::Display for KeySource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            KeySource::Master => write!(f, "master password"),
+            KeySource::Passphrase => write!(f, "backup passphrase"),
+        }
+    }
+}
+
+/// Plaintext header stored in front of the encrypted payload of an archive.
+/// It is also fed to AES-GCM as associated data, so it cannot be altered.
+/// It carries everything needed to re-derive the key on a fresh machine.
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ArchiveHeader {
+    pub version: u32,
+    pub created_at: DateTime<Utc>,
+    pub nonce: String,
+    #[serde(default)]
+    pub key_source: KeySource,
+    #[serde(default)]
+    pub salt: String,
+    #[serde(default)]
+    pub kdf_iterations: u32,
+    // a master password archive of a vault bound to a key file also needs the file
+    #[serde(default)]
+    pub keyfile: bool,
+}
+
+/// The backup passphrase key as persisted in the config, wrapped with the vault key
+/// so that automatic backups don't need to prompt for the passphrase. The vault key
+/// is never stored, a copy of the config alone does not unwrap it.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct BackupKey {
+    pub wrapped_key: String,
+    pub salt: String,
+    pub kdf_iterations: u32,
+}
+
+/// Key used to encrypt new archives, along with the metadata written in their header.
+pub struct ArchiveKey {
+    pub key: Vec<u8>,
+    pub source: KeySource,
+    pub salt: String,
+    pub kdf_iterations: u32,
+    pub keyfile: bool,
+}
+
+impl ArchiveKey {
+    /// Picks the backup passphrase key if one is configured, the vault key otherwise.
+    /// `vault_key` is the hex key of the unlocked vault, see `Context::vault_key`,
+    /// the config never holds anything an archive opens with.
+    pub fn from_config(kgc: &KoflGlobalConfig, vault_key: &str) -> std::io::Result<ArchiveKey> {
+        let vault_key = hex::decode(vault_key).map_err(invalid_data)?;
+
+        match kgc.get_backup_key() {
+            Some(backup_key) => {
+                let wrapped = hex::decode(&backup_key.wrapped_key).map_err(invalid_data)?;
+                Ok(ArchiveKey {
+                    key: open(&vault_key, &wrapped)?,
+                    source: KeySource::Passphrase,
+                    salt: backup_key.salt.clone(),
+                    kdf_iterations: backup_key.kdf_iterations,
+                    keyfile: false,
+                })
+            }
+            None => Ok(ArchiveKey {
+                key: vault_key,
+                source: KeySource::Master,
+                salt: kgc.get_salt(),
+                kdf_iterations: kgc.get_kdf_iterations(),
+                keyfile: kgc.has_keyfile(),
+            }),
+        }
+    }
+
+    /// Finds the key for an existing archive using the local config and the unlocked vault, without prompting.
+    pub fn for_header(header: &ArchiveHeader, kgc: &KoflGlobalConfig, vault_key: &str) -> Result<Vec<u8>, String> {
+        let current = ArchiveKey::from_config(kgc, vault_key).map_err(|e| e.to_string())?;
+        match header.key_source {
+            KeySource::Master => {
+                if !header.salt.is_empty() && header.salt != kgc.get_salt() {
+                    return Err("archive belongs to a different vault".to_string());
+                }
+                hex::decode(vault_key).map_err(|e| e.to_string())
+            }
+            KeySource::Passphrase => {
+                if current.source != KeySource::Passphrase || current.salt != header.salt {
+                    return Err("archive was made with a different backup passphrase".to_string());
+                }
+                Ok(current.key)
+            }
+        }
+    }
+
+    /// Re-derives the key of an archive from the secret typed by the user.
+    pub fn derive(header: &ArchiveHeader, secret: &str) -> Vec<u8> {
+        match header.key_source {
+            KeySource::Master => kdf::derive_master_key(secret, &header.salt, header.kdf_iterations),
+            KeySource::Passphrase => {
+                derive_passphrase_key(secret, &header.salt, header.kdf_iterations).to_vec()
+            }
+        }
+    }
+}
+
+impl BackupKey {
+    /// The same passphrase key wrapped with a new vault key.
+    pub fn rewrap(&self, old_vault_key: &[u8], new_vault_key: &[u8]) -> std::io::Result<BackupKey> {
+        let wrapped = hex::decode(&self.wrapped_key).map_err(invalid_data)?;
+        let key = open(old_vault_key, &wrapped)?;
+        Ok(BackupKey {
+            wrapped_key: hex::encode(seal(new_vault_key, &key)?),
+            salt: self.salt.clone(),
+            kdf_iterations: self.kdf_iterations,
+        })
+    }
+}
+
+/// Creates a new backup passphrase key, wrapped with `vault_key` for storage in the config.
+pub fn new_backup_key(passphrase: &str, vault_key: &[u8]) -> std::io::Result<BackupKey> {
+    let salt: String = thread_rng()
+        .sample_iter(&Alphanumeric)
+        .take(16)
+        .map(char::from)
+        .collect();
+    let key = derive_passphrase_key(passphrase, &salt, CONS::BACKUP_KDF_ITERATIONS);
+
+    Ok(BackupKey {
+        wrapped_key: hex::encode(seal(vault_key, &key)?),
+        salt,
+        kdf_iterations: CONS::BACKUP_KDF_ITERATIONS,
+    })
+}
+
+fn derive_passphrase_key(passphrase: &str, salt: &str, iterations: u32) -> [u8; 32] {
+    let mut key = [0u8; 32];
+    pbkdf2::pbkdf2_hmac::<Sha256>(passphrase.as_bytes(), salt.as_bytes(), iterations, &mut key);
+    key
+}
+
+/// AES-256-GCM encryption, the output is `nonce || ciphertext`.
+fn seal(key: &[u8], plaintext: &[u8]) -> std::io::Result<Vec<u8>> {
+    let mut nonce = [0u8; 12];
+    rand::thread_rng().fill_bytes(&mut nonce);
+    let cipher = Aes256Gcm::new_from_slice(key).map_err(invalid_data)?;
+    let mut out = nonce.to_vec();
+    out.extend(
+        cipher
+            .encrypt(Nonce::from_slice(&nonce), plaintext)
+            .map_err(|_| invalid_data("encryption failed"))?,
+    );
+    Ok(out)
+}
+
+fn open(key: &[u8], sealed: &[u8]) -> std::io::Result<Vec<u8>> {
+    if sealed.len() < 12 {
+        return Err(invalid_data("sealed data is too short"));
+    }
+    let (nonce, ciphertext) = sealed.split_at(12);
+    let cipher = Aes256Gcm::new_from_slice(key).map_err(invalid_data)?;
+    cipher
+        .decrypt(Nonce::from_slice(nonce), ciphertext)
+        .map_err(|_| invalid_data("decryption failed"))
+}
+
+fn invalid_data<E: ToString>(err: E) -> Error {
+    Error::new(ErrorKind::InvalidData, err.to_string())
 }
 
 impl Backup {
     pub fn new() -> Result<Backup, std::io::Error> {
-        let backup_dir = get_backup_dir().ok_or_else(|| {
-            std::io::Error::new(
-                std::io::ErrorKind::NotFound,
-                "Failed to create or access backup directory",
-            )
-        })?;
+        Backup::from_settings(&SettingsRegistry::new())
+    }
+
+    /// Uses `backup_location` when set, the backup directory of the vault otherwise.
+    pub fn from_settings(settings: &SettingsRegistry) -> Result<Backup, std::io::Error> {
+        let location = settings.backup_location().unwrap_or_default();
+
+        let backup_dir = if location.is_empty() {
+            get_backup_dir().ok_or_else(|| {
+                std::io::Error::new(
+                    std::io::ErrorKind::NotFound,
+                    "Failed to create or access backup directory",
+                )
+            })?
+        } else {
+            let dir = PathBuf::from(location);
+            layout::create_private_dir(&dir).map_err(std::io::Error::other)?;
+            dir
+        };
 
         Ok(Backup {
             backup_dir,
             backup_empty: false, // I should set this later to true, but do I even need this ?
+            retention: RetentionPolicy::from_settings(settings),
         })
     }
 
-         
+    pub fn get_backup_dir(&self) -> &PathBuf {
+        &self.backup_dir
+    }
+
+    /// Backs up the vault after a command changed it. The change is stored by then,
+    /// so a failure, like a backup_location on an unplugged drive, only warns.
+    pub fn after_write(context: &Context) {
+        match Backup::backup_context(context) {
+            Ok(path) => debug!("Backup written to {}", path.display()),
+            Err(e) => warn!("The change is saved, but no backup was made: {}", e),
+        }
+    }
+
+    fn backup_context(context: &Context) -> Result<PathBuf, String> {
+        let kgc = context.kgc.borrow();
+        let bc = Backup::from_settings(&kgc.get_settings()).map_err(|e| e.to_string())?;
+        let archive_key = ArchiveKey::from_config(&kgc, &context.vault_key()?).map_err(|e| e.to_string())?;
+        bc.create_new_backup(
+            kgc.get_config_path(),
+            &context.db.connection,
+            &kgc.get_config_path().with_extension("checksum"),
+            &archive_key,
+        )
+        .map_err(|e| e.to_string())
+    }
+
+    /// Snapshots the database through SQLite's online backup API, checks the copy
+    /// with `integrity_check`, then writes config, database and checksum into a single
+    /// compressed archive encrypted with `key`.
     pub fn create_new_backup(
         &self,
         kgc_file: &PathBuf,
-        db_file: &PathBuf,
+        db: &Connection,
         checksumfile: &PathBuf,
-    ) -> Result<(), std::io::Error> {
-        // Format directory name as YYYY-MM-DD_HH_MM_SS
-        let dir_name = Utc::now().format("%Y-%m-%d_%H_%M_%S").to_string();
+        key: &ArchiveKey,
+    ) -> Result<PathBuf, std::io::Error> {
+        layout::create_private_dir(&self.backup_dir).map_err(std::io::Error::other)?;
+
+        // Format file name as YYYY-MM-DD_HH_MM_SS.koflbak
+        let name = Utc::now().format(CONS::BACKUP_DIR_NAME_FORMAT).to_string();
+        let archive_path = self.backup_dir.join(&name).with_extension(ARCHIVE_EXTENSION);
+
+        let config = fs::read(kgc_file)?;
+        let checksum = fs::read(checksumfile)?;
+        let snapshot = Backup::snapshot_database(db)?;
+
+        let files = vec![
+            (".kofl".to_string(), config),
+            ("kofl.sqlite".to_string(), snapshot),
+            (".kofl.checksum".to_string(), checksum),
+        ];
+        Backup::write_archive(&archive_path, &files, key)?;
+
+        let removed = self.prune()?;
+        if !removed.is_empty() {
+            debug!("Pruned {} old backup(s)", removed.len());
+        }
 
-        // Create backup directory path
-        let backup_dir_path = self.backup_dir.join(&dir_name);
+        Ok(archive_path)
+    }
 
-        // Create the directory
-        fs::create_dir_all(&backup_dir_path)?;
+    // copied and checked in memory, the backup location may be a synced or
+    // shared folder and must never see the plaintext database
+    fn snapshot_database(db: &Connection) -> std::io::Result<Vec<u8>> {
+        let mut snapshot = Connection::open_in_memory().map_err(invalid_data)?;
+        rusqlite::backup::Backup::new(db, &mut snapshot)
+            .and_then(|copy| copy.run_to_completion(256, Duration::ZERO, None))
+            .map_err(invalid_data)?;
+        Backup::check_database(&snapshot).map_err(invalid_data)?;
+        let data = snapshot.serialize(DatabaseName::Main).map_err(invalid_data)?;
+        Ok(data.to_vec())
+    }
 
-        // Copy files to backup directory
-        fs::copy(kgc_file, backup_dir_path.join(".kofl"))?;
-        fs::copy(db_file, backup_dir_path.join("kofl.sqlite"))?;
-        fs::copy(checksumfile, backup_dir_path.join(".kofl.checksum"))?;
+    // the counterpart of `snapshot_database`, the decrypted database stays in memory
+    fn open_snapshot(data: &[u8]) -> Result<Connection, String> {
+        if data.is_empty() {
+            return Err("the database is empty".to_string());
+        }
+        let mut conn = Connection::open_in_memory().map_err(|e| e.to_string())?;
+        // SAFETY: deserialize takes over a buffer from sqlite3_malloc64, which is
+        // checked for null and filled with exactly `data.len()` bytes first
+        let owned = unsafe {
+            let ptr = NonNull::new(rusqlite::ffi::sqlite3_malloc64(data.len() as u64) as *mut u8)
+                .ok_or("out of memory for the database check")?;
+            std::ptr::copy_nonoverlapping(data.as_ptr(), ptr.as_ptr(), data.len());
+            OwnedData::from_raw_nonnull(ptr, data.len())
+        };
+        conn.deserialize(DatabaseName::Main, owned, true).map_err(|e| e.to_string())?;
+        Ok(conn)
+    }
 
+    fn check_database(conn: &Connection) -> Result<(), String> {
+        let result: String = conn
+            .query_row("PRAGMA integrity_check", [], |row| row.get(0))
+            .map_err(|e| e.to_string())?;
+        if result != "ok" {
+            return Err(format!("database integrity check failed: {}", result));
+        }
         Ok(())
     }
 
-    pub fn get_last_backup(&self) -> std::io::Result<Option<PathBuf>> {
-        let mut entries = fs::read_dir(&self.backup_dir)?
-            .filter_map(|e| e.ok())
-            .inspect(|entry| debug!("{:?}", entry.path()))
-            .collect::<Vec<_>>();
-
-        entries.sort_by_key(|dir| dir.metadata().and_then(|m| m.modified()).ok());
-
-        if let Some(last_entry) = entries.last() {
-            // println!("Last backup directory: {:?}", last_entry.file_name());
-            // println!("Path: {:?}", last_entry.path());
-            // println!("Modified: {:?}", last_entry.metadata()?.modified()?);
-
-            // Print files inside the last backup directory
-            for entry in fs::read_dir(last_entry.path())? {
-                let file = entry?;
-                debug!("{:?}", file.path());
+    fn write_archive(path: &PathBuf, files: &[(String, Vec<u8>)], key: &ArchiveKey) -> std::io::Result<()> {
+        // payload layout: [u16 name len][name][u64 data len][data] per file
+        let mut encoder = DeflateEncoder::new(Vec::new(), Compression::default());
+        for (name, data) in files {
+            encoder.write_all(&(name.len() as u16).to_be_bytes())?;
+            encoder.write_all(name.as_bytes())?;
+            encoder.write_all(&(data.len() as u64).to_be_bytes())?;
+            encoder.write_all(data)?;
+        }
+        let compressed = encoder.finish()?;
+
+        let mut nonce = [0u8; 12];
+        rand::thread_rng().fill_bytes(&mut nonce);
+        let header = ArchiveHeader {
+            version: 2,
+            created_at: Utc::now(),
+            nonce: hex::encode(nonce),
+            key_source: key.source,
+            salt: key.salt.clone(),
+            kdf_iterations: key.kdf_iterations,
+            keyfile: key.keyfile,
+        };
+        let header_bytes = toml::to_string(&header).map_err(invalid_data)?.into_bytes();
+
+        let cipher = Aes256Gcm::new_from_slice(&key.key).map_err(invalid_data)?;
+        let ciphertext = cipher
+            .encrypt(
+                Nonce::from_slice(&nonce),
+                Payload { msg: &compressed, aad: &header_bytes },
+            )
+            .map_err(|_| invalid_data("backup encryption failed"))?;
+
+        let mut out = Vec::with_capacity(ARCHIVE_MAGIC.len() + 4 + header_bytes.len() + ciphertext.len());
+        out.extend_from_slice(ARCHIVE_MAGIC);
+        out.extend_from_slice(&(header_bytes.len() as u32).to_be_bytes());
+        out.extend_from_slice(&header_bytes);
+        out.extend_from_slice(&ciphertext);
+        layout::write_private(path, out)
+    }
+
+    /// Decrypts and unpacks an archive, returning the files it contains.
+    pub fn read_archive(path: &PathBuf, key: &[u8]) -> std::io::Result<Vec<(String, Vec<u8>)>> {
+        let raw = fs::read(path)?;
+        let (header_bytes, ciphertext) = Backup::split_archive(&raw)?;
+        let header = Backup::parse_header(header_bytes)?;
+
+        let nonce = hex::decode(&header.nonce).map_err(invalid_data)?;
+        if nonce.len() != 12 {
+            return Err(invalid_data("invalid nonce in backup header"));
+        }
+        let cipher = Aes256Gcm::new_from_slice(key).map_err(invalid_data)?;
+        let compressed = cipher
+            .decrypt(
+                Nonce::from_slice(&nonce),
+                Payload { msg: ciphertext, aad: header_bytes },
+            )
+            .map_err(|_| invalid_data("backup decryption failed (wrong key or corrupted archive)"))?;
+
+        let mut payload = Vec::new();
+        DeflateDecoder::new(&compressed[..]).read_to_end(&mut payload)?;
+
+        let mut files = Vec::new();
+        let mut cursor = &payload[..];
+        while !cursor.is_empty() {
+            let name_len = u16::from_be_bytes(Backup::take(&mut cursor, 2)?.try_into().unwrap()) as usize;
+            let name = String::from_utf8(Backup::take(&mut cursor, name_len)?.to_vec()).map_err(invalid_data)?;
+            let data_len = u64::from_be_bytes(Backup::take(&mut cursor, 8)?.try_into().unwrap()) as usize;
+            let data = Backup::take(&mut cursor, data_len)?.to_vec();
+            files.push((name, data));
+        }
+        Ok(files)
+    }
+
+    /// Reads the plaintext header of an archive, no key needed.
+    pub fn read_header(path: &PathBuf) -> std::io::Result<ArchiveHeader> {
+        let raw = fs::read(path)?;
+        let (header_bytes, _) = Backup::split_archive(&raw)?;
+        Backup::parse_header(header_bytes)
+    }
+
+    fn parse_header(header_bytes: &[u8]) -> std::io::Result<ArchiveHeader> {
+        toml::from_str(std::str::from_utf8(header_bytes).map_err(invalid_data)?).map_err(invalid_data)
+    }
+
+    fn split_archive(raw: &[u8]) -> std::io::Result<(&[u8], &[u8])> {
+        let mut cursor = raw;
+        if Backup::take(&mut cursor, ARCHIVE_MAGIC.len())? != ARCHIVE_MAGIC {
+            return Err(invalid_data("not a kofl backup archive"));
+        }
+        let header_len = u32::from_be_bytes(Backup::take(&mut cursor, 4)?.try_into().unwrap()) as usize;
+        let header = Backup::take(&mut cursor, header_len)?;
+        Ok((header, cursor))
+    }
+
+    fn take<'a>(cursor: &mut &'a [u8], len: usize) -> std::io::Result<&'a [u8]> {
+        if cursor.len() < len {
+            return Err(invalid_data("truncated backup archive"));
+        }
+        let (head, tail) = cursor.split_at(len);
+        *cursor = tail;
+        Ok(head)
+    }
+
+    /// Lists every backup generation, oldest first.
+    /// Entries whose name is not a backup timestamp are ignored.
+    pub fn list_backups(&self) -> std::io::Result<Vec<BackupInfo>> {
+        if !self.backup_dir.is_dir() {
+            return Ok(Vec::new());
+        }
+
+        let mut backups = Vec::new();
+        for entry in fs::read_dir(&self.backup_dir)? {
+            let entry = entry?;
+            let path = entry.path();
+            let name = entry.file_name().to_string_lossy().to_string();
+            // older backups are directories of loose files, newer ones are archives
+            let stem = if path.is_dir() {
+                name.as_str()
+            } else {
+                match name.strip_suffix(&format!(".{}", ARCHIVE_EXTENSION)) {
+                    Some(stem) => stem,
+                    None => {
+                        debug!("Skipping unknown entry in backup dir: {}", name);
+                        continue;
+                    }
+                }
+            };
+            let created_at =
+                match NaiveDateTime::parse_from_str(stem, CONS::BACKUP_DIR_NAME_FORMAT) {
+                    Ok(stamp) => stamp,
+                    Err(_) => {
+                        debug!("Skipping unknown entry in backup dir: {}", name);
+                        continue;
+                    }
+                };
+
+            let size = if path.is_dir() {
+                fs::read_dir(&path)?
+                    .filter_map(|f| f.ok())
+                    .filter_map(|f| f.metadata().ok())
+                    .map(|m| m.len())
+                    .sum()
+            } else {
+                entry.metadata()?.len()
+            };
+
+            backups.push(BackupInfo { name: stem.to_string(), path, created_at, size });
+        }
+
+        backups.sort_by_key(|b| b.created_at);
+        Ok(backups)
+    }
+
+    /// Checks that a backup is complete and usable: all files are present,
+    /// the config matches its checksum and the database passes `integrity_check`.
+    pub fn verify_backup(&self, backup: &BackupInfo, key: &[u8]) -> Result<(), String> {
+        let files = if backup.path.is_dir() {
+            let mut files = Vec::new();
+            for name in [".kofl", "kofl.sqlite", ".kofl.checksum"] {
+                let file = backup.path.join(name);
+                if !file.is_file() {
+                    return Err(format!("missing file {}", file.display()));
+                }
+                files.push((name.to_string(), fs::read(&file).map_err(|e| e.to_string())?));
+            }
+            files
+        } else {
+            Backup::read_archive(&backup.path, key).map_err(|e| e.to_string())?
+        };
+
+        self.check_files(&backup.name, &files)
+    }
+
+    /// Same checks as `verify_backup`, on files already unpacked from an archive.
+    pub fn check_files(&self, name: &str, files: &[(String, Vec<u8>)]) -> Result<(), String> {
+        let find = |file_name: &str| {
+            files
+                .iter()
+                .find(|(n, _)| n == file_name)
+                .map(|(_, data)| data)
+                .ok_or(format!("missing file {}", file_name))
+        };
+        let config = find(".kofl")?;
+        let stored_checksum = String::from_utf8_lossy(find(".kofl.checksum")?).to_string();
+        let database = find("kofl.sqlite")?;
+
+        let mut hasher = Sha256::new();
+        hasher.update(config);
+        if hex::encode(hasher.finalize()) != stored_checksum.trim() {
+            return Err("config checksum mismatch".to_string());
+        }
+
+        let conn = Backup::open_snapshot(database).map_err(|e| format!("{}: {}", name, e))?;
+        Backup::check_database(&conn)
+    }
+
+    /// Removes the backups that fall outside the retention policy
+    /// and returns the ones that were deleted.
+    pub fn prune(&self) -> std::io::Result<Vec<BackupInfo>> {
+        let backups = self.list_backups()?;
+        let stamps: Vec<NaiveDateTime> = backups.iter().map(|b| b.created_at).collect();
+        let keep = self.retention.select(&stamps);
+
+        let mut removed = Vec::new();
+        for (idx, backup) in backups.into_iter().enumerate() {
+            if keep.contains(&idx) {
+                continue;
+            }
+            if backup.path.is_dir() {
+                fs::remove_dir_all(&backup.path)?;
+            } else {
+                fs::remove_file(&backup.path)?;
             }
+            info!("Removed backup {}", backup.name);
+            removed.push(backup);
+        }
+        Ok(removed)
+    }
 
-            return Ok(Some(last_entry.path()));
+    pub fn get_last_backup(&self) -> std::io::Result<Option<PathBuf>> {
+        let backups = self.list_backups()?;
+        for backup in &backups {
+            debug!("{:?}", backup.path);
         }
 
-        Ok(None)
+        Ok(backups.last().map(|b| b.path.clone()))
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use serial_test::serial;
     use std::{env, fs};
     use tempfile::TempDir;
 
@@ -111,24 +678,43 @@ mod tests {
         }
     }
 
-    fn create_test_files(temp_dir: &TempDir) -> (PathBuf, PathBuf, PathBuf) {
+    const TEST_KEY: [u8; 32] = [7u8; 32];
+
+    fn test_key() -> ArchiveKey {
+        ArchiveKey {
+            key: TEST_KEY.to_vec(),
+            source: KeySource::Master,
+            salt: "test_salt".to_string(),
+            kdf_iterations: 0,
+            keyfile: false,
+        }
+    }
+
+    fn create_test_files(temp_dir: &TempDir) -> (PathBuf, Connection, PathBuf) {
         let kgc_file = temp_dir.path().join(".kofl");
         let db_file = temp_dir.path().join("kofl.sqlite");
         let checksum_file = temp_dir.path().join(".kofl.checksum");
 
         // Create test files with some content
         fs::write(&kgc_file, "test config content").unwrap();
-        fs::write(&db_file, "test database content").unwrap();
-        fs::write(&checksum_file, "test checksum content").unwrap();
+        fs::write(&checksum_file, hex::encode(Sha256::digest(b"test config content"))).unwrap();
 
-        (kgc_file, db_file, checksum_file)
+        let db = Connection::open(&db_file).unwrap();
+        db.execute_batch(
+            "CREATE TABLE IF NOT EXISTS entry (id INTEGER PRIMARY KEY, ent_name TEXT);
+             INSERT INTO entry (ent_name) VALUES ('test database content');",
+        )
+        .unwrap();
+
+        (kgc_file, db, checksum_file)
     }
 
     #[test]
+    #[serial]
     fn test_backup_creation_success() {
         let temp_dir = tempfile::tempdir().unwrap();
-        let home_guard = EnvGuard::new("HOME");
-        home_guard.set_var(temp_dir.path().to_str().unwrap());
+        let kofl_home_guard = EnvGuard::new("KOFL_HOME");
+        kofl_home_guard.set_var(temp_dir.path().to_str().unwrap());
 
         let backup = Backup::new();
         assert!(backup.is_ok());
@@ -139,13 +725,14 @@ mod tests {
     }
 
     #[test]
+    #[serial]
     fn test_backup_dir_already_exists() {
         let temp_dir = tempfile::tempdir().unwrap();
-        let home_guard = EnvGuard::new("HOME");
-        home_guard.set_var(temp_dir.path().to_str().unwrap());
+        let kofl_home_guard = EnvGuard::new("KOFL_HOME");
+        kofl_home_guard.set_var(temp_dir.path().to_str().unwrap());
 
         // Create backup directory manually first
-        let backup_path = temp_dir.path().join(".kofl_backups");
+        let backup_path = temp_dir.path().join("backups");
         fs::create_dir_all(&backup_path).unwrap();
 
         let backup = Backup::new();
@@ -153,10 +740,11 @@ mod tests {
     }
 
     #[test]
+    #[serial]
     fn test_backup_empty_flag() {
         let temp_dir = tempfile::tempdir().unwrap();
-        let home_guard = EnvGuard::new("HOME");
-        home_guard.set_var(temp_dir.path().to_str().unwrap());
+        let kofl_home_guard = EnvGuard::new("KOFL_HOME");
+        kofl_home_guard.set_var(temp_dir.path().to_str().unwrap());
 
         let backup = Backup::new().unwrap();
         assert!(!backup.backup_empty);
@@ -170,25 +758,26 @@ mod tests {
         let backup = Backup {
             backup_dir: backup_dir.clone(),
             backup_empty: true,
+            retention: RetentionPolicy::new(10),
         };
 
-        let (kgc_file, db_file, checksum_file) = create_test_files(&temp_dir);
-        let (kgc_file1, db_file1, checksum_file1) = create_test_files(&temp_dir);
+        let (kgc_file, db, checksum_file) = create_test_files(&temp_dir);
+        let (kgc_file1, db1, checksum_file1) = create_test_files(&temp_dir);
 
         // Act
-        let result = backup.create_new_backup(&kgc_file, &db_file, &checksum_file);
+        let result = backup.create_new_backup(&kgc_file, &db, &checksum_file, &test_key());
         assert!(result.is_ok(), "First backup creation should succeed");
 
         std::thread::sleep(std::time::Duration::from_secs(1)); // Ensure different timestamp
 
-        let result1 = backup.create_new_backup(&kgc_file1, &db_file1, &checksum_file1);
+        let result1 = backup.create_new_backup(&kgc_file1, &db1, &checksum_file1, &test_key());
         assert!(result1.is_ok(), "Second backup creation should succeed");
 
         let last_backup_path = backup.get_last_backup().expect("Failed to get last backup");
 
         // Assert
         let entries = fs::read_dir(&backup_dir).unwrap().collect::<Vec<_>>();
-        assert_eq!(entries.len(), 2, "There should be two backup directories");
+        assert_eq!(entries.len(), 2, "There should be two backup archives");
 
         // Check if the last backup path is correct
         assert!(last_backup_path.is_some(), "Last backup path should exist");
@@ -208,55 +797,45 @@ mod tests {
         let backup = Backup {
             backup_dir: backup_dir.clone(),
             backup_empty: true,
+            retention: RetentionPolicy::new(10),
         };
 
-        let (kgc_file, db_file, checksum_file) = create_test_files(&temp_dir);
+        let (kgc_file, db, checksum_file) = create_test_files(&temp_dir);
 
         // Act
-        let result = backup.create_new_backup(&kgc_file, &db_file, &checksum_file);
+        let result = backup.create_new_backup(&kgc_file, &db, &checksum_file, &test_key());
 
         // Assert
         assert!(result.is_ok(), "Backup creation should succeed");
 
-        // Check if backup directory exists
-        let backup_dirs = fs::read_dir(&backup_dir).unwrap();
-        let backup_dir_entry = backup_dirs.into_iter().next().unwrap().unwrap();
+        // A single archive file is written per backup
+        let backup_files: Vec<_> = fs::read_dir(&backup_dir)
+            .unwrap()
+            .map(|entry| entry.unwrap().path())
+            .collect();
+        assert_eq!(backup_files.len(), 1, "Only the archive should be left in the backup dir");
+        let archive_path = &backup_files[0];
+        assert!(archive_path.is_file());
+        assert_eq!(archive_path.extension().unwrap(), ARCHIVE_EXTENSION);
 
-        // Verify directory name format
-        let dir_name = backup_dir_entry.file_name();
-        let dir_name_str = dir_name.to_str().unwrap();
-        // println!("{}", dir_name_str);
-        // assert!(dir_name_str.matches(r"d{4}-d{2}-d{2}_d{2}_d{2}_d{2}").count() == 1,
-        //         "Backup directory name should match YYYY-MM-DD_HH_MM_SS format");
-
-        // Verify files exist and content matches
-        let backup_path = backup_dir_entry.path();
-        assert!(
-            backup_path.join(".kofl").exists(),
-            "Config file should exist in backup"
-        );
-        assert!(
-            backup_path.join("kofl.sqlite").exists(),
-            "Database file should exist in backup"
-        );
-        assert!(
-            backup_path.join(".kofl.checksum").exists(),
-            "Checksum file should exist in backup"
-        );
+        // The archive is not readable as plain text
+        let raw = fs::read(archive_path).unwrap();
+        assert!(!String::from_utf8_lossy(&raw).contains("test config content"));
 
         // Verify content
-        assert_eq!(
-            fs::read_to_string(backup_path.join(".kofl")).unwrap(),
-            "test config content"
-        );
-        assert_eq!(
-            fs::read_to_string(backup_path.join("kofl.sqlite")).unwrap(),
-            "test database content"
-        );
-        assert_eq!(
-            fs::read_to_string(backup_path.join(".kofl.checksum")).unwrap(),
-            "test checksum content"
-        );
+        let files = Backup::read_archive(archive_path, &TEST_KEY).unwrap();
+        let names: Vec<_> = files.iter().map(|(name, _)| name.as_str()).collect();
+        assert_eq!(names, vec![".kofl", "kofl.sqlite", ".kofl.checksum"]);
+        assert_eq!(files[0].1, b"test config content");
+
+        // The database snapshot is a valid sqlite file holding the same rows
+        let restored = temp_dir.path().join("restored.sqlite");
+        fs::write(&restored, &files[1].1).unwrap();
+        let name: String = Connection::open(&restored)
+            .unwrap()
+            .query_row("SELECT ent_name FROM entry", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(name, "test database content");
     }
 
     #[test]
@@ -268,13 +847,15 @@ mod tests {
         let backup = Backup {
             backup_dir: backup_dir.clone(),
             backup_empty: true,
+            retention: RetentionPolicy::new(10),
         };
 
         let nonexistent_file = temp_dir.path().join("nonexistent");
+        let db = Connection::open_in_memory().unwrap();
 
         // Act
         let result =
-            backup.create_new_backup(&nonexistent_file, &nonexistent_file, &nonexistent_file);
+            backup.create_new_backup(&nonexistent_file, &db, &nonexistent_file, &test_key());
 
         // Assert
         assert!(
@@ -292,20 +873,21 @@ mod tests {
         let backup = Backup {
             backup_dir: backup_dir.clone(),
             backup_empty: false,
+            retention: RetentionPolicy::new(10),
         };
 
-        let (kgc_file, db_file, checksum_file) = create_test_files(&temp_dir);
+        let (kgc_file, db, checksum_file) = create_test_files(&temp_dir);
 
         // Create first backup
         backup
-            .create_new_backup(&kgc_file, &db_file, &checksum_file)
+            .create_new_backup(&kgc_file, &db, &checksum_file, &test_key())
             .unwrap();
 
         // Wait a second to ensure different timestamp
         std::thread::sleep(std::time::Duration::from_secs(1));
 
         // Create second backup
-        let result = backup.create_new_backup(&kgc_file, &db_file, &checksum_file);
+        let result = backup.create_new_backup(&kgc_file, &db, &checksum_file, &test_key());
 
         // Assert
         assert!(result.is_ok(), "Second backup should succeed");
@@ -316,7 +898,7 @@ mod tests {
             .map(|entry| entry.unwrap())
             .collect();
 
-        assert_eq!(backup_dirs.len(), 2, "Should have two backup directories");
+        assert_eq!(backup_dirs.len(), 2, "Should have two backup archives");
 
         // Verify different timestamps
         let first_backup = backup_dirs[0].file_name().into_string().unwrap();
@@ -327,6 +909,172 @@ mod tests {
         );
     }
 
+    fn stamp(value: &str) -> NaiveDateTime {
+        NaiveDateTime::parse_from_str(value, CONS::BACKUP_DIR_NAME_FORMAT).unwrap()
+    }
+
+    #[test]
+    fn test_retention_keeps_last_n() {
+        let policy = RetentionPolicy {
+            keep_last: 2,
+            keep_daily: 0,
+            keep_weekly: 0,
+        };
+        let stamps = vec![
+            stamp("2025-01-01_10_00_00"),
+            stamp("2025-01-01_11_00_00"),
+            stamp("2025-01-01_12_00_00"),
+        ];
+
+        let keep = policy.select(&stamps);
+
+        assert_eq!(keep.len(), 2);
+        assert!(keep.contains(&1) && keep.contains(&2), "Newest two should be kept");
+    }
+
+    #[test]
+    fn test_retention_keeps_daily_and_weekly_generations() {
+        let policy = RetentionPolicy {
+            keep_last: 1,
+            keep_daily: 2,
+            keep_weekly: 2,
+        };
+        let stamps = vec![
+            stamp("2025-01-01_09_00_00"), // week 1, oldest day
+            stamp("2025-01-01_18_00_00"), // week 1, newest of its day
+            stamp("2025-01-08_09_00_00"), // week 2
+            stamp("2025-01-09_09_00_00"), // week 2
+            stamp("2025-01-09_10_00_00"), // newest
+        ];
+
+        let keep = policy.select(&stamps);
+
+        // last: 4, daily: 4 (Jan 9) and 2 (Jan 8), weekly: 4 (week 2) and 1 (week 1)
+        let mut kept: Vec<_> = keep.into_iter().collect();
+        kept.sort();
+        assert_eq!(kept, vec![1, 2, 4]);
+    }
+
+    #[test]
+    fn test_prune_removes_backups_outside_policy() {
+        let temp_dir = TempDir::new().expect("Failed to create temp dir");
+        let backup_dir = temp_dir.path().join("backups");
+
+        for name in ["2025-01-01_10_00_00", "2025-01-01_11_00_00", "2025-01-01_12_00_00"] {
+            fs::create_dir_all(backup_dir.join(name)).unwrap();
+        }
+        fs::write(backup_dir.join("notes.txt"), "not a backup").unwrap();
+
+        let backup = Backup {
+            backup_dir: backup_dir.clone(),
+            backup_empty: false,
+            retention: RetentionPolicy {
+                keep_last: 1,
+                keep_daily: 0,
+                keep_weekly: 0,
+            },
+        };
+
+        let removed = backup.prune().unwrap();
+
+        assert_eq!(removed.len(), 2, "Two backups should be pruned");
+        let remaining = backup.list_backups().unwrap();
+        assert_eq!(remaining.len(), 1);
+        assert_eq!(remaining[0].name, "2025-01-01_12_00_00");
+        assert!(backup_dir.join("notes.txt").exists(), "Unknown files are left alone");
+    }
+
+    #[test]
+    fn test_verify_backup() {
+        let temp_dir = TempDir::new().expect("Failed to create temp dir");
+        let backup_dir = temp_dir.path().join("backups");
+
+        let backup = Backup {
+            backup_dir: backup_dir.clone(),
+            backup_empty: false,
+            retention: RetentionPolicy::new(10),
+        };
+
+        let (kgc_file, db, checksum_file) = create_test_files(&temp_dir);
+
+        backup.create_new_backup(&kgc_file, &db, &checksum_file, &test_key()).unwrap();
+        let created = backup.list_backups().unwrap().pop().unwrap();
+        assert!(backup.verify_backup(&created, &TEST_KEY).is_ok(), "Fresh backup should verify");
+        assert!(backup.verify_backup(&created, &[1u8; 32]).is_err(), "Wrong key should fail");
+
+        let mut raw = fs::read(&created.path).unwrap();
+        let last = raw.len() - 1;
+        raw[last] ^= 0xff;
+        fs::write(&created.path, raw).unwrap();
+        assert!(backup.verify_backup(&created, &TEST_KEY).is_err(), "Tampered backup should fail");
+    }
+
+    #[test]
+    fn test_check_files_rejects_a_broken_database() {
+        let temp_dir = TempDir::new().expect("Failed to create temp dir");
+        let backup = Backup {
+            backup_dir: temp_dir.path().join("backups"),
+            backup_empty: false,
+            retention: RetentionPolicy::new(10),
+        };
+        let config = b"salt = \"x\"".to_vec();
+        let checksum = hex::encode(Sha256::digest(&config)).into_bytes();
+        let files = |database: Vec<u8>| {
+            vec![
+                (".kofl".to_string(), config.clone()),
+                ("kofl.sqlite".to_string(), database),
+                (".kofl.checksum".to_string(), checksum.clone()),
+            ]
+        };
+
+        let snapshot = Backup::snapshot_database(&Connection::open_in_memory().unwrap()).unwrap();
+        assert!(backup.check_files("fresh", &files(snapshot)).is_ok());
+        assert!(backup.check_files("garbage", &files(b"not a database".to_vec())).is_err());
+        assert!(backup.check_files("empty", &files(Vec::new())).is_err());
+    }
+
+    #[test]
+    fn test_archive_key_can_be_derived_from_header() {
+        let temp_dir = TempDir::new().expect("Failed to create temp dir");
+        let backup = Backup {
+            backup_dir: temp_dir.path().join("backups"),
+            backup_empty: false,
+            retention: RetentionPolicy::new(10),
+        };
+        let (kgc_file, db, checksum_file) = create_test_files(&temp_dir);
+
+        let master_key = ArchiveKey {
+            key: Sha256::digest(b"master-pwdvault-salt").to_vec(),
+            source: KeySource::Master,
+            salt: "vault-salt".to_string(),
+            kdf_iterations: 0,
+            keyfile: false,
+        };
+        let passphrase_key = ArchiveKey {
+            key: derive_passphrase_key("backup passphrase", "backup-salt", 1000).to_vec(),
+            source: KeySource::Passphrase,
+            salt: "backup-salt".to_string(),
+            kdf_iterations: 1000,
+            keyfile: false,
+        };
+
+        for (key, secret) in [(master_key, "master-pwd"), (passphrase_key, "backup passphrase")] {
+            let path = backup.create_new_backup(&kgc_file, &db, &checksum_file, &key).unwrap();
+            let header = Backup::read_header(&path).unwrap();
+            assert_eq!(header.key_source, key.source);
+
+            // what `kofl backup restore` does on a fresh machine
+            let derived = ArchiveKey::derive(&header, secret);
+            assert_eq!(derived, key.key);
+            assert!(Backup::read_archive(&path, &derived).is_ok());
+
+            let wrong = ArchiveKey::derive(&header, "wrong secret");
+            assert!(Backup::read_archive(&path, &wrong).is_err());
+
+            fs::remove_file(&path).unwrap();
+        }
+    }
+
     #[ignore]
     #[test]
     fn test_create_new_backup_permissions() {
@@ -345,12 +1093,13 @@ mod tests {
             let backup = Backup {
                 backup_dir: backup_dir.clone(),
                 backup_empty: true,
+                retention: RetentionPolicy::new(10),
             };
 
-            let (kgc_file, db_file, checksum_file) = create_test_files(&temp_dir);
+            let (kgc_file, db, checksum_file) = create_test_files(&temp_dir);
 
             // Act
-            let result = backup.create_new_backup(&kgc_file, &db_file, &checksum_file);
+            let result = backup.create_new_backup(&kgc_file, &db, &checksum_file, &test_key());
 
             // Assert
             assert!(
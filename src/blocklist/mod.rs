@@ -0,0 +1,201 @@
+//! Blocklist of common passwords and dictionary words.
+//!
+//! Both lists are compiled into FST sets by `build.rs` and embedded in the
+//! binary. `data/common-passwords.txt` and the strength meter's lists are
+//! hand-curated, `data/english-words.txt` is the lowercase part of the English
+//! word list shipped with the `hashers` crate (MIT OR Apache-2.0).
+//!
+//! A password is rejected if, once its trivial decorations are stripped
+//! (case, leading/trailing digits and symbols, l33t substitutions, reversal,
+//! doubling), what is left is a common password or a dictionary word.
+
+use fst::Set;
+use std::collections::BTreeSet;
+use std::fmt;
+use std::path::Path;
+use std::sync::OnceLock;
+
+static COMMON_PASSWORDS: &[u8] = include_bytes!(concat!(env!("OUT_DIR"), "/common-passwords.fst"));
+static ENGLISH_WORDS: &[u8] = include_bytes!(concat!(env!("OUT_DIR"), "/english-words.fst"));
+
+// dictionary words shorter than this are too common inside strong passwords to block
+const MIN_WORD_LENGTH: usize = 4;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum BlocklistHit {
+    CommonPassword(String),
+    DictionaryWord(String),
+    UserBlocklist(String),
+}
+
+impl fmt::Display for BlocklistHit {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BlocklistHit::CommonPassword(word) => {
+                write!(f, "the password is a variation of the common password '{}'", word)
+            }
+            BlocklistHit::DictionaryWord(word) => {
+                write!(f, "the password is the dictionary word '{}' with trivial changes", word)
+            }
+            BlocklistHit::UserBlocklist(word) => {
+                write!(f, "the password matches '{}' from your blocklist file", word)
+            }
+        }
+    }
+}
+
+pub struct Blocklist {
+    user: Option<Set<Vec<u8>>>,
+}
+
+fn embedded(bytes: &'static [u8], cell: &'static OnceLock<Set<&'static [u8]>>) -> &'static Set<&'static [u8]> {
+    cell.get_or_init(|| Set::new(bytes).expect("embedded blocklist is a valid FST"))
+}
+
+fn common_passwords() -> &'static Set<&'static [u8]> {
+    static SET: OnceLock<Set<&'static [u8]>> = OnceLock::new();
+    embedded(COMMON_PASSWORDS, &SET)
+}
+
+fn english_words() -> &'static Set<&'static [u8]> {
+    static SET: OnceLock<Set<&'static [u8]>> = OnceLock::new();
+    embedded(ENGLISH_WORDS, &SET)
+}
+
+impl Blocklist {
+    /// The embedded lists only.
+    pub fn new() -> Self {
+        Blocklist { user: None }
+    }
+
+    /// The embedded lists plus one password per line from `path`.
+    pub fn with_user_file(path: &Path) -> std::io::Result<Self> {
+        let content = std::fs::read_to_string(path)?;
+        let words: BTreeSet<String> = content
+            .lines()
+            .map(|line| line.trim().to_lowercase())
+            .filter(|line| !line.is_empty() && !line.starts_with('#'))
+            .collect();
+        let set = Set::from_iter(words)
+            .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))?;
+        Ok(Blocklist { user: Some(set) })
+    }
+
+    /// Loads the user file configured in `blocklist_file`, if any.
+    pub fn from_setting(blocklist_file: Option<String>) -> std::io::Result<Self> {
+        match blocklist_file {
+            Some(path) if !path.is_empty() => Blocklist::with_user_file(Path::new(&path)),
+            _ => Ok(Blocklist::new()),
+        }
+    }
+
+    pub fn check(&self, password: &str) -> Option<BlocklistHit> {
+        for candidate in candidates(password) {
+            if let Some(user) = &self.user {
+                if user.contains(&candidate) {
+                    return Some(BlocklistHit::UserBlocklist(candidate));
+                }
+            }
+            if common_passwords().contains(&candidate) {
+                return Some(BlocklistHit::CommonPassword(candidate));
+            }
+            if candidate.chars().count() >= MIN_WORD_LENGTH && english_words().contains(&candidate) {
+                return Some(BlocklistHit::DictionaryWord(candidate));
+            }
+        }
+        None
+    }
+}
+
+fn unleet_char(c: char) -> char {
+    match c {
+        '4' | '@' => 'a',
+        '8' => 'b',
+        '(' => 'c',
+        '3' => 'e',
+        '6' | '9' => 'g',
+        '1' | '!' | '|' => 'i',
+        '0' => 'o',
+        '$' | '5' => 's',
+        '7' | '+' => 't',
+        '2' => 'z',
+        _ => c,
+    }
+}
+
+// `xx` for `xxxx`, when the password is one word typed twice
+fn undouble(word: &str) -> Option<String> {
+    let chars: Vec<char> = word.chars().collect();
+    let half = chars.len() / 2;
+    if chars.len() % 2 == 0 && half > 0 && chars[..half] == chars[half..] {
+        Some(chars[..half].iter().collect())
+    } else {
+        None
+    }
+}
+
+/// The password with each combination of trivial decorations removed, most literal first.
+fn candidates(password: &str) -> Vec<String> {
+    let lowered = password.to_lowercase();
+    let stripped = lowered
+        .trim_matches(|c: char| !c.is_alphabetic())
+        .to_string();
+    // keep l33t characters inside the word, only strip the outer decorations
+    let unleeted: String = lowered
+        .chars()
+        .map(unleet_char)
+        .collect::<String>()
+        .trim_matches(|c: char| !c.is_alphabetic())
+        .to_string();
+    let inner_unleeted: String = stripped.chars().map(unleet_char).collect();
+
+    let mut candidates = vec![lowered.clone(), stripped.clone(), unleeted, inner_unleeted];
+    let reversed: Vec<String> = candidates.iter().map(|c| c.chars().rev().collect()).collect();
+    candidates.extend(reversed);
+    let undoubled: Vec<String> = candidates.iter().filter_map(|c| undouble(c)).collect();
+    candidates.extend(undoubled);
+
+    let mut seen = BTreeSet::new();
+    candidates.retain(|c| !c.is_empty() && seen.insert(c.clone()));
+    candidates
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    #[test]
+    fn test_common_passwords_are_blocked_with_decorations() {
+        let blocklist = Blocklist::new();
+        assert_eq!(blocklist.check("123456"), Some(BlocklistHit::CommonPassword("123456".to_string())));
+        assert_eq!(blocklist.check("Password1!"), Some(BlocklistHit::CommonPassword("password".to_string())));
+        assert_eq!(blocklist.check("P@$$w0rd"), Some(BlocklistHit::CommonPassword("password".to_string())));
+        assert_eq!(blocklist.check("yeknom"), Some(BlocklistHit::CommonPassword("monkey".to_string())));
+    }
+
+    #[test]
+    fn test_dictionary_words_are_blocked_with_decorations() {
+        let blocklist = Blocklist::new();
+        assert!(matches!(blocklist.check("Elephant2024!"), Some(BlocklistHit::DictionaryWord(_))));
+        assert!(matches!(blocklist.check("umbrellaumbrella"), Some(BlocklistHit::DictionaryWord(_))));
+    }
+
+    #[test]
+    fn test_strong_passwords_pass() {
+        let blocklist = Blocklist::new();
+        assert_eq!(blocklist.check("xK#9vQ!m2Rp$L7wZ"), None);
+        assert_eq!(blocklist.check("Tangerine%Wobble7Sofa"), None);
+    }
+
+    #[test]
+    fn test_user_blocklist_file() {
+        let mut file = tempfile::NamedTempFile::new().unwrap();
+        writeln!(file, "# company words").unwrap();
+        writeln!(file, "Acmecorp").unwrap();
+
+        let blocklist = Blocklist::with_user_file(file.path()).unwrap();
+        assert_eq!(blocklist.check("acmecorp2024!"), Some(BlocklistHit::UserBlocklist("acmecorp".to_string())));
+        assert_eq!(Blocklist::new().check("acmecorp2024!"), None);
+    }
+}
@@ -0,0 +1,68 @@
+//! Encryption of the entry passwords with the vault key.
+
+use aes::cipher::{generic_array::GenericArray, KeyIvInit, StreamCipher};
+use aes_gcm::aead::{Aead, KeyInit};
+use aes_gcm::{Aes256Gcm, Nonce};
+use ctr::Ctr32BE;
+use rand::RngCore;
+
+type Aes256Ctr = Ctr32BE<aes::Aes256>;
+
+fn key(master_key_hex: &str) -> Result<Vec<u8>, String> {
+    let key = hex::decode(master_key_hex).map_err(|e| format!("Error decoding master key hash: {}", e))?;
+    if key.len() != 32 {
+        return Err("The master key hash is not a 256-bit key".to_string());
+    }
+    Ok(key)
+}
+
+fn apply(master_key_hex: &str, data: &mut [u8]) -> Result<(), String> {
+    let key = key(master_key_hex)?;
+    // must match the nonce used by AddCmd and UpdateCmd
+    let nonce = GenericArray::from_slice(&[0u8; 16]);
+    let mut cipher = Aes256Ctr::new(GenericArray::from_slice(&key), nonce);
+    cipher.apply_keystream(data);
+    Ok(())
+}
+
+/// Decrypts the hex `password_hash` column of an entry.
+pub fn decrypt_password(master_key_hex: &str, password_hash: &str) -> Result<String, String> {
+    let mut data = hex::decode(password_hash).map_err(|_| "Error decoding password hash".to_string())?;
+    apply(master_key_hex, &mut data)?;
+    String::from_utf8(data).map_err(|_| "Error decrypting password".to_string())
+}
+
+/// Encrypts `password` into the hex form stored in the `password_hash` column.
+pub fn encrypt_password(master_key_hex: &str, password: &str) -> Result<String, String> {
+    let mut data = password.as_bytes().to_vec();
+    apply(master_key_hex, &mut data)?;
+    Ok(hex::encode(data))
+}
+
+/// Encrypts `secret` with AES-256-GCM and a random nonce, returning hex of nonce || ciphertext.
+///
+/// Used for data added after the password column, which cannot change its
+/// scheme without re-encrypting every vault.
+pub fn seal(master_key_hex: &str, secret: &str) -> Result<String, String> {
+    let cipher = Aes256Gcm::new_from_slice(&key(master_key_hex)?).map_err(|e| e.to_string())?;
+    let mut nonce = [0u8; 12];
+    rand::thread_rng().fill_bytes(&mut nonce);
+    let ciphertext = cipher
+        .encrypt(Nonce::from_slice(&nonce), secret.as_bytes())
+        .map_err(|_| "Error encrypting secret".to_string())?;
+    Ok(hex::encode([nonce.to_vec(), ciphertext].concat()))
+}
+
+/// Reverses `seal`, failing when the data was tampered with or the key is wrong.
+pub fn open(master_key_hex: &str, sealed: &str) -> Result<String, String> {
+    let data = hex::decode(sealed).map_err(|_| "Error decoding sealed secret".to_string())?;
+    if data.len() < 12 {
+        return Err("The sealed secret is too short".to_string());
+    }
+    let (nonce, ciphertext) = data.split_at(12);
+    let cipher = Aes256Gcm::new_from_slice(&key(master_key_hex)?).map_err(|e| e.to_string())?;
+    let plaintext = cipher
+        .decrypt(Nonce::from_slice(nonce), ciphertext)
+        .map_err(|_| "Error decrypting secret".to_string())?;
+    String::from_utf8(plaintext).map_err(|_| "Error decrypting secret".to_string())
+}
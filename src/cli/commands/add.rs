@@ -1,11 +1,13 @@
 use crate::backup::Backup;
+use crate::clipboard;
 use crate::cli::Command;
-use crate::validator::core::{ValidationType, ValidationResult};
+use crate::validator::core::{Sensitive, Sensitivity, ValidationType, ValidationResult};
 use crate::validator::registry::ValidationRegistry;
 use std::fmt;
 use crate::errors::{ErrorExecution, ErrorValidation};
 use crate::context::Context;
 use crate::db::Db::Entry;
+use crate::generator::GeneratorRules;
 use chrono::prelude::*;
 use log::{debug, info, warn, error};
 use sha2::Digest;
@@ -23,19 +25,47 @@ pub struct AddCmd {
     pub password: String,
     pub suggest_flag: bool,
     pub suggested_pwd : Cell<String>,
+    pub generator_rules: GeneratorRules,
+    pub save_rules: bool,
+    pub username: Option<String>,
+    pub url: Option<String>,
+    // print the generated password instead of copying it
+    pub print: bool,
 }
 
 
 impl  AddCmd {
-    pub fn new(name: String, password: String, suggest_flag: bool) -> AddCmd
+    pub fn new(name: String, password: String, suggest_flag: bool, generator_rules: GeneratorRules, save_rules: bool, username: Option<String>, url: Option<String>, print: bool) -> AddCmd
     {
         AddCmd {
             name,
             password,
             suggest_flag,
             suggested_pwd: Cell::new(String::new()),
+            generator_rules,
+            save_rules,
+            username,
+            url,
+            print,
         }
     }
+
+    /// The password to store, the generated one once validation suggested it.
+    pub fn new_password(&self) -> String {
+        if !self.suggest_flag {
+            return self.password.clone();
+        }
+        let suggested = self.suggested_pwd.take();
+        self.suggested_pwd.set(suggested.clone());
+        suggested
+    }
+}
+
+impl Sensitive for AddCmd {
+    // a printed password ends up in the terminal scrollback
+    fn sensitivity(&self) -> Sensitivity {
+        if self.suggest_flag && self.print { Sensitivity::Sensitive } else { Sensitivity::Normal }
+    }
 }
 
 impl PartialEq for AddCmd {
@@ -58,9 +88,12 @@ impl fmt::Debug for AddCmd {
 
 impl Command for AddCmd {
     fn execute(&self, context: &Context) -> bool  {
-        let master_key_hash = {
-            let kgc = context.kgc.borrow();
-            kgc.get_hashed_pwd()
+        let master_key_hash = match context.vault_key() {
+            Ok(key) => key,
+            Err(e) => {
+                error!("{}", e);
+                return false;
+            }
         };
 
         let master_key_bytes = match hex::decode(&master_key_hash) {
@@ -82,7 +115,7 @@ impl Command for AddCmd {
         let mut encrypted_password;
 
         if (self.suggest_flag) {
-            encrypted_password = self.suggested_pwd.take().into_bytes();
+            encrypted_password = self.new_password().into_bytes();
 
         }else {
             encrypted_password = self.password.clone().into_bytes();
@@ -98,7 +131,9 @@ impl Command for AddCmd {
             id: 0, // will be ignored by sqlite
             ent_name: self.name.clone(),
             password_hash: encrypted_password_hex,
-            timestamp: Utc::now().to_rfc3339()
+            timestamp: Utc::now().to_rfc3339(),
+            username: self.username.clone(),
+            url: self.url.clone(),
         };
 
         // Add the entry to the database if error return false
@@ -113,12 +148,25 @@ impl Command for AddCmd {
             }
         }
 
+        if self.save_rules {
+            if let Err(e) = context.db.set_generator_rules(&self.name, Some(&self.generator_rules.to_toml())) {
+                error!("Error saving generator rules: {}", e);
+                return false;
+            }
+            info!("Generator rules saved for {}", self.name);
+        }
 
-        let bc = Backup::new().unwrap();
+        Backup::after_write(context);
 
-        bc.create_new_backup(&context.kgc.borrow().get_config_path(), 
-        &context.kgc.borrow().get_data_storage_path(), 
-        &context.kgc.borrow().get_config_path().with_extension("checksum")).unwrap();
+        if self.suggest_flag {
+            let suggested = self.suggested_pwd.take();
+            if self.print {
+                println!("Generated password: {}", suggested);
+            } else if let Err(e) = clipboard::copy_with_settings(&context.kgc.borrow().get_settings(), &suggested) {
+                error!("{}, use `kofl get {} --print` to show the generated password", e, self.name);
+                return false;
+            }
+        }
 
         true
     }
@@ -131,7 +179,10 @@ impl Command for AddCmd {
             ValidationType::MasterKeyCheck,
             ValidationType::SessionCheck,
             ValidationType::EntryExistsCheck,
+            ValidationType::ReauthCheck,
             ValidationType::PasswordRequirementCheck,
+            ValidationType::PasswordStrengthCheck,
+            ValidationType::BreachCheck,
         ];
 
 
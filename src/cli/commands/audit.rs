@@ -0,0 +1,274 @@
+use crate::audit::{AuditItem, AuditReport};
+use crate::cipher;
+use crate::cli::Command;
+use crate::context::Context;
+use crate::hibp::PwnedPasswords;
+use crate::policy::PasswordPolicy;
+use crate::strength;
+use crate::validator::core::{ValidationResult, ValidationType};
+use crate::validator::registry::ValidationRegistry;
+use chrono::{DateTime, Utc};
+use log::{debug, error, info, warn};
+use std::cell::Cell;
+use std::path::PathBuf;
+
+pub enum AuditAction {
+    // max_age_days overrides the max_age_days of the policy of each entry
+    Report { json: bool, max_age_days: Option<usize>, min_health: u32 },
+    Breaches { hibp_file: Option<PathBuf> },
+}
+
+pub struct AuditCmd {
+    pub action: AuditAction,
+    // false until the audit ran and found the vault within the thresholds
+    passed: Cell<bool>,
+}
+
+impl AuditCmd {
+    pub fn new(action: AuditAction) -> Self {
+        AuditCmd { action, passed: Cell::new(false) }
+    }
+
+    /// Whether the caller should exit with success, for scheduled jobs.
+    pub fn passed(&self) -> bool {
+        self.passed.get()
+    }
+
+    fn report(&self, json: bool, max_age_days: Option<usize>, min_health: u32, context: &Context) -> bool {
+        let master_key_hash = match context.vault_key() {
+            Ok(key) => key,
+            Err(e) => {
+                error!("{}", e);
+                return false;
+            }
+        };
+        let (settings, username) = {
+            let kgc = context.kgc.borrow();
+            (kgc.get_settings(), kgc.get_user_login())
+        };
+        let min_score = settings.min_password_score().unwrap_or(2);
+
+        let mut hibp = match PwnedPasswords::from_setting(settings.hibp_file()) {
+            Ok(hibp) => hibp,
+            Err(e) => {
+                warn!("Breach check skipped, could not open the HIBP file: {}", e);
+                None
+            }
+        };
+
+        let entries = match context.db.list_entries() {
+            Ok(entries) => entries,
+            Err(e) => {
+                error!("Error listing entries: {}", e);
+                return false;
+            }
+        };
+
+        let mut items = Vec::new();
+        for entry in entries {
+            let password = match cipher::decrypt_password(&master_key_hash, &entry.password_hash) {
+                Ok(password) => password,
+                Err(e) => {
+                    error!("Could not decrypt {}: {}", entry.ent_name, e);
+                    return false;
+                }
+            };
+
+            let policy = PasswordPolicy::for_entry(&context.kgc.borrow(), &entry.ent_name);
+            let mut weaknesses: Vec<String> = policy.check(&password).iter().map(|v| v.to_string()).collect();
+            let estimate = strength::estimate(&password, &[&username, &entry.ent_name]);
+            if (estimate.score as u32) < min_score {
+                weaknesses.push(format!("strength score {} is below the minimum of {}", estimate.score, min_score));
+            }
+
+            let breach_count = match hibp.as_mut().map(|hibp| hibp.count(&password)) {
+                Some(Ok(count)) => count,
+                Some(Err(e)) => {
+                    error!("Error reading the HIBP file: {}", e);
+                    return false;
+                }
+                None => 0,
+            };
+
+            items.push(AuditItem {
+                name: entry.ent_name,
+                password,
+                updated_at: DateTime::parse_from_rfc3339(&entry.timestamp).ok().map(|t| t.with_timezone(&Utc)),
+                username: entry.username,
+                url: entry.url,
+                weaknesses,
+                breach_count,
+                // the same limit `kofl list --expiring` uses, see `rotation::expiries`
+                max_age_days: max_age_days.unwrap_or(policy.max_age_days),
+            });
+        }
+
+        let report = AuditReport::build(&items, max_age_days, hibp.is_some(), Utc::now());
+        if json {
+            match serde_json::to_string_pretty(&report) {
+                Ok(out) => println!("{}", out),
+                Err(e) => {
+                    error!("Error serializing the report: {}", e);
+                    return false;
+                }
+            }
+        } else {
+            AuditCmd::print_report(&report);
+        }
+
+        if report.health_score < min_health {
+            warn!("Health score {} is below the threshold of {}", report.health_score, min_health);
+        } else {
+            self.passed.set(true);
+        }
+        true
+    }
+
+    fn print_report(report: &AuditReport) {
+        println!("Vault health: {}/100 ({} entries)", report.health_score, report.entries);
+
+        if !report.reused.is_empty() {
+            println!("\nReused passwords:");
+            for group in &report.reused {
+                println!("  {}", group.join(", "));
+            }
+        }
+        if let Some(breached) = report.breached.as_ref().filter(|b| !b.is_empty()) {
+            println!("\nFound in known breaches:");
+            for entry in breached {
+                println!("  {}  seen {} times", entry.name, entry.count);
+            }
+        }
+        if !report.weak.is_empty() {
+            println!("\nWeak passwords:");
+            for entry in &report.weak {
+                println!("  {}: {}", entry.name, entry.reasons.join(", "));
+            }
+        }
+        if !report.old.is_empty() {
+            println!("\nNot changed within their maximum age:");
+            for entry in &report.old {
+                match entry.age_days {
+                    Some(days) => println!("  {}  {} days old, max {}", entry.name, days, entry.max_age_days),
+                    None => println!("  {}  unknown age, max {}", entry.name, entry.max_age_days),
+                }
+            }
+        }
+        if !report.missing_details.is_empty() {
+            println!("\nMissing details:");
+            for entry in &report.missing_details {
+                println!("  {}: no {}", entry.name, entry.missing.join(" or "));
+            }
+        }
+    }
+
+    fn breaches(&self, hibp_file: &Option<PathBuf>, context: &Context) -> bool {
+        let master_key_hash = match context.vault_key() {
+            Ok(key) => key,
+            Err(e) => {
+                error!("{}", e);
+                return false;
+            }
+        };
+        let configured = context.kgc.borrow().get_settings().hibp_file();
+
+        // the flag wins over the hibp_file setting
+        let opened = match hibp_file {
+            Some(path) => PwnedPasswords::open(path).map(Some),
+            None => PwnedPasswords::from_setting(configured),
+        };
+        let mut hibp = match opened {
+            Ok(Some(hibp)) => hibp,
+            Ok(None) => {
+                error!("No HIBP file given, use --hibp-file or `kofl config set hibp_file <path>`");
+                return false;
+            }
+            Err(e) => {
+                error!("Could not open the HIBP file: {}", e);
+                return false;
+            }
+        };
+
+        let entries = match context.db.list_entries() {
+            Ok(entries) => entries,
+            Err(e) => {
+                error!("Error listing entries: {}", e);
+                return false;
+            }
+        };
+
+        let mut compromised = Vec::new();
+        for entry in &entries {
+            let password = match cipher::decrypt_password(&master_key_hash, &entry.password_hash) {
+                Ok(password) => password,
+                Err(e) => {
+                    warn!("Skipping {}: {}", entry.ent_name, e);
+                    continue;
+                }
+            };
+            match hibp.count(&password) {
+                Ok(0) => {}
+                Ok(count) => compromised.push((entry.ent_name.clone(), count)),
+                Err(e) => {
+                    error!("Error reading the HIBP file: {}", e);
+                    return false;
+                }
+            }
+        }
+
+        if compromised.is_empty() {
+            info!("None of the {} entries appear in known breaches", entries.len());
+            self.passed.set(true);
+            return true;
+        }
+
+        compromised.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+        for (name, count) in &compromised {
+            println!("{}  seen {} times", name, count);
+        }
+        warn!(
+            "{} of {} entries appear in known breaches, change them with `kofl update <name>`",
+            compromised.len(),
+            entries.len()
+        );
+        true
+    }
+}
+
+impl Command for AuditCmd {
+    fn execute(&self, context: &Context) -> bool {
+        match &self.action {
+            AuditAction::Report { json, max_age_days, min_health } => {
+                self.report(*json, *max_age_days, *min_health, context)
+            }
+            AuditAction::Breaches { hibp_file } => self.breaches(hibp_file, context),
+        }
+    }
+
+    fn validate(&self, context: &Context) -> bool {
+        let val_reg = ValidationRegistry::<AuditCmd>::new();
+
+        let val_checks = vec![
+            ValidationType::MasterKeyCheck,
+            ValidationType::SessionCheck,
+        ];
+
+        for a_check in val_checks {
+            match val_reg.validators.get(&a_check).unwrap().validate(context, &self) {
+                ValidationResult::Failure(msg) => {
+                    error!("{msg}");
+                    return false
+                },
+                ValidationResult::Warning(msg) => warn!("{msg}"),
+                ValidationResult::Success => debug!("test passed ✅")
+            }
+        }
+
+        true
+    }
+
+    fn display(&self) {
+        debug!("Audit command");
+        ()
+    }
+}
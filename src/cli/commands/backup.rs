@@ -0,0 +1,364 @@
+use crate::backup::{new_backup_key, ArchiveKey, Backup, BackupInfo, KeySource};
+use crate::cli::Command;
+use crate::config::Config::KoflGlobalConfig;
+use crate::context::Context;
+use crate::kdf;
+use crate::keyfile;
+use crate::layout;
+use crate::validator::core::{Sensitive, Sensitivity, ValidationResult, ValidationType};
+use crate::validator::registry::ValidationRegistry;
+use log::{debug, error, info, warn};
+use rusqlite::{Connection, DatabaseName};
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+pub enum BackupAction {
+    List,
+    Verify(Option<String>),
+    Prune,
+    Restore { name: Option<String>, file: Option<PathBuf> },
+    Passphrase { remove: bool },
+}
+
+pub struct BackupCmd {
+    pub action: BackupAction,
+}
+
+impl BackupCmd {
+    pub fn new(action: BackupAction) -> Self {
+        BackupCmd { action }
+    }
+
+    fn open_backup(context: &Context) -> Option<Backup> {
+        match Backup::from_settings(&context.kgc.borrow().get_settings()) {
+            Ok(bc) => Some(bc),
+            Err(e) => {
+                error!("Error accessing backup directory: {}", e);
+                None
+            }
+        }
+    }
+
+    fn list(&self, bc: &Backup) -> bool {
+        let backups = match bc.list_backups() {
+            Ok(backups) => backups,
+            Err(e) => {
+                error!("Error listing backups: {}", e);
+                return false;
+            }
+        };
+
+        if backups.is_empty() {
+            info!("No backups found in {}", bc.get_backup_dir().display());
+            return true;
+        }
+
+        for backup in &backups {
+            let encryption = if backup.path.is_dir() {
+                String::from("plaintext")
+            } else {
+                match Backup::read_header(&backup.path) {
+                    Ok(header) => header.key_source.to_string(),
+                    Err(_) => String::from("unreadable"),
+                }
+            };
+            println!(
+                "{}  {}  {} bytes  {}",
+                backup.name,
+                backup.created_at.format("%Y-%m-%d %H:%M:%S"),
+                backup.size,
+                encryption
+            );
+        }
+        info!("{} backup(s) in {}", backups.len(), bc.get_backup_dir().display());
+        true
+    }
+
+    fn verify(&self, bc: &Backup, name: &Option<String>, context: &Context) -> bool {
+        let backups = match bc.list_backups() {
+            Ok(backups) => backups,
+            Err(e) => {
+                error!("Error listing backups: {}", e);
+                return false;
+            }
+        };
+
+        let targets: Vec<_> = match name {
+            Some(name) => backups.into_iter().filter(|b| &b.name == name).collect(),
+            None => backups,
+        };
+
+        if targets.is_empty() {
+            error!("No matching backup found");
+            return false;
+        }
+
+        let mut all_ok = true;
+        for backup in &targets {
+            let result = BackupCmd::local_key(backup, context)
+                .and_then(|key| bc.verify_backup(backup, &key));
+            match result {
+                Ok(_) => info!("{} ✅", backup.name),
+                Err(msg) => {
+                    error!("{} ⛔ {}", backup.name, msg);
+                    all_ok = false;
+                }
+            }
+        }
+        all_ok
+    }
+
+    // key of an archive as known by the local config, legacy directories need none
+    fn local_key(backup: &BackupInfo, context: &Context) -> Result<Vec<u8>, String> {
+        if backup.path.is_dir() {
+            return Ok(Vec::new());
+        }
+        let header = Backup::read_header(&backup.path).map_err(|e| e.to_string())?;
+        ArchiveKey::for_header(&header, &context.kgc.borrow(), &context.vault_key()?)
+    }
+
+    fn prune(&self, bc: &Backup) -> bool {
+        match bc.prune() {
+            Ok(removed) => {
+                info!("Pruned {} backup(s)", removed.len());
+                true
+            }
+            Err(e) => {
+                error!("Error pruning backups: {}", e);
+                false
+            }
+        }
+    }
+
+    fn find_archive(bc: &Backup, name: &Option<String>, file: &Option<PathBuf>) -> Option<PathBuf> {
+        if let Some(file) = file {
+            return Some(file.clone());
+        }
+
+        let archives: Vec<BackupInfo> = match bc.list_backups() {
+            Ok(backups) => backups.into_iter().filter(|b| b.path.is_file()).collect(),
+            Err(e) => {
+                error!("Error listing backups: {}", e);
+                return None;
+            }
+        };
+
+        match name {
+            Some(name) => archives.into_iter().find(|b| &b.name == name).map(|b| b.path),
+            None => archives.last().map(|b| b.path.clone()),
+        }
+    }
+
+    fn restore(&self, bc: &Backup, name: &Option<String>, file: &Option<PathBuf>, context: &Context) -> bool {
+        let archive_path = match BackupCmd::find_archive(bc, name, file) {
+            Some(path) => path,
+            None => {
+                error!("No matching backup archive found");
+                return false;
+            }
+        };
+
+        if context.kgc.borrow().is_master_key_provided() {
+            warn!("Restoring will replace the current vault with {}", archive_path.display());
+            print!("Type 'yes' to continue ===> ");
+            let _ = std::io::stdout().flush();
+            let mut answer = String::new();
+            if std::io::stdin().read_line(&mut answer).is_err() || answer.trim() != "yes" {
+                info!("Restore aborted");
+                return false;
+            }
+        }
+
+        let mut header = match Backup::read_header(&archive_path) {
+            Ok(header) => header,
+            Err(e) => {
+                error!("Error reading backup archive: {}", e);
+                return false;
+            }
+        };
+        if header.salt.is_empty() {
+            // archives written before the salt was recorded in the header
+            header.salt = context.kgc.borrow().get_salt();
+        }
+
+        let mut secret = rpassword::prompt_password(format!("Enter the {} of this backup ===> ", header.key_source)).unwrap();
+        if header.keyfile && header.key_source == KeySource::Master {
+            print!("Path of the key file of this backup ===> ");
+            let _ = std::io::stdout().flush();
+            let mut path = String::new();
+            let _ = std::io::stdin().read_line(&mut path);
+            match keyfile::digest(Path::new(path.trim())) {
+                Ok(digest) => secret = kdf::with_keyfile(&secret, Some(&digest)),
+                Err(e) => {
+                    error!("{}", e);
+                    return false;
+                }
+            }
+        }
+        let key = ArchiveKey::derive(&header, &secret);
+
+        let files = match Backup::read_archive(&archive_path, &key) {
+            Ok(files) => files,
+            Err(e) => {
+                error!("Could not open backup: {}", e);
+                return false;
+            }
+        };
+
+        if let Err(msg) = bc.check_files("restore", &files) {
+            error!("Backup is not usable: {}", msg);
+            return false;
+        }
+
+        let find = |file_name: &str| files.iter().find(|(n, _)| n == file_name).map(|(_, data)| data).unwrap();
+
+        let mut restored: KoflGlobalConfig = match std::str::from_utf8(find(".kofl"))
+            .map_err(|e| e.to_string())
+            .and_then(|content| toml::from_str(content).map_err(|e| e.to_string()))
+        {
+            Ok(config) => config,
+            Err(e) => {
+                error!("Could not read the config stored in the backup: {}", e);
+                return false;
+            }
+        };
+
+        // restore the database in place through the online backup API
+        let tmp_path = match layout::scratch_file("restore") {
+            Ok(path) => path,
+            Err(e) => {
+                error!("Error writing database snapshot: {}", e);
+                return false;
+            }
+        };
+        if let Err(e) = layout::write_private(&tmp_path, find("kofl.sqlite")) {
+            error!("Error writing database snapshot: {}", e);
+            return false;
+        }
+        let data_path = context.kgc.borrow().get_data_storage_path().clone();
+        let result = Connection::open(&data_path)
+            .and_then(|mut target| target.restore(DatabaseName::Main, &tmp_path, None::<fn(rusqlite::backup::Progress)>));
+        let _ = fs::remove_file(&tmp_path);
+        if let Err(e) = result {
+            error!("Error restoring database: {}", e);
+            return false;
+        }
+
+        // the backup may come from another machine, keep the local paths
+        {
+            let mut kgc = context.kgc.borrow_mut();
+            restored.set_config_path(kgc.get_config_path().clone());
+            restored.set_data_storage_path(kgc.get_data_storage_path().clone());
+            *kgc = restored;
+        }
+        context.kgc.borrow().update();
+        // the restored config may want another master password than the session key
+        context.ss.lock();
+
+        info!("Backup restored from {}, run login to start a new session", archive_path.display());
+        true
+    }
+
+    fn passphrase(&self, remove: bool, context: &Context) -> bool {
+        if remove {
+            let mut kgc = context.kgc.borrow_mut();
+            kgc.set_backup_key(None);
+            kgc.update();
+            info!("New backups will be encrypted with the master password");
+            return true;
+        }
+
+        let passphrase = rpassword::prompt_password("type a backup passphrase ==> ").unwrap();
+        let passphrase_confirmed = rpassword::prompt_password("type the backup passphrase again ==> ").unwrap();
+
+        if passphrase != passphrase_confirmed {
+            error!("Passphrase mismatch");
+            return false;
+        }
+
+        let vault_key = match context.vault_key().and_then(|key| hex::decode(key).map_err(|e| e.to_string())) {
+            Ok(bytes) => bytes,
+            Err(e) => {
+                error!("Error reading the vault key: {}", e);
+                return false;
+            }
+        };
+
+        match new_backup_key(&passphrase, &vault_key) {
+            Ok(backup_key) => {
+                let mut kgc = context.kgc.borrow_mut();
+                kgc.set_backup_key(Some(backup_key));
+                kgc.update();
+            }
+            Err(e) => {
+                error!("Error creating backup key: {}", e);
+                return false;
+            }
+        }
+
+        info!("New backups will be encrypted with the backup passphrase");
+        true
+    }
+}
+
+impl Sensitive for BackupCmd {
+    // restore replaces the vault, prune deletes archives and the passphrase opens them
+    fn sensitivity(&self) -> Sensitivity {
+        match self.action {
+            BackupAction::List | BackupAction::Verify(_) => Sensitivity::Normal,
+            BackupAction::Prune | BackupAction::Restore { .. } | BackupAction::Passphrase { .. } => Sensitivity::Sensitive,
+        }
+    }
+}
+
+impl Command for BackupCmd {
+    fn execute(&self, context: &Context) -> bool {
+        let bc = match BackupCmd::open_backup(context) {
+            Some(bc) => bc,
+            None => return false,
+        };
+
+        match &self.action {
+            BackupAction::List => self.list(&bc),
+            BackupAction::Verify(name) => self.verify(&bc, name, context),
+            BackupAction::Prune => self.prune(&bc),
+            BackupAction::Restore { name, file } => self.restore(&bc, name, file, context),
+            BackupAction::Passphrase { remove } => self.passphrase(*remove, context),
+        }
+    }
+
+    fn validate(&self, context: &Context) -> bool {
+        let val_reg = ValidationRegistry::<BackupCmd>::new();
+
+        let val_checks = match self.action {
+            // restoring onto a fresh machine, there is no vault to protect yet
+            BackupAction::Restore { .. } if !context.kgc.borrow().is_master_key_provided() => vec![],
+            _ => vec![
+                ValidationType::MasterKeyCheck,
+                ValidationType::SessionCheck,
+                ValidationType::ReauthCheck,
+            ],
+        };
+
+        for a_check in val_checks {
+
+            match val_reg.validators.get(&a_check).unwrap().validate(context, &self) {
+                ValidationResult::Failure(msg) => {
+                    error!("{msg}");
+                    return false;
+                },
+                ValidationResult::Warning(msg) => warn!("{msg}"),
+                ValidationResult::Success => debug!("test passed ✅")
+
+            }
+        }
+
+        true
+    }
+
+    fn display(&self) {
+        debug!("Backup Command");
+        ()
+    }
+}
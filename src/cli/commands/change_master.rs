@@ -0,0 +1,120 @@
+use crate::cli::commands::{InitCmd, LogInCmd};
+use crate::cli::Command;
+use crate::context::Context;
+use crate::kdf;
+use crate::keyfile;
+use crate::rekey;
+use crate::validator::core::{ValidationResult, ValidationType};
+use crate::validator::registry::ValidationRegistry;
+use log::{debug, error, info, warn};
+use std::path::PathBuf;
+
+/// Replaces the master password and re-encrypts the vault under the new key.
+pub struct ChangeMasterCmd {
+    // required when the vault is bound to a key file
+    pub keyfile: Option<PathBuf>,
+}
+
+impl ChangeMasterCmd {
+    pub fn new(keyfile: Option<PathBuf>) -> Self {
+        ChangeMasterCmd { keyfile }
+    }
+
+    /// Asks for a new master password and switches the vault to the key derived from it,
+    /// bound to the key file with `keyfile_digest` if any. The old password is not needed,
+    /// callers check it or another proof first.
+    pub fn set_new_password(context: &Context, keyfile_digest: Option<&str>) -> bool {
+        let (settings, username, iterations) = {
+            let kgc = context.kgc.borrow();
+            (kgc.get_settings(), kgc.get_user_login(), kgc.get_kdf_iterations())
+        };
+        let new_pwd = match InitCmd::read_master_password(&settings, &username) {
+            Some(pwd) => pwd,
+            None => return false,
+        };
+        if context.kgc.borrow().matches_vault_key(&context.kgc.borrow().hash_master_password(&new_pwd, keyfile_digest)) {
+            error!("The new master password is the same as the current one");
+            return false;
+        }
+
+        // this archive still opens with the old master password
+        if let Err(e) = rekey::backup(context) {
+            error!("Could not back up the vault before the change: {}", e);
+            return false;
+        }
+
+        let salt = rekey::new_salt();
+        let new_key = kdf::derive_master_hash(&kdf::with_keyfile(&new_pwd, keyfile_digest), &salt, iterations);
+        if let Err(e) = rekey::rekey(context, &new_key, salt, keyfile_digest.is_some()) {
+            error!("{}", e);
+            return false;
+        }
+
+        if let Err(e) = rekey::backup(context) {
+            warn!("Could not back up the re-encrypted vault: {}", e);
+        }
+        info!("Master password changed, backups made before now still need the old one");
+        true
+    }
+
+    /// Sets a new master password after the vault key was proven some other way than
+    /// the password, e.g. recovery shares. The key file binding is dropped.
+    pub fn reset_forgotten(context: &Context) -> bool {
+        info!("Access confirmed, choose a new master password");
+        if context.kgc.borrow().has_keyfile() {
+            warn!("The key file binding is removed, `kofl keyfile rotate` binds a new one");
+        }
+        if !ChangeMasterCmd::set_new_password(context, None) {
+            return false;
+        }
+
+        context.kgc.borrow_mut().reset_failed_logins();
+        context.kgc.borrow().update();
+        info!("Run `kofl login` with the new master password");
+        true
+    }
+}
+
+impl Command for ChangeMasterCmd {
+    fn execute(&self, context: &Context) -> bool {
+        if LogInCmd::verify_master_password(context, self.keyfile.as_deref()).is_none() {
+            return false;
+        }
+        // the key file stays, verify_master_password already checked it is readable
+        let keyfile_digest = match keyfile::for_vault(&context.kgc.borrow(), self.keyfile.as_deref()) {
+            Ok(digest) => digest,
+            Err(e) => {
+                error!("{}", e);
+                return false;
+            }
+        };
+        ChangeMasterCmd::set_new_password(context, keyfile_digest.as_deref())
+    }
+
+    fn validate(&self, context: &Context) -> bool {
+        let val_reg = ValidationRegistry::<ChangeMasterCmd>::new();
+
+        let val_checks = vec![
+            ValidationType::MasterKeyCheck,
+            ValidationType::RateLimitCheck,
+        ];
+
+        for a_check in val_checks {
+            match val_reg.validators.get(&a_check).unwrap().validate(context, &self) {
+                ValidationResult::Failure(msg) => {
+                    error!("{msg}");
+                    return false
+                },
+                ValidationResult::Warning(msg) => warn!("{msg}"),
+                ValidationResult::Success => debug!("test passed ✅")
+            }
+        }
+
+        true
+    }
+
+    fn display(&self) {
+        debug!("Change master command");
+        ()
+    }
+}
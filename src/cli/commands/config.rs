@@ -0,0 +1,162 @@
+use crate::cli::Command;
+use crate::context::Context;
+use crate::setting::setting_key::SettingKey;
+use crate::validator::core::{Sensitive, Sensitivity, ValidationResult, ValidationType};
+use crate::validator::registry::ValidationRegistry;
+use log::{debug, error, info, warn};
+
+pub enum ConfigAction {
+    Get(String),
+    Set(String, String),
+    List,
+    Reset(Option<String>),
+}
+
+pub struct ConfigCmd {
+    pub action: ConfigAction,
+}
+
+impl ConfigCmd {
+    pub fn new(action: ConfigAction) -> Self {
+        ConfigCmd { action }
+    }
+
+    fn parse_key(name: &str) -> Option<SettingKey> {
+        match name.parse::<SettingKey>() {
+            Ok(key) => Some(key),
+            Err(e) => {
+                let names: Vec<String> = SettingKey::ALL.iter().map(|k| k.to_string()).collect();
+                error!("{}, known settings are: {}", e, names.join(", "));
+                None
+            }
+        }
+    }
+
+    fn get(&self, name: &str, context: &Context) -> bool {
+        let key = match ConfigCmd::parse_key(name) {
+            Some(key) => key,
+            None => return false,
+        };
+        let registry = context.kgc.borrow().get_settings();
+        match registry.get(key) {
+            Some(setting) => {
+                println!("{} = {}", setting.name(), setting.get_value());
+                true
+            }
+            None => {
+                error!("Setting '{}' is not registered", key);
+                false
+            }
+        }
+    }
+
+    fn set(&self, name: &str, raw: &str, context: &Context) -> bool {
+        let key = match ConfigCmd::parse_key(name) {
+            Some(key) => key,
+            None => return false,
+        };
+
+        let mut registry = context.kgc.borrow().get_settings();
+        let value = match registry.parse(key, raw) {
+            Ok(value) => value,
+            Err(e) => {
+                error!("Invalid value for {}: {}", key, e);
+                return false;
+            }
+        };
+        if let Err(e) = registry.update(key, value.clone()) {
+            error!("Invalid value for {}: {}", key, e);
+            return false;
+        }
+
+        let mut kgc = context.kgc.borrow_mut();
+        kgc.set_setting(key, &value);
+        kgc.update();
+        info!("{} set to {}", key, value);
+        true
+    }
+
+    fn list(&self, context: &Context) -> bool {
+        let registry = context.kgc.borrow().get_settings();
+        for setting in registry.list() {
+            println!("{} = {}", setting.name(), setting.get_value());
+            println!("    {}", setting.description());
+        }
+        true
+    }
+
+    fn reset(&self, name: &Option<String>, context: &Context) -> bool {
+        let mut kgc = context.kgc.borrow_mut();
+        match name {
+            Some(name) => {
+                let key = match ConfigCmd::parse_key(name) {
+                    Some(key) => key,
+                    None => return false,
+                };
+                kgc.reset_setting(key);
+                info!("{} reset to its default value", key);
+            }
+            None => {
+                kgc.reset_all_settings();
+                info!("All settings reset to their default values");
+            }
+        }
+        kgc.update();
+        true
+    }
+}
+
+impl Sensitive for ConfigCmd {
+    // changing settings can turn protections off
+    fn sensitivity(&self) -> Sensitivity {
+        match self.action {
+            ConfigAction::Get(_) | ConfigAction::List => Sensitivity::Normal,
+            ConfigAction::Set(..) | ConfigAction::Reset(_) => Sensitivity::Sensitive,
+        }
+    }
+}
+
+impl Command for ConfigCmd {
+    fn execute(&self, context: &Context) -> bool {
+        match &self.action {
+            ConfigAction::Get(name) => self.get(name, context),
+            ConfigAction::Set(name, value) => self.set(name, value, context),
+            ConfigAction::List => self.list(context),
+            ConfigAction::Reset(name) => self.reset(name, context),
+        }
+    }
+
+    fn validate(&self, context: &Context) -> bool {
+        let val_reg = ValidationRegistry::<ConfigCmd>::new();
+
+        let val_checks = match self.action {
+            // reading settings does not require an unlocked vault
+            ConfigAction::Get(_) | ConfigAction::List => vec![],
+            _ => vec![
+                ValidationType::MasterKeyCheck,
+                ValidationType::SessionCheck,
+                ValidationType::ReauthCheck,
+            ],
+        };
+
+        for a_check in val_checks {
+
+            match val_reg.validators.get(&a_check).unwrap().validate(context, &self) {
+                ValidationResult::Failure(msg) => {
+                    error!("{msg}");
+                    return false;
+                },
+                ValidationResult::Warning(msg) => warn!("{msg}"),
+                ValidationResult::Success => debug!("test passed ✅")
+
+            }
+        }
+
+        true
+    }
+
+    fn display(&self) {
+        debug!("Config Command");
+        ()
+    }
+}
@@ -1,6 +1,7 @@
 use crate::cli::Command;
 use crate::errors::{ErrorExecution, ErrorValidation};
 use crate::context::Context;
+use crate::cli::commands::LogInCmd;
 use crate::validator::core::{ValidationResult, ValidationType};
 use crate::validator::registry::ValidationRegistry;
 use rand::{thread_rng, Rng};
@@ -9,6 +10,7 @@ use rusqlite::config;
 use sha2::{Sha256, Digest};
 use log::{debug, error, info, warn};
 use std::fs;
+use std::path::PathBuf;
 
 
 use aes::cipher::KeyIvInit;
@@ -17,42 +19,17 @@ type Aes256Ctr = Ctr32BE<aes::Aes256>;
 
 
 pub struct DestroyCmd {
-    // for now is emty 
+    // required when the vault is bound to a key file
+    pub keyfile: Option<PathBuf>,
 }
 
 impl DestroyCmd {
-    pub fn new() -> Self {
-        DestroyCmd{}
+    pub fn new(keyfile: Option<PathBuf>) -> Self {
+        DestroyCmd{ keyfile }
     }
 
-}
-
-impl Command for DestroyCmd {
-    fn execute(&self, context: &Context) -> bool  {
-        warn!(
-        "Note this is will delete all your data!!, Backup if needed
-        ");
-        
-        let master_pwd_input = rpassword::prompt_password("Enter the master password ===> ").unwrap();
-
-        let salt = context.kgc.borrow().get_salt();
-        let stored_hash = context.kgc.borrow().get_hashed_pwd();
-    
-        let mut hasher = Sha256::new();
-        hasher.update(master_pwd_input.as_bytes());
-        hasher.update(salt.as_bytes());
-        let computed_hash = hasher.finalize();
-        let computed_hash_hex = hex::encode(computed_hash);
-    
-        // println!("Computed hash: {}", computed_hash_hex);
-        // println!("Stored hash:   {}", stored_hash);
-    
-        // 4. Compare the computed hash with the stored hash.
-        if computed_hash_hex != stored_hash {
-            error!("Invalid password");
-            return false;
-        }
-
+    /// Deletes the config, database and session of the vault, callers confirm it first.
+    pub fn wipe(context: &Context) -> bool {
         // retrieve all config path and session path
 
         let binding = context.kgc.borrow();
@@ -105,12 +82,28 @@ impl Command for DestroyCmd {
         true
     }
 
+}
+
+impl Command for DestroyCmd {
+    fn execute(&self, context: &Context) -> bool  {
+        warn!(
+        "Note this is will delete all your data!!, Backup if needed
+        ");
+        
+        if LogInCmd::verify_master_password(context, self.keyfile.as_deref()).is_none() {
+            return false;
+        }
+
+        DestroyCmd::wipe(context)
+    }
+
     fn validate(&self, context: &Context) -> bool  {
         
         let val_reg = ValidationRegistry::<DestroyCmd>::new();
 
         let val_checks = vec![
             ValidationType::MasterKeyCheck,
+            ValidationType::RateLimitCheck,
             ValidationType::SessionCheck,
         ];
 
@@ -0,0 +1,57 @@
+use crate::cli::Command;
+use crate::context::Context;
+use crate::permissions;
+use crate::vault;
+use log::{debug, error, info, warn};
+
+/// Reports kofl files other users can reach, and makes them private with `--fix-permissions`.
+pub struct DoctorCmd {
+    pub fix_permissions: bool,
+}
+
+impl DoctorCmd {
+    pub fn new(fix_permissions: bool) -> Self {
+        DoctorCmd { fix_permissions }
+    }
+}
+
+impl Command for DoctorCmd {
+    fn execute(&self, context: &Context) -> bool {
+        let backup_dir = permissions::backup_dir(&context.kgc.borrow().get_settings());
+        let findings = permissions::inspect(&permissions::kofl_paths(&backup_dir));
+        if findings.is_empty() {
+            info!("All kofl files of the {} vault are private ✅", vault::selected());
+            return true;
+        }
+
+        let mut ok = true;
+        for finding in &findings {
+            if !self.fix_permissions {
+                warn!("{}", finding.describe());
+                continue;
+            }
+            match finding.fix() {
+                Ok(()) => info!("Fixed: {}", finding.describe()),
+                Err(e) => {
+                    error!("{}", e);
+                    ok = false;
+                }
+            }
+        }
+        if !self.fix_permissions {
+            info!("Run `kofl doctor --fix-permissions` to make them private");
+            return false;
+        }
+        ok
+    }
+
+    // meant for vaults the startup checks refuse, so nothing is required
+    fn validate(&self, _context: &Context) -> bool {
+        true
+    }
+
+    fn display(&self) {
+        debug!("Doctor command, fix permissions {}", self.fix_permissions);
+        ()
+    }
+}
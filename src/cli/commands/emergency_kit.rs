@@ -0,0 +1,324 @@
+use crate::backup::Backup;
+use crate::cli::commands::{ChangeMasterCmd, InitCmd};
+use crate::cli::Command;
+use crate::context::Context;
+use crate::emergency_kit::{EmergencyKit, SealedKey};
+use crate::validator::core::{Sensitive, Sensitivity, ValidationResult, ValidationType};
+use crate::validator::registry::ValidationRegistry;
+use chrono::Utc;
+use log::{debug, error, info, warn};
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::os::unix::fs::OpenOptionsExt;
+use std::path::{Path, PathBuf};
+
+pub enum EmergencyKitAction {
+    Create { output: Option<PathBuf>, with_key: bool, recovery_codes: bool, keyfile: Option<PathBuf> },
+    Verify { file: PathBuf },
+    Restore { file: PathBuf },
+}
+
+/// Printable kit to get back into the vault, see `crate::emergency_kit`.
+pub struct EmergencyKitCmd {
+    pub action: EmergencyKitAction,
+}
+
+impl EmergencyKitCmd {
+    pub fn new(action: EmergencyKitAction) -> Self {
+        EmergencyKitCmd { action }
+    }
+
+    fn read_passphrase(context: &Context) -> Option<String> {
+        let (settings, username) = {
+            let kgc = context.kgc.borrow();
+            (kgc.get_settings(), kgc.get_user_login())
+        };
+        loop {
+            let passphrase = rpassword::prompt_password("type a kit passphrase, not the master password ==> ").unwrap();
+            let problems = InitCmd::master_password_problems(&passphrase, &settings, &username);
+            if !problems.is_empty() {
+                warn!("This passphrase is too weak: {}", problems.join(", "));
+                continue;
+            }
+            let confirmed = rpassword::prompt_password("type the kit passphrase again ==> ").unwrap();
+            if passphrase != confirmed {
+                error!("Passphrase mismatch");
+                return None;
+            }
+            return Some(passphrase);
+        }
+    }
+
+    fn read_kit(file: &Path) -> Option<EmergencyKit> {
+        let html = match fs::read_to_string(file) {
+            Ok(html) => html,
+            Err(e) => {
+                error!("Cannot read {}: {}", file.display(), e);
+                return None;
+            }
+        };
+        match EmergencyKit::from_html(&html) {
+            Ok(kit) => Some(kit),
+            Err(e) => {
+                error!("{}", e);
+                None
+            }
+        }
+    }
+
+    // the vault key from the sealed key of the kit, if it is still the key of this vault
+    fn open_sealed_key(context: &Context, sealed_key: &SealedKey) -> Option<String> {
+        let passphrase = rpassword::prompt_password("Enter the kit passphrase ===> ").unwrap();
+        let vault_key = match sealed_key.open(&passphrase) {
+            Ok(key) => key,
+            Err(e) => {
+                error!("{}", e);
+                return None;
+            }
+        };
+        if !context.kgc.borrow().matches_vault_key(&vault_key) {
+            error!("The sealed key is from before a master password or key file change");
+            return None;
+        }
+        Some(vault_key)
+    }
+
+    fn create(
+        &self,
+        context: &Context,
+        output: Option<&PathBuf>,
+        with_key: bool,
+        recovery_codes: bool,
+    ) -> bool {
+        let mut kit = {
+            let kgc = context.kgc.borrow();
+            let mut kit = EmergencyKit::new(
+                kgc.get_user_login(),
+                kgc.get_config_path().clone(),
+                kgc.get_data_storage_path().clone(),
+                kgc.get_salt(),
+                kgc.get_kdf_iterations(),
+            );
+            kit.backup_dir = Backup::from_settings(&kgc.get_settings()).ok().map(|b| b.get_backup_dir().clone());
+            kit.keyfile = kgc.has_keyfile();
+            kit.two_factor = kgc.get_two_factor().is_some();
+            kit.recovery_kit_id = kgc.get_recovery_kit().map(|recovery_kit| recovery_kit.kit_id.clone());
+            kit
+        };
+
+        // replaced in the config only once the kit is written
+        let mut two_factor = context.kgc.borrow().get_two_factor().cloned();
+        if recovery_codes {
+            match two_factor.as_mut() {
+                Some(two_factor) => kit.recovery_codes = two_factor.new_recovery_codes(),
+                None => warn!("Two-factor unlock is not enabled, there are no recovery codes to add"),
+            }
+        } else if let Some(two_factor) = &two_factor {
+            info!(
+                "{} recovery codes left, not in the kit, --recovery-codes adds new ones",
+                two_factor.recovery_codes_left()
+            );
+        }
+
+        if with_key {
+            let passphrase = match EmergencyKitCmd::read_passphrase(context) {
+                Some(passphrase) => passphrase,
+                None => return false,
+            };
+            let vault_key = match context.vault_key() {
+                Ok(key) => key,
+                Err(e) => {
+                    error!("{}", e);
+                    return false;
+                }
+            };
+            let iterations = context.kgc.borrow().get_kdf_iterations();
+            match SealedKey::new(&vault_key, &passphrase, iterations) {
+                Ok(sealed_key) => kit.sealed_key = Some(sealed_key),
+                Err(e) => {
+                    error!("{}", e);
+                    return false;
+                }
+            }
+        }
+
+        let html = match kit.to_html() {
+            Ok(html) => html,
+            Err(e) => {
+                error!("{}", e);
+                return false;
+            }
+        };
+        let path = output
+            .cloned()
+            .unwrap_or_else(|| PathBuf::from(format!("kofl-emergency-kit-{}.html", Utc::now().format("%Y-%m-%d"))));
+        let written = OpenOptions::new()
+            .write(true)
+            .create_new(true)
+            .mode(0o600)
+            .open(&path)
+            .and_then(|mut file| file.write_all(html.as_bytes()));
+        if let Err(e) = written {
+            error!("Cannot write the emergency kit to {}: {}", path.display(), e);
+            return false;
+        }
+
+        if recovery_codes && two_factor.is_some() {
+            context.kgc.borrow_mut().set_two_factor(two_factor);
+            context.kgc.borrow().update();
+            warn!("Recovery codes given out before now no longer work");
+        }
+        info!("Emergency kit written to {}", path.display());
+        info!("Print it, and check the file with `kofl emergency-kit verify` after vault changes");
+        true
+    }
+
+    fn verify(&self, context: &Context, file: &Path) -> bool {
+        let kit = match EmergencyKitCmd::read_kit(file) {
+            Some(kit) => kit,
+            None => return false,
+        };
+        let kgc = context.kgc.borrow();
+        let mut ok = true;
+
+        if kit.user != kgc.get_user_login() {
+            error!("The kit is for {}, not {}", kit.user, kgc.get_user_login());
+            ok = false;
+        }
+        if kit.kdf_salt != kgc.get_salt() || kit.kdf_iterations != kgc.get_kdf_iterations() || kit.keyfile != kgc.has_keyfile() {
+            error!("The master password, key file or KDF changed since the kit was made");
+            ok = false;
+        }
+        for (what, path) in [("config file", &kit.config_path), ("vault database", &kit.storage_path)] {
+            if !path.exists() {
+                warn!("The {} {} is not on this machine", what, path.display());
+            }
+        }
+
+        match (kgc.get_two_factor(), kit.recovery_codes.is_empty()) {
+            (Some(two_factor), false) => {
+                let valid = kit.recovery_codes.iter().filter(|code| two_factor.has_recovery_code(code)).count();
+                if valid == 0 {
+                    error!("None of the recovery codes in the kit work any more");
+                    ok = false;
+                } else {
+                    info!("{} of the {} recovery codes in the kit still work", valid, kit.recovery_codes.len());
+                }
+            }
+            (Some(_), true) => warn!("Two-factor unlock is enabled but the kit has no recovery codes"),
+            (None, _) => {
+                if kit.two_factor {
+                    warn!("Two-factor unlock was turned off since the kit was made");
+                }
+            }
+        }
+
+        let current_kit_id = kgc.get_recovery_kit().map(|recovery_kit| recovery_kit.kit_id.clone());
+        if kit.recovery_kit_id.is_some() && kit.recovery_kit_id != current_kit_id {
+            warn!("The Shamir recovery kit named on the sheet was replaced");
+        }
+
+        match &kit.sealed_key {
+            Some(sealed_key) => {
+                if EmergencyKitCmd::open_sealed_key(context, sealed_key).is_some() {
+                    info!("The sealed key opens this vault");
+                } else {
+                    ok = false;
+                }
+            }
+            None => info!("The kit has no sealed key, the master password is still needed"),
+        }
+
+        if ok {
+            info!("The emergency kit made on {} is up to date ✅", kit.created_at.format("%Y-%m-%d"));
+        } else {
+            error!("The emergency kit does not restore access, fix the errors above or make a new one");
+        }
+        ok
+    }
+
+    fn restore(&self, context: &Context, file: &Path) -> bool {
+        let kit = match EmergencyKitCmd::read_kit(file) {
+            Some(kit) => kit,
+            None => return false,
+        };
+        let sealed_key = match &kit.sealed_key {
+            Some(sealed_key) => sealed_key,
+            None => {
+                error!("The kit has no sealed key, it was made without --with-key");
+                return false;
+            }
+        };
+        match EmergencyKitCmd::open_sealed_key(context, sealed_key) {
+            Some(vault_key) => context.unlock(vault_key),
+            None => return false,
+        }
+        ChangeMasterCmd::reset_forgotten(context)
+    }
+}
+
+impl Sensitive for EmergencyKitCmd {
+    // a kit replaces the 2FA recovery codes and may carry the vault key
+    fn sensitivity(&self) -> Sensitivity {
+        match self.action {
+            EmergencyKitAction::Create { .. } => Sensitivity::Sensitive,
+            EmergencyKitAction::Verify { .. } | EmergencyKitAction::Restore { .. } => Sensitivity::Normal,
+        }
+    }
+
+    fn keyfile(&self) -> Option<&Path> {
+        match &self.action {
+            EmergencyKitAction::Create { keyfile, .. } => keyfile.as_deref(),
+            EmergencyKitAction::Verify { .. } | EmergencyKitAction::Restore { .. } => None,
+        }
+    }
+}
+
+impl Command for EmergencyKitCmd {
+    fn execute(&self, context: &Context) -> bool {
+        match &self.action {
+            EmergencyKitAction::Create { output, with_key, recovery_codes, .. } => {
+                self.create(context, output.as_ref(), *with_key, *recovery_codes)
+            }
+            EmergencyKitAction::Verify { file } => self.verify(context, file),
+            EmergencyKitAction::Restore { file } => self.restore(context, file),
+        }
+    }
+
+    fn validate(&self, context: &Context) -> bool {
+        let val_reg = ValidationRegistry::<EmergencyKitCmd>::new();
+
+        // verify and restore are used without the master password, like `kofl recovery combine`
+        let val_checks = match self.action {
+            EmergencyKitAction::Create { .. } => vec![
+                ValidationType::MasterKeyCheck,
+                ValidationType::RateLimitCheck,
+                ValidationType::SessionCheck,
+                ValidationType::ReauthCheck,
+            ],
+            EmergencyKitAction::Verify { .. } | EmergencyKitAction::Restore { .. } => vec![ValidationType::MasterKeyCheck],
+        };
+
+        for a_check in val_checks {
+            match val_reg.validators.get(&a_check).unwrap().validate(context, &self) {
+                ValidationResult::Failure(msg) => {
+                    error!("{msg}");
+                    return false
+                },
+                ValidationResult::Warning(msg) => warn!("{msg}"),
+                ValidationResult::Success => debug!("test passed ✅")
+            }
+        }
+
+        true
+    }
+
+    fn display(&self) {
+        match &self.action {
+            EmergencyKitAction::Create { .. } => debug!("Emergency kit command, create"),
+            EmergencyKitAction::Verify { file } => debug!("Emergency kit command, verify {}", file.display()),
+            EmergencyKitAction::Restore { file } => debug!("Emergency kit command, restore from {}", file.display()),
+        }
+        ()
+    }
+}
@@ -0,0 +1,73 @@
+use crate::cli::Command;
+use crate::context::Context;
+use crate::generator::{self, GeneratorOptions, GeneratorRules};
+use crate::policy::PasswordPolicy;
+use crate::strength;
+use log::{debug, error, info, warn};
+
+pub struct GenerateCmd {
+    pub rules: GeneratorRules,
+    pub count: usize,
+    // generate for this entry, using its policy and stored rules
+    pub entry: Option<String>,
+}
+
+impl GenerateCmd {
+    pub fn new(rules: GeneratorRules, count: usize, entry: Option<String>) -> Self {
+        GenerateCmd { rules, count, entry }
+    }
+}
+
+impl Command for GenerateCmd {
+    fn execute(&self, context: &Context) -> bool {
+        let target = self.entry.clone().unwrap_or_default();
+        let (policy, mut options) = {
+            let kgc = context.kgc.borrow();
+            let policy = PasswordPolicy::for_entry(&kgc, &target);
+            let options = GeneratorOptions::from_policy(&policy, &kgc.get_settings());
+            (policy, options)
+        };
+
+        let stored = match &self.entry {
+            Some(name) => context
+                .db
+                .get_generator_rules(name)
+                .ok()
+                .flatten()
+                .and_then(|raw| GeneratorRules::from_toml(&raw).ok())
+                .unwrap_or_default(),
+            None => GeneratorRules::default(),
+        };
+        options.apply(&stored.merge(&self.rules));
+        info!("Strategy: {}, entropy: {:.0} bits", options.strategy, generator::entropy_bits(&options));
+
+        for _ in 0..self.count.max(1) {
+            let password = match generator::generate(&options) {
+                Ok(password) => password,
+                Err(e) => {
+                    error!("{}", e);
+                    return false;
+                }
+            };
+            println!("{}", password);
+
+            let violations = policy.check(&password);
+            if !violations.is_empty() {
+                let details: Vec<String> = violations.iter().map(|v| v.to_string()).collect();
+                warn!("This password would be rejected by the policy: {}", details.join(", "));
+            }
+            info!("Strength: {}", strength::estimate(&password, &[&target]).meter());
+        }
+        true
+    }
+
+    // generating does not read or change the vault
+    fn validate(&self, _context: &Context) -> bool {
+        true
+    }
+
+    fn display(&self) {
+        debug!("Generate Command");
+        ()
+    }
+}
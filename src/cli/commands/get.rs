@@ -1,35 +1,35 @@
+use crate::cipher;
 use crate::cli::Command;
 use crate::errors::{ErrorExecution, ErrorValidation};
 use crate::context::Context;
 use log::{debug, error, info, warn};
 use sha2::Digest;
-use crate::validator::core::{CommandType, ValidationResult, ValidationType};
+use crate::validator::core::{CommandType, Sensitive, Sensitivity, ValidationResult, ValidationType};
 use crate::validator::registry::ValidationRegistry;
-use arboard::{Clipboard};
-use arboard::Error as ClipboardError;
-
-
-
-use aes::cipher::{
-    KeyIvInit, StreamCipher,
-    generic_array::GenericArray,
-};
-use ctr::Ctr32BE;
-type Aes256Ctr = Ctr32BE<aes::Aes256>;
+use crate::clipboard;
 
 
 pub struct GetCmd {
-    pub ent_name: String
+    pub ent_name: String,
+    // print the password instead of copying it, for headless machines
+    pub print: bool,
 }
 
 
 impl GetCmd {
-    pub fn new(ent_name: String) -> Self {
-        GetCmd{ent_name}
+    pub fn new(ent_name: String, print: bool) -> Self {
+        GetCmd{ent_name, print}
     }
 }
 
 
+impl Sensitive for GetCmd {
+    // a printed password ends up in the terminal scrollback
+    fn sensitivity(&self) -> Sensitivity {
+        if self.print { Sensitivity::Sensitive } else { Sensitivity::Normal }
+    }
+}
+
 impl Command for GetCmd {
 
     fn execute(&self, context: &Context) -> bool {
@@ -46,44 +46,18 @@ impl Command for GetCmd {
             }).unwrap();
 
         // Get master key hash
-        let master_key_hash = {
-            let kgc = context.kgc.borrow();
-            kgc.get_hashed_pwd()
-        };
-
-        // Decode the master key
-        let master_key_bytes = match  hex::decode(&master_key_hash) {
-            Ok(bytes) => bytes,
-            Err(_) => {
-                error!("Error decoding master key hash");
+        let master_key_hash = match context.vault_key() {
+            Ok(key) => key,
+            Err(e) => {
+                error!("{}", e);
                 return false;
             }
         };
-        // Create key and nonce
-        let key = GenericArray::from_slice(&master_key_bytes);
-        let nonce = GenericArray::from_slice(&[0u8; 16]); // Must match the nonce used in AddCmd
-
-        // Initialize cipher
-        let mut cipher = Aes256Ctr::new(key, nonce);
-
-        // Decode the stored encrypted password
 
-        let mut encrypted_password = match hex::decode(&entry.password_hash) {
+        let decrypted_password = match cipher::decrypt_password(&master_key_hash, &entry.password_hash) {
             Ok(pwd) => pwd,
-            Err(_) => {
-                error!("Error decoding password hash");
-                return false;
-            }
-        };
-
-        // Decrypt
-        cipher.apply_keystream(&mut encrypted_password);
-
-
-        let decrypted_password = match String::from_utf8(encrypted_password) {
-            Ok(pwd) => pwd,
-            Err(_) => {
-                error!("Error decrypting password");
+            Err(e) => {
+                error!("{}", e);
                 return false;
             }
         };
@@ -91,23 +65,15 @@ impl Command for GetCmd {
         // println!("Entry Name: {}", entry.ent_name);
         // println!("Password: {}", decrypted_password);
 
-        let mut clipboard = Clipboard::new().unwrap();
-       
-
-        // let the_string = "Hello, world!";
-        // match clipboard.set_text(decrypted_password) {
-        //     Ok(_) => info!("Password is copied to clipboard"),
-        //     Err(ClipboardError::ClipboardNotSupported) => error!("Your system does not support clibboard."),
-        //     Err(ClipboardError::ContentNotAvailable) => error!("Content not available"),
-        //     Err(ClipboardError::ClipboardOccupied) => error!("Clipboard is occupied"),
-        //     Err(ClipboardError::ConversionFailure) => error!("Conversion failure should not happen as we retrieve utf-8 strings not images"),
-        //     Err(ClipboardError::Unknown{description}) => error!("{}", description),
-        //     Err(_) => error!("undefined behaviour"),
-        // }
-
-        match clipboard.set_text(decrypted_password) {
-            Ok(_) => info!("Password is copied to clipboard"),
-            Err(e) => error!("Error copying to clipboard: {}", e),
+        if self.print {
+            println!("{}", decrypted_password);
+            return true;
+        }
+
+        let settings = context.kgc.borrow().get_settings();
+        if let Err(e) = clipboard::copy_with_settings(&settings, &decrypted_password) {
+            error!("{}, use --print to show the password instead", e);
+            return false;
         }
         
         // println!("Clipboard text was: {}", clipboard.get_text().unwrap());
@@ -122,6 +88,7 @@ impl Command for GetCmd {
             ValidationType::MasterKeyCheck,
             ValidationType::SessionCheck,
             ValidationType::EntryExistsCheck,
+            ValidationType::ReauthCheck,
         ];
 
 
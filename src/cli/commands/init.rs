@@ -2,12 +2,24 @@ use crate::cli::Command;
 use crate::errors::{ErrorExecution, ErrorValidation};
 use crate::context::Context;
 use crate::session::Session;
+use crate::cli::commands::TwoFactorCmd;
 use crate::validator::core::{ValidationResult, ValidationType};
 use crate::validator::registry::ValidationRegistry;
+use crate::constants::CONS;
+use crate::blocklist::Blocklist;
+use crate::kdf;
+use crate::keyfile;
+use crate::policy::PasswordPolicy;
+use crate::strength;
+use crate::setting::registry::SettingsRegistry;
+use crate::setting::setting_key::SettingKey;
 use rand::{thread_rng, Rng};
 use rand::distributions::Alphanumeric;
-use sha2::{Sha256, Digest};
 use log::{debug, error, info, warn};
+use std::collections::BTreeMap;
+use std::io::Write;
+use std::path::PathBuf;
+use std::{env, fs};
 
 
 use aes::cipher::KeyIvInit;
@@ -16,61 +28,257 @@ type Aes256Ctr = Ctr32BE<aes::Aes256>;
 
 
 pub struct InitCmd {
-    // for now is emty 
+    pub defaults: bool,
+    pub config_file: Option<PathBuf>,
+    // enroll a TOTP second factor right away
+    pub two_factor: bool,
+    // bind the vault key to this file, created when missing
+    pub keyfile: Option<PathBuf>,
 }
 
 impl InitCmd {
-    pub fn new() -> Self {
-        InitCmd{}
+    pub fn new(defaults: bool, config_file: Option<PathBuf>, two_factor: bool, keyfile: Option<PathBuf>) -> Self {
+        InitCmd{ defaults, config_file, two_factor, keyfile }
+    }
+
+    fn read_line(prompt: &str) -> String {
+        print!("{}", prompt);
+        let _ = std::io::stdout().flush();
+        let mut answer = String::new();
+        let _ = std::io::stdin().read_line(&mut answer);
+        answer.trim().to_string()
+    }
+
+    // asks for one setting until a valid value is given, an empty answer keeps the current one
+    fn ask(registry: &mut SettingsRegistry, key: SettingKey) {
+        loop {
+            let (description, current) = match registry.get(key) {
+                Some(setting) => (setting.description(), setting.get_value()),
+                None => return,
+            };
+            let answer = InitCmd::read_line(&format!("{} [{}] ==> ", description, current));
+            if answer.is_empty() {
+                return;
+            }
+            match registry.parse(key, &answer).and_then(|value| registry.update(key, value)) {
+                Ok(_) => return,
+                Err(e) => error!("{}", e),
+            }
+        }
+    }
+
+    // lets the user pick the KDF cost while showing how long an unlock takes on this machine
+    fn ask_kdf_iterations(registry: &mut SettingsRegistry) {
+        loop {
+            let iterations = registry.kdf_iterations().unwrap_or(CONS::MASTER_KDF_ITERATIONS);
+            let elapsed = kdf::benchmark(iterations);
+            info!("{} KDF iterations take {} ms on this machine", iterations, elapsed.as_millis());
+
+            let answer = InitCmd::read_line("Keep this value? Type a new iteration count or press enter ==> ");
+            if answer.is_empty() {
+                return;
+            }
+            if let Err(e) = registry
+                .parse(SettingKey::KdfIterations, &answer)
+                .and_then(|value| registry.update(SettingKey::KdfIterations, value))
+            {
+                error!("{}", e);
+            }
+        }
+    }
+
+    fn wizard(registry: &mut SettingsRegistry) {
+        info!("Let's set up kofl, press enter to keep the value in brackets");
+
+        InitCmd::ask(registry, SettingKey::SessionDuration);
+        InitCmd::ask(registry, SettingKey::IdleTimeout);
+        InitCmd::ask(registry, SettingKey::ClipboardTimeout);
+        InitCmd::ask(registry, SettingKey::PasswordMinLength);
+        InitCmd::ask(registry, SettingKey::RequireSpecialChars);
+        InitCmd::ask_kdf_iterations(registry);
+        InitCmd::ask(registry, SettingKey::BackupLocation);
+    }
+
+    /// Applies the `setting = value` pairs of a TOML file, all of them must be valid.
+    pub(crate) fn apply_config_file(registry: &mut SettingsRegistry, path: &PathBuf) -> Result<(), String> {
+        let content = fs::read_to_string(path).map_err(|e| format!("Cannot read {}: {}", path.display(), e))?;
+        let table: BTreeMap<String, toml::Value> =
+            toml::from_str(&content).map_err(|e| format!("Invalid config file {}: {}", path.display(), e))?;
+
+        for (name, value) in &table {
+            let raw = match value {
+                toml::Value::String(s) => s.clone(),
+                other => other.to_string(),
+            };
+            name.parse::<SettingKey>()
+                .and_then(|key| registry.parse(key, &raw).and_then(|value| registry.update(key, value)))
+                .map_err(|e| format!("{}: {}", name, e))?;
+        }
+        Ok(())
+    }
+
+    /// Problems that make `pwd` unacceptable as a master password under the chosen settings.
+    pub fn master_password_problems(pwd: &str, registry: &SettingsRegistry, username: &str) -> Vec<String> {
+        let mut problems: Vec<String> = PasswordPolicy::from_settings(registry)
+            .check(pwd)
+            .iter()
+            .map(|violation| violation.to_string())
+            .collect();
+
+        match Blocklist::from_setting(registry.blocklist_file()) {
+            Ok(blocklist) => {
+                if let Some(hit) = blocklist.check(pwd) {
+                    problems.push(hit.to_string());
+                }
+            }
+            Err(e) => problems.push(format!("the blocklist file cannot be read: {}", e)),
+        }
+
+        let estimate = strength::estimate(pwd, &[username]);
+        info!("Master password strength: {}", estimate);
+        let min_score = registry.min_password_score().unwrap_or(2);
+        if (estimate.score as u32) < min_score {
+            problems.push(format!("strength score {} is below the minimum of {}", estimate.score, min_score));
+        }
+        problems
+    }
+
+    // KOFL_MASTER_PASSWORD allows provisioning without a terminal
+    pub fn read_master_password(registry: &SettingsRegistry, username: &str) -> Option<String> {
+        if let Ok(pwd) = env::var("KOFL_MASTER_PASSWORD") {
+            let problems = InitCmd::master_password_problems(&pwd, registry, username);
+            if !problems.is_empty() {
+                error!("KOFL_MASTER_PASSWORD is too weak: {}", problems.join(", "));
+                return None;
+            }
+            return Some(pwd);
+        }
+
+        loop {
+            // no terminal to ask on, KOFL_MASTER_PASSWORD is the way to init without one
+            let master_pwd = match rpassword::prompt_password("type a master password ==> ") {
+                Ok(pwd) => pwd,
+                Err(e) => {
+                    error!("Cannot read the master password: {}", e);
+                    return None;
+                }
+            };
+
+            let problems = InitCmd::master_password_problems(&master_pwd, registry, username);
+            if !problems.is_empty() {
+                warn!("This master password is too weak: {}", problems.join(", "));
+                continue;
+            }
+
+            let master_pwd_confirmed = match rpassword::prompt_password("type the master password again ==> ") {
+                Ok(pwd) => pwd,
+                Err(e) => {
+                    error!("Cannot read the master password: {}", e);
+                    return None;
+                }
+            };
+            if master_pwd != master_pwd_confirmed {
+                error!("Password mismatch");
+                return None;
+            }
+            return Some(master_pwd);
+        }
     }
 
 }
 
 impl Command for InitCmd {
         fn execute(&self, context: &Context) -> bool  {
-            
-            let master_pwd  = rpassword::prompt_password("type a master password ==> ").unwrap();
-            let master_pwd_confirmed = rpassword::prompt_password("type the master password again ==> ").unwrap();
 
-            if master_pwd != master_pwd_confirmed {
-                error!("Password mismatch");
-                return false;
+            let mut registry = context.kgc.borrow().get_settings();
+
+            if let Some(path) = &self.config_file {
+                if let Err(e) = InitCmd::apply_config_file(&mut registry, path) {
+                    error!("{}", e);
+                    return false;
+                }
+            } else if !self.defaults {
+                InitCmd::wizard(&mut registry);
             }
 
+            let keyfile_digest = match &self.keyfile {
+                Some(path) => {
+                    if !path.exists() {
+                        if let Err(e) = keyfile::generate(path) {
+                            error!("{}", e);
+                            return false;
+                        }
+                        info!("Created a new key file at {}, keep a copy of it somewhere safe", path.display());
+                    }
+                    match keyfile::digest(path) {
+                        Ok(digest) => Some(digest),
+                        Err(e) => {
+                            error!("{}", e);
+                            return false;
+                        }
+                    }
+                }
+                None => None,
+            };
+
+            let username = context.kgc.borrow().get_user_login();
+            let master_pwd = match InitCmd::read_master_password(&registry, &username) {
+                Some(pwd) => pwd,
+                None => return false,
+            };
+
             let salt:String = thread_rng()
                 .sample_iter(&Alphanumeric)
                 .take(16)
                 .map(char::from)
                 .collect();
 
-            //hash the master password with the salt
-            let mut hasher = Sha256::new();
-            hasher.update(master_pwd.clone());
-            hasher.update(salt.clone());
-            let hashed_password = hasher.finalize();
-            let hashed_password_hex = hex::encode(hashed_password); // Convert to hexadecimal string
-
-            // println!("salt = {salt}");
-            // println!("master pwd = {master_pwd}");
-            // println!("oooooooooooooo hashed_password = {hashed_password_hex}");
-            
-            // Update the configuration with the salt and hashed password
+            // derive the vault key from the master password and the salt, only its verifier is stored
+            let kdf_iterations = registry.kdf_iterations().unwrap_or(CONS::MASTER_KDF_ITERATIONS);
+            let vault_key = kdf::derive_master_hash(&kdf::with_keyfile(&master_pwd, keyfile_digest.as_deref()), &salt, kdf_iterations);
+
+            // Update the configuration with the salt, key verifier and chosen settings
             {
                 let mut kgc = context.kgc.borrow_mut();
                 kgc.set_salt(salt.clone());
-                kgc.set_master_key_hash(hashed_password_hex); // Assuming you have a method to set the hashed password
-                kgc.set_master_key_provided(true); // Assuming you have a method to set this flag
+                kgc.set_vault_key(&vault_key);
+                kgc.set_kdf_iterations(kdf_iterations);
+                kgc.set_keyfile(keyfile_digest.is_some());
+                kgc.set_master_key_provided(true);
+
+                kgc.reset_all_settings();
+                for setting in registry.list() {
+                    let value = setting.get_value();
+                    if value.to_string() != setting.default().to_string() {
+                        kgc.set_setting(setting.key(), &value);
+                    }
+                }
             }
 
             context.kgc.borrow().update();
-            // Print the updated configuration
-            // println!("Updated kgc = {:?}", context.kgc.borrow());
+            context.unlock(vault_key.clone());
 
             let user_login = context.kgc.borrow().get_user_login().clone();
-            let new_session = Session::new(user_login, true);
+            let session_duration = registry.session_duration().unwrap_or(30);
+            let scope = registry.session_scope().unwrap_or_else(|| "user".to_string());
+            let new_session = match Session::start(user_login, session_duration, &scope) {
+                Ok(mut session) => {
+                    session.set_vault_key(vault_key);
+                    session
+                }
+                Err(e) => {
+                    warn!("{}, run `kofl login` where it can be bound", e);
+                    Session::new(context.kgc.borrow().get_user_login(), false, session_duration)
+                }
+            };
         
             new_session.write_session_config_to_toml_file();
 
+            if self.two_factor && !TwoFactorCmd::enroll(context) {
+                warn!("The vault is set up without a second factor, run `kofl 2fa enable` to try again");
+            }
+
+            info!("kofl is ready, settings can be changed later with `kofl config`");
             true
         }
 
@@ -0,0 +1,130 @@
+use crate::cli::commands::LogInCmd;
+use crate::cli::Command;
+use crate::context::Context;
+use crate::kdf;
+use crate::keyfile;
+use crate::rekey;
+use crate::validator::core::{Sensitive, Sensitivity, ValidationResult, ValidationType};
+use crate::validator::registry::ValidationRegistry;
+use log::{debug, error, info, warn};
+use std::path::{Path, PathBuf};
+
+pub enum KeyfileAction {
+    // `current` is None when the vault is not bound to a key file yet
+    Rotate { current: Option<PathBuf>, new: PathBuf },
+}
+
+/// Binding of the vault key to a key file.
+pub struct KeyfileCmd {
+    pub action: KeyfileAction,
+}
+
+impl KeyfileCmd {
+    pub fn new(action: KeyfileAction) -> Self {
+        KeyfileCmd { action }
+    }
+
+    /// Derives the vault key from the master password and the new file, then
+    /// re-encrypts everything sealed with the old key.
+    fn rotate(&self, context: &Context, current: Option<&PathBuf>, new: &PathBuf) -> bool {
+        // usually checked by `ReauthValidator` already, the new key needs the password itself
+        let master_pwd = match context
+            .master_password()
+            .or_else(|| LogInCmd::verify_master_password(context, current.map(|p| p.as_path())))
+        {
+            Some(pwd) => pwd,
+            None => return false,
+        };
+
+        if !new.exists() {
+            if let Err(e) = keyfile::generate(new) {
+                error!("{}", e);
+                return false;
+            }
+            info!("Created a new key file at {}", new.display());
+        }
+        let new_digest = match keyfile::digest(new) {
+            Ok(digest) => digest,
+            Err(e) => {
+                error!("{}", e);
+                return false;
+            }
+        };
+
+        // archives made from here on need the new file, this one still opens with the old setup
+        if let Err(e) = rekey::backup(context) {
+            error!("Could not back up the vault before the change: {}", e);
+            return false;
+        }
+
+        let iterations = context.kgc.borrow().get_kdf_iterations();
+        // a fresh salt tells old master password archives apart from the new key
+        let salt = rekey::new_salt();
+        let new_key = kdf::derive_master_hash(&kdf::with_keyfile(&master_pwd, Some(&new_digest)), &salt, iterations);
+        if let Err(e) = rekey::rekey(context, &new_key, salt, true) {
+            error!("{}", e);
+            return false;
+        }
+
+        if let Err(e) = rekey::backup(context) {
+            warn!("Could not back up the re-encrypted vault: {}", e);
+        }
+        info!("The vault is now bound to {}, keep a copy of it somewhere safe", new.display());
+        if let Some(current) = current {
+            info!("{} is no longer needed, except for backups made before now", current.display());
+        }
+        true
+    }
+}
+
+impl Sensitive for KeyfileCmd {
+    // a new key file re-keys the vault
+    fn sensitivity(&self) -> Sensitivity {
+        Sensitivity::Sensitive
+    }
+
+    fn keyfile(&self) -> Option<&Path> {
+        match &self.action {
+            KeyfileAction::Rotate { current, .. } => current.as_deref(),
+        }
+    }
+}
+
+impl Command for KeyfileCmd {
+    fn execute(&self, context: &Context) -> bool {
+        match &self.action {
+            KeyfileAction::Rotate { current, new } => self.rotate(context, current.as_ref(), new),
+        }
+    }
+
+    fn validate(&self, context: &Context) -> bool {
+        let val_reg = ValidationRegistry::<KeyfileCmd>::new();
+
+        // the master password is asked again, a session is not enough to re-key the vault
+        let val_checks = vec![
+            ValidationType::MasterKeyCheck,
+            ValidationType::RateLimitCheck,
+            ValidationType::ReauthCheck,
+        ];
+
+        for a_check in val_checks {
+            match val_reg.validators.get(&a_check).unwrap().validate(context, &self) {
+                ValidationResult::Failure(msg) => {
+                    error!("{msg}");
+                    return false
+                },
+                ValidationResult::Warning(msg) => warn!("{msg}"),
+                ValidationResult::Success => debug!("test passed ✅")
+            }
+        }
+
+        true
+    }
+
+    fn display(&self) {
+        match &self.action {
+            KeyfileAction::Rotate { new, .. } => debug!("Keyfile command, rotate to {}", new.display()),
+        }
+        ()
+    }
+}
@@ -0,0 +1,108 @@
+use crate::cli::Command;
+use crate::context::Context;
+use crate::rotation;
+use crate::validator::core::{ValidationResult, ValidationType};
+use crate::validator::registry::ValidationRegistry;
+use chrono::{DateTime, Utc};
+use log::{debug, error, info, warn};
+
+pub struct ListCmd {
+    // only the entries due for rotation, or expiring within `within_days`
+    pub expiring: bool,
+    pub within_days: u32,
+}
+
+impl ListCmd {
+    pub fn new(expiring: bool, within_days: u32) -> Self {
+        ListCmd { expiring, within_days }
+    }
+
+    fn list_expiring(&self, context: &Context, entries: &[crate::db::Db::Entry]) -> bool {
+        let now = Utc::now();
+        let mut due: Vec<rotation::Expiry> = rotation::expiries(&context.kgc.borrow(), entries)
+            .into_iter()
+            .filter(|expiry| expiry.days_left(now).map_or(true, |days| days < self.within_days as i64) || expiry.is_overdue(now))
+            .collect();
+        due.sort_by_key(|expiry| expiry.days_left(now).unwrap_or(i64::MIN));
+
+        if due.is_empty() {
+            info!("No entries are due for rotation in the next {} days", self.within_days);
+            return true;
+        }
+
+        for expiry in &due {
+            match expiry.days_left(now) {
+                None => println!("{}  unknown age, rotate it", expiry.name),
+                Some(_) if expiry.is_overdue(now) => {
+                    let days = -expiry.days_left(now).unwrap_or(0);
+                    println!("{}  overdue by {} days (max age {} days)", expiry.name, days, expiry.max_age_days)
+                }
+                Some(days) => println!("{}  expires in {} days (max age {} days)", expiry.name, days, expiry.max_age_days),
+            }
+        }
+        info!("{} entries due for rotation, use `kofl rotate <name>`", due.len());
+        true
+    }
+}
+
+impl Command for ListCmd {
+    fn execute(&self, context: &Context) -> bool {
+        let mut entries = match context.db.list_entries() {
+            Ok(entries) => entries,
+            Err(e) => {
+                error!("Error listing entries: {}", e);
+                return false;
+            }
+        };
+        entries.sort_by(|a, b| a.ent_name.cmp(&b.ent_name));
+
+        if self.expiring {
+            return self.list_expiring(context, &entries);
+        }
+
+        if entries.is_empty() {
+            info!("The vault is empty, add an entry with `kofl add <name>`");
+            return true;
+        }
+        for entry in &entries {
+            let changed = DateTime::parse_from_rfc3339(&entry.timestamp)
+                .map(|t| t.format("%Y-%m-%d").to_string())
+                .unwrap_or_else(|_| entry.timestamp.clone());
+            println!(
+                "{}  {}  {}  changed {}",
+                entry.ent_name,
+                entry.username.as_deref().unwrap_or("-"),
+                entry.url.as_deref().unwrap_or("-"),
+                changed
+            );
+        }
+        true
+    }
+
+    fn validate(&self, context: &Context) -> bool {
+        let val_reg = ValidationRegistry::<ListCmd>::new();
+
+        let val_checks = vec![
+            ValidationType::MasterKeyCheck,
+            ValidationType::SessionCheck,
+        ];
+
+        for a_check in val_checks {
+            match val_reg.validators.get(&a_check).unwrap().validate(context, &self) {
+                ValidationResult::Failure(msg) => {
+                    error!("{msg}");
+                    return false
+                },
+                ValidationResult::Warning(msg) => warn!("{msg}"),
+                ValidationResult::Success => debug!("test passed ✅")
+            }
+        }
+
+        true
+    }
+
+    fn display(&self) {
+        debug!("List command, expiring = {}", self.expiring);
+        ()
+    }
+}
@@ -2,12 +2,15 @@ use crate::cli::Command;
 use crate::errors::{ErrorExecution, ErrorValidation};
 use crate::context::Context;
 use crate::session::Session;
+use crate::cli::commands::TwoFactorCmd;
+use crate::keyfile;
 use crate::validator::core::{ValidationResult, ValidationType};
 use crate::validator::registry::ValidationRegistry;
 use log::{debug, error, info, warn};
 use rand::{thread_rng, Rng};
 use rand::distributions::Alphanumeric;
 use sha2::{Sha256, Digest};
+use std::path::{Path, PathBuf};
 
 
 use aes::cipher::KeyIvInit;
@@ -16,13 +19,56 @@ type Aes256Ctr = Ctr32BE<aes::Aes256>;
 
 
 pub struct LogInCmd {
-    // for now is emty 
+    // required when the vault is bound to a key file
+    pub keyfile: Option<PathBuf>,
 }
 
 impl LogInCmd {
-    pub fn new() -> Self {
-        LogInCmd{}
-    }   
+    pub fn new(keyfile: Option<PathBuf>) -> Self {
+        LogInCmd{ keyfile }
+    }
+
+    /// Prompts for the master password and checks it, with the key file when the vault has one.
+    /// A wrong password counts towards the login lockout. Returns the password once verified,
+    /// the vault key derived from it is then available through `Context::vault_key`.
+    pub fn verify_master_password(context: &Context, keyfile: Option<&Path>) -> Option<String> {
+        let keyfile_digest = match keyfile::for_vault(&context.kgc.borrow(), keyfile) {
+            Ok(digest) => digest,
+            Err(e) => {
+                error!("{}", e);
+                return None;
+            }
+        };
+
+        let master_pwd_input = rpassword::prompt_password("Enter the master password ===> ").unwrap();
+
+        let vault_key = context.kgc.borrow().hash_master_password(&master_pwd_input, keyfile_digest.as_deref());
+
+        if !context.kgc.borrow().matches_vault_key(&vault_key) {
+            if keyfile_digest.is_some() {
+                error!("Invalid password or key file");
+            } else {
+                error!("Invalid password");
+            }
+            let locked = context.kgc.borrow_mut().record_failed_login();
+            if locked {
+                warn!("Too many failed attempts, login is locked for a while");
+            }
+            context.kgc.borrow().update();
+            return None;
+        }
+        // configs from before the verifier still hold the key itself
+        if !context.kgc.borrow().has_key_verifier() {
+            context.kgc.borrow_mut().set_vault_key(&vault_key);
+            context.kgc.borrow().update();
+            info!("The vault key is no longer kept in the config");
+        }
+        context.unlock(vault_key);
+        context.remember_master_password(master_pwd_input.clone());
+        // also covers the sensitive actions that follow, see `ReauthValidator`
+        context.ss.record_reauth();
+        Some(master_pwd_input)
+    }
 
 }
 
@@ -30,31 +76,39 @@ impl Command for LogInCmd {
 
 
     fn execute(&self, context: &Context) -> bool {
-        let master_pwd_input = rpassword::prompt_password("Enter the master password ===> ").unwrap();
+        if LogInCmd::verify_master_password(context, self.keyfile.as_deref()).is_none() {
+            return false;
+        }
 
-        let salt = context.kgc.borrow().get_salt();
-        let stored_hash = context.kgc.borrow().get_hashed_pwd();
-    
-        let mut hasher = Sha256::new();
-        hasher.update(master_pwd_input.as_bytes());
-        hasher.update(salt.as_bytes());
-        let computed_hash = hasher.finalize();
-        let computed_hash_hex = hex::encode(computed_hash);
-    
-        // println!("Computed hash: {}", computed_hash_hex);
-        // println!("Stored hash:   {}", stored_hash);
-    
-        // 4. Compare the computed hash with the stored hash.
-        if computed_hash_hex != stored_hash {
-            error!("Invalid password");
+        if !TwoFactorCmd::challenge(context) {
             return false;
         }
-    
+
+        if context.kgc.borrow().get_failed_login_attempts() > 0 {
+            context.kgc.borrow_mut().reset_failed_logins();
+            context.kgc.borrow().update();
+        }
         
 
         let user_login = context.kgc.borrow().get_user_login().clone();
-        let new_session = Session::new(user_login, true);
-        
+        let settings = context.kgc.borrow().get_settings();
+        let session_duration = settings.session_duration().unwrap_or(30);
+        let scope = settings.session_scope().unwrap_or_else(|| "user".to_string());
+        let mut new_session = match Session::start(user_login, session_duration, &scope) {
+            Ok(session) => session,
+            Err(e) => {
+                error!("{}", e);
+                return false;
+            }
+        };
+        // the key stays with the session only, commands until it ends use it
+        match context.vault_key() {
+            Ok(key) => new_session.set_vault_key(key),
+            Err(e) => {
+                error!("{}", e);
+                return false;
+            }
+        }
         new_session.write_session_config_to_toml_file();
     
     
@@ -69,6 +123,7 @@ impl Command for LogInCmd {
 
         let val_checks = vec![
             ValidationType::MasterKeyCheck,
+            ValidationType::RateLimitCheck,
             ValidationType::SessionCheck,
         ];
 
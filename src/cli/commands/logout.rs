@@ -0,0 +1,53 @@
+use crate::cli::Command;
+use crate::context::Context;
+use crate::validator::core::{ValidationResult, ValidationType};
+use crate::validator::registry::ValidationRegistry;
+use log::{debug, error, info, warn};
+
+/// Ends the current session before it times out.
+pub struct LogoutCmd {}
+
+impl LogoutCmd {
+    pub fn new() -> Self {
+        LogoutCmd {}
+    }
+}
+
+impl Command for LogoutCmd {
+    fn execute(&self, context: &Context) -> bool {
+        if context.ss.check_if_expired() {
+            info!("No active session, the vault is already locked");
+        } else {
+            info!("Vault locked, `kofl login` starts a new session");
+        }
+        // written either way, so a session that only timed out is closed for good
+        context.ss.lock();
+        true
+    }
+
+    fn validate(&self, context: &Context) -> bool {
+        let val_reg = ValidationRegistry::<LogoutCmd>::new();
+
+        let val_checks = vec![
+            ValidationType::MasterKeyCheck,
+        ];
+
+        for a_check in val_checks {
+            match val_reg.validators.get(&a_check).unwrap().validate(context, &self) {
+                ValidationResult::Failure(msg) => {
+                    error!("{msg}");
+                    return false
+                },
+                ValidationResult::Warning(msg) => warn!("{msg}"),
+                ValidationResult::Success => debug!("test passed ✅")
+            }
+        }
+
+        true
+    }
+
+    fn display(&self) {
+        debug!("Logout command");
+        ()
+    }
+}
@@ -2,11 +2,43 @@ mod add;
 mod get;
 mod init;
 mod login;
+mod logout;
 mod destroy;
 mod update;
+mod backup;
+mod config;
+mod policy;
+mod generate;
+mod audit;
+mod list;
+mod rotate;
+mod otp;
+mod two_factor;
+mod keyfile;
+mod change_master;
+mod recovery;
+mod emergency_kit;
+mod vault;
+mod doctor;
 pub use add::AddCmd;
 pub use get::GetCmd;
 pub use init::InitCmd;
 pub use login::LogInCmd;
+pub use logout::LogoutCmd;
 pub use destroy::DestroyCmd;
-pub use update::UpdateCmd;
\ No newline at end of file
+pub use update::UpdateCmd;
+pub use backup::{BackupAction, BackupCmd};
+pub use config::{ConfigAction, ConfigCmd};
+pub use policy::{PolicyAction, PolicyCmd};
+pub use generate::GenerateCmd;
+pub use audit::{AuditAction, AuditCmd};
+pub use list::ListCmd;
+pub use rotate::RotateCmd;
+pub use otp::{OtpAction, OtpCmd};
+pub use two_factor::{TwoFactorAction, TwoFactorCmd};
+pub use keyfile::{KeyfileAction, KeyfileCmd};
+pub use change_master::ChangeMasterCmd;
+pub use recovery::{RecoveryAction, RecoveryCmd};
+pub use emergency_kit::{EmergencyKitAction, EmergencyKitCmd};
+pub use vault::{VaultAction, VaultCmd};
+pub use doctor::DoctorCmd;
\ No newline at end of file
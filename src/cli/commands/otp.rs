@@ -0,0 +1,266 @@
+use crate::backup::Backup;
+use crate::cipher;
+use crate::cli::Command;
+use crate::clipboard;
+use crate::context::Context;
+use crate::otp::{migration, OtpKind, OtpSecret};
+use crate::validator::core::{Sensitive, Sensitivity, ValidationResult, ValidationType};
+use crate::validator::registry::ValidationRegistry;
+use chrono::Utc;
+use log::{debug, error, info, warn};
+
+pub enum OtpAction {
+    Code { name: String, copy: bool },
+    // `secret` is an otpauth:// URI or a bare base32 secret
+    Set { name: String, secret: String },
+    Remove { name: String },
+    Import { uri: String },
+}
+
+/// Two-factor secrets stored next to the entry passwords.
+pub struct OtpCmd {
+    pub action: OtpAction,
+}
+
+impl OtpCmd {
+    pub fn new(action: OtpAction) -> Self {
+        OtpCmd { action }
+    }
+
+    /// The entry the action works on, None for imports.
+    pub fn entry_name(&self) -> Option<&str> {
+        match &self.action {
+            OtpAction::Code { name, .. } | OtpAction::Set { name, .. } | OtpAction::Remove { name } => Some(name),
+            OtpAction::Import { .. } => None,
+        }
+    }
+
+    fn load(&self, context: &Context, name: &str) -> Result<Option<OtpSecret>, String> {
+        let sealed = context
+            .db
+            .get_otp_secret(name)
+            .map_err(|e| format!("Error reading the OTP secret: {}", e))?;
+        match sealed {
+            Some(sealed) => {
+                let uri = cipher::open(&context.vault_key()?, &sealed)?;
+                OtpSecret::parse(&uri).map(Some)
+            }
+            None => Ok(None),
+        }
+    }
+
+    fn store(&self, context: &Context, name: &str, otp: &OtpSecret) -> Result<(), String> {
+        let sealed = cipher::seal(&context.vault_key()?, &otp.to_uri())?;
+        context
+            .db
+            .set_otp_secret(name, Some(&sealed))
+            .map_err(|e| format!("Error saving the OTP secret: {}", e))
+    }
+
+    fn code(&self, context: &Context, name: &str, copy: bool) -> bool {
+        let mut otp = match self.load(context, name) {
+            Ok(Some(otp)) => otp,
+            Ok(None) => {
+                error!("{} has no OTP secret, add one with `kofl otp set {}`", name, name);
+                return false;
+            }
+            Err(e) => {
+                error!("{}", e);
+                return false;
+            }
+        };
+
+        let (code, valid_for) = match otp.kind {
+            OtpKind::Totp { .. } => {
+                let now = Utc::now().timestamp().max(0) as u64;
+                let (code, remaining) = otp.totp_at(now).unwrap();
+                (code, Some(remaining))
+            }
+            OtpKind::Hotp { counter } => {
+                // the counter moves on before the code is shown, a code is never handed out twice
+                otp.kind = OtpKind::Hotp { counter: counter + 1 };
+                if let Err(e) = self.store(context, name, &otp) {
+                    error!("{}", e);
+                    return false;
+                }
+                (otp.hotp(counter), None)
+            }
+        };
+
+        if copy {
+            let timeout = valid_for.unwrap_or(0) as u32;
+            let max_pastes = context.kgc.borrow().get_settings().clipboard_max_pastes().unwrap_or(0);
+            if let Err(e) = clipboard::copy(&code, timeout, max_pastes) {
+                error!("{}, run without --copy to show the code instead", e);
+                return false;
+            }
+            info!("Code is copied to clipboard");
+        } else {
+            println!("{}", code);
+        }
+
+        match (valid_for, otp.kind) {
+            (Some(seconds), _) => info!("Valid for {} more seconds", seconds),
+            (None, OtpKind::Hotp { counter }) => info!("HOTP code, the counter is now {}", counter),
+            (None, OtpKind::Totp { .. }) => {}
+        }
+        true
+    }
+
+    fn set(&self, context: &Context, name: &str, secret: &str) -> bool {
+        let otp = match OtpSecret::parse(secret) {
+            Ok(otp) => otp,
+            Err(e) => {
+                error!("{}", e);
+                return false;
+            }
+        };
+        if let Err(e) = self.store(context, name, &otp) {
+            error!("{}", e);
+            return false;
+        }
+        info!("OTP secret of {} saved ({}, {} digits)", name, otp.algorithm, otp.digits);
+        true
+    }
+
+    fn remove(&self, context: &Context, name: &str) -> bool {
+        if let Err(e) = context.db.set_otp_secret(name, None) {
+            error!("Error removing the OTP secret: {}", e);
+            return false;
+        }
+        info!("OTP secret of {} removed", name);
+        true
+    }
+
+    /// Attaches each account of a migration export to the entry named like its issuer or account.
+    fn import(&self, context: &Context, uri: &str) -> bool {
+        let secrets = match migration::parse_uri(uri) {
+            Ok(secrets) => secrets,
+            Err(e) => {
+                error!("{}", e);
+                return false;
+            }
+        };
+        let entries = match context.db.list_entries() {
+            Ok(entries) => entries,
+            Err(e) => {
+                error!("Error listing entries: {}", e);
+                return false;
+            }
+        };
+
+        let mut imported = 0;
+        for otp in &secrets {
+            let wanted: Vec<String> = [&otp.issuer, &otp.account].iter().filter_map(|s| s.as_ref()).map(|s| s.to_lowercase()).collect();
+            let matches: Vec<&str> = entries
+                .iter()
+                .map(|entry| entry.ent_name.as_str())
+                .filter(|name| {
+                    // "work/github" matches the issuer "GitHub"
+                    let short = name.rsplit('/').next().unwrap_or(name).to_lowercase();
+                    wanted.contains(&name.to_lowercase()) || wanted.contains(&short)
+                })
+                .collect();
+
+            let name = match matches.as_slice() {
+                [name] => *name,
+                [] => {
+                    warn!("Skipped {}: no entry with that name, add it and import again", otp.label());
+                    continue;
+                }
+                _ => {
+                    warn!("Skipped {}: it matches {}, set it with `kofl otp set`", otp.label(), matches.join(", "));
+                    continue;
+                }
+            };
+
+            match context.db.get_otp_secret(name) {
+                Ok(Some(_)) => {
+                    warn!("Skipped {}: {} already has an OTP secret", otp.label(), name);
+                    continue;
+                }
+                Ok(None) => {}
+                Err(e) => {
+                    error!("Error reading the OTP secret: {}", e);
+                    return false;
+                }
+            }
+
+            if let Err(e) = self.store(context, name, otp) {
+                error!("{}", e);
+                return false;
+            }
+            info!("Imported {} into {}", otp.label(), name);
+            imported += 1;
+        }
+
+        info!("{} of {} accounts imported", imported, secrets.len());
+        true
+    }
+}
+
+impl Sensitive for OtpCmd {
+    // like `kofl get --print`, a code on screen is sensitive but one in the clipboard is not,
+    // and replacing or removing a secret can lock the user out of the account
+    fn sensitivity(&self) -> Sensitivity {
+        match self.action {
+            OtpAction::Code { copy: true, .. } => Sensitivity::Normal,
+            OtpAction::Code { copy: false, .. }
+            | OtpAction::Set { .. }
+            | OtpAction::Remove { .. }
+            | OtpAction::Import { .. } => Sensitivity::Sensitive,
+        }
+    }
+}
+
+impl Command for OtpCmd {
+    fn execute(&self, context: &Context) -> bool {
+        let changed = match &self.action {
+            OtpAction::Code { name, copy } => return self.code(context, name, *copy),
+            OtpAction::Set { name, secret } => self.set(context, name, secret),
+            OtpAction::Remove { name } => self.remove(context, name),
+            OtpAction::Import { uri } => self.import(context, uri),
+        };
+        if !changed {
+            return false;
+        }
+
+        Backup::after_write(context);
+
+        true
+    }
+
+    fn validate(&self, context: &Context) -> bool {
+        let val_reg = ValidationRegistry::<OtpCmd>::new();
+
+        let val_checks = vec![
+            ValidationType::MasterKeyCheck,
+            ValidationType::SessionCheck,
+            ValidationType::EntryExistsCheck,
+            ValidationType::ReauthCheck,
+        ];
+
+        for a_check in val_checks {
+            match val_reg.validators.get(&a_check).unwrap().validate(context, &self) {
+                ValidationResult::Failure(msg) => {
+                    error!("{msg}");
+                    return false
+                },
+                ValidationResult::Warning(msg) => warn!("{msg}"),
+                ValidationResult::Success => debug!("test passed ✅")
+            }
+        }
+
+        true
+    }
+
+    fn display(&self) {
+        match &self.action {
+            OtpAction::Code { name, copy } => debug!("Otp command, code of {} (copy = {})", name, copy),
+            OtpAction::Set { name, .. } => debug!("Otp command, set the secret of {}", name),
+            OtpAction::Remove { name } => debug!("Otp command, remove the secret of {}", name),
+            OtpAction::Import { .. } => debug!("Otp command, import a migration export"),
+        }
+        ()
+    }
+}
@@ -0,0 +1,132 @@
+use crate::cli::Command;
+use crate::context::Context;
+use crate::policy::{PasswordPolicy, PolicyOverride};
+use crate::validator::core::{Sensitive, Sensitivity, ValidationResult, ValidationType};
+use crate::validator::registry::ValidationRegistry;
+use log::{debug, error, info, warn};
+
+pub enum PolicyAction {
+    Show(Option<String>),
+    Set { target: String, field: String, value: String },
+    Unset { target: String, field: Option<String> },
+}
+
+pub struct PolicyCmd {
+    pub action: PolicyAction,
+}
+
+impl PolicyCmd {
+    pub fn new(action: PolicyAction) -> Self {
+        PolicyCmd { action }
+    }
+
+    fn show(&self, target: &Option<String>, context: &Context) -> bool {
+        let kgc = context.kgc.borrow();
+        match target {
+            Some(target) => {
+                println!("{}", PasswordPolicy::for_entry(&kgc, target));
+            }
+            None => {
+                println!("{}", PasswordPolicy::from_settings(&kgc.get_settings()));
+                for (scope, policy_override) in kgc.get_policy_overrides() {
+                    let fields = toml::to_string(policy_override).unwrap_or_default();
+                    println!("\n[{}]\n{}", scope, fields.trim_end());
+                }
+            }
+        }
+        true
+    }
+
+    fn set(&self, target: &str, field: &str, value: &str, context: &Context) -> bool {
+        let mut kgc = context.kgc.borrow_mut();
+        let mut policy_override = kgc.get_policy_overrides().get(target).cloned().unwrap_or_default();
+
+        if let Err(e) = policy_override.set(field, value) {
+            error!("{}", e);
+            return false;
+        }
+
+        kgc.set_policy_override(target, Some(policy_override));
+        kgc.update();
+        info!("{} set to {} for {}", field, value, target);
+        true
+    }
+
+    fn unset(&self, target: &str, field: &Option<String>, context: &Context) -> bool {
+        let mut kgc = context.kgc.borrow_mut();
+        let mut policy_override: PolicyOverride = match kgc.get_policy_overrides().get(target) {
+            Some(policy_override) => policy_override.clone(),
+            None => {
+                warn!("No policy override for {}", target);
+                return true;
+            }
+        };
+
+        match field {
+            Some(field) => {
+                if let Err(e) = policy_override.unset(field) {
+                    error!("{}", e);
+                    return false;
+                }
+                kgc.set_policy_override(target, Some(policy_override));
+            }
+            None => kgc.set_policy_override(target, None),
+        }
+        kgc.update();
+        info!("Policy override updated for {}", target);
+        true
+    }
+}
+
+impl Sensitive for PolicyCmd {
+    // overrides can weaken the password rules
+    fn sensitivity(&self) -> Sensitivity {
+        match self.action {
+            PolicyAction::Show(_) => Sensitivity::Normal,
+            PolicyAction::Set { .. } | PolicyAction::Unset { .. } => Sensitivity::Sensitive,
+        }
+    }
+}
+
+impl Command for PolicyCmd {
+    fn execute(&self, context: &Context) -> bool {
+        match &self.action {
+            PolicyAction::Show(target) => self.show(target, context),
+            PolicyAction::Set { target, field, value } => self.set(target, field, value, context),
+            PolicyAction::Unset { target, field } => self.unset(target, field, context),
+        }
+    }
+
+    fn validate(&self, context: &Context) -> bool {
+        let val_reg = ValidationRegistry::<PolicyCmd>::new();
+
+        let val_checks = match self.action {
+            PolicyAction::Show(_) => vec![],
+            _ => vec![
+                ValidationType::MasterKeyCheck,
+                ValidationType::SessionCheck,
+                ValidationType::ReauthCheck,
+            ],
+        };
+
+        for a_check in val_checks {
+
+            match val_reg.validators.get(&a_check).unwrap().validate(context, &self) {
+                ValidationResult::Failure(msg) => {
+                    error!("{msg}");
+                    return false;
+                },
+                ValidationResult::Warning(msg) => warn!("{msg}"),
+                ValidationResult::Success => debug!("test passed ✅")
+
+            }
+        }
+
+        true
+    }
+
+    fn display(&self) {
+        debug!("Policy Command");
+        ()
+    }
+}
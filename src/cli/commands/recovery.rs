@@ -0,0 +1,203 @@
+use crate::cli::commands::ChangeMasterCmd;
+use crate::cli::Command;
+use crate::context::Context;
+use crate::recovery::{RecoveryKit, Share};
+use crate::two_factor::qr_code;
+use crate::validator::core::{Sensitive, Sensitivity, ValidationResult, ValidationType};
+use crate::validator::registry::ValidationRegistry;
+use log::{debug, error, info, warn};
+use std::path::{Path, PathBuf};
+
+pub enum RecoveryAction {
+    Split { shares: u8, threshold: u8, qr: bool, keyfile: Option<PathBuf> },
+    Combine,
+    Status,
+}
+
+/// Shamir recovery kit to reset a forgotten master password.
+pub struct RecoveryCmd {
+    pub action: RecoveryAction,
+}
+
+impl RecoveryCmd {
+    pub fn new(action: RecoveryAction) -> Self {
+        RecoveryCmd { action }
+    }
+
+    fn split(&self, context: &Context, shares: u8, threshold: u8, qr: bool) -> bool {
+        let vault_key = match context.vault_key() {
+            Ok(key) => key,
+            Err(e) => {
+                error!("{}", e);
+                return false;
+            }
+        };
+        let (kit, shares) = match RecoveryKit::create(&vault_key, shares, threshold) {
+            Ok(created) => created,
+            Err(e) => {
+                error!("{}", e);
+                return false;
+            }
+        };
+
+        if let Some(old) = context.kgc.borrow().get_recovery_kit() {
+            warn!("The shares of kit {} no longer work", old.kit_id);
+        }
+        println!(
+            "Recovery kit {}: any {} of these {} shares reset the master password. Give each to a different person or place.\n",
+            kit.kit_id,
+            kit.threshold,
+            shares.len()
+        );
+        for share in &shares {
+            println!("Share {} of {}:\n{}\n", share.number, kit.shares, share);
+            if qr {
+                match qr_code(&share.to_string()) {
+                    Ok(code) => println!("{}", code),
+                    Err(e) => warn!("Cannot draw the QR code: {}", e),
+                }
+            }
+        }
+
+        context.kgc.borrow_mut().set_recovery_kit(Some(kit));
+        context.kgc.borrow().update();
+        info!("Recovery kit saved, the shares are not stored anywhere by kofl");
+        true
+    }
+
+    fn read_shares(kit: &RecoveryKit) -> Option<Vec<Share>> {
+        let mut shares: Vec<Share> = Vec::new();
+        while shares.len() < kit.threshold as usize {
+            let input = rpassword::prompt_password(format!(
+                "Share {} of the {} needed (empty to stop) ===> ",
+                shares.len() + 1,
+                kit.threshold
+            ))
+            .unwrap();
+            if input.trim().is_empty() {
+                return None;
+            }
+            match input.parse::<Share>() {
+                Ok(share) if shares.iter().any(|s| s.number == share.number) => {
+                    warn!("Share {} was already given", share.number)
+                }
+                Ok(share) => shares.push(share),
+                Err(e) => warn!("{}", e),
+            }
+        }
+        Some(shares)
+    }
+
+    fn combine(&self, context: &Context) -> bool {
+        let kit = match context.kgc.borrow().get_recovery_kit() {
+            Some(kit) => kit.clone(),
+            None => {
+                error!("This vault has no recovery kit");
+                return false;
+            }
+        };
+
+        let shares = match RecoveryCmd::read_shares(&kit) {
+            Some(shares) => shares,
+            None => {
+                info!("Recovery aborted");
+                return false;
+            }
+        };
+        let vault_key = match kit.recover(&shares) {
+            Ok(key) => key,
+            Err(e) => {
+                error!("{}", e);
+                return false;
+            }
+        };
+        if !context.kgc.borrow().matches_vault_key(&vault_key) {
+            error!("The shares open the kit but not this vault");
+            return false;
+        }
+        context.unlock(vault_key);
+
+        ChangeMasterCmd::reset_forgotten(context)
+    }
+
+    fn status(&self, context: &Context) -> bool {
+        match context.kgc.borrow().get_recovery_kit() {
+            Some(kit) => println!(
+                "Recovery kit {} made on {}: {} of {} shares needed",
+                kit.kit_id,
+                kit.created_at.format("%Y-%m-%d"),
+                kit.threshold,
+                kit.shares
+            ),
+            None => println!("No recovery kit, `kofl recovery split` makes one"),
+        }
+        true
+    }
+}
+
+impl Sensitive for RecoveryCmd {
+    // the shares together hold the vault key
+    fn sensitivity(&self) -> Sensitivity {
+        match self.action {
+            RecoveryAction::Split { .. } => Sensitivity::Sensitive,
+            RecoveryAction::Combine | RecoveryAction::Status => Sensitivity::Normal,
+        }
+    }
+
+    fn keyfile(&self) -> Option<&Path> {
+        match &self.action {
+            RecoveryAction::Split { keyfile, .. } => keyfile.as_deref(),
+            RecoveryAction::Combine | RecoveryAction::Status => None,
+        }
+    }
+}
+
+impl Command for RecoveryCmd {
+    fn execute(&self, context: &Context) -> bool {
+        match &self.action {
+            RecoveryAction::Split { shares, threshold, qr, .. } => self.split(context, *shares, *threshold, *qr),
+            RecoveryAction::Combine => self.combine(context),
+            RecoveryAction::Status => self.status(context),
+        }
+    }
+
+    fn validate(&self, context: &Context) -> bool {
+        let val_reg = ValidationRegistry::<RecoveryCmd>::new();
+
+        // combining is for a forgotten password, it cannot need a session or wait out a lockout
+        let val_checks = match self.action {
+            RecoveryAction::Split { .. } => vec![
+                ValidationType::MasterKeyCheck,
+                ValidationType::RateLimitCheck,
+                ValidationType::SessionCheck,
+                ValidationType::ReauthCheck,
+            ],
+            RecoveryAction::Combine => vec![ValidationType::MasterKeyCheck],
+            RecoveryAction::Status => vec![ValidationType::MasterKeyCheck, ValidationType::SessionCheck],
+        };
+
+        for a_check in val_checks {
+            match val_reg.validators.get(&a_check).unwrap().validate(context, &self) {
+                ValidationResult::Failure(msg) => {
+                    error!("{msg}");
+                    return false
+                },
+                ValidationResult::Warning(msg) => warn!("{msg}"),
+                ValidationResult::Success => debug!("test passed ✅")
+            }
+        }
+
+        true
+    }
+
+    fn display(&self) {
+        match &self.action {
+            RecoveryAction::Split { shares, threshold, .. } => {
+                debug!("Recovery command, split into {} shares with threshold {}", shares, threshold)
+            }
+            RecoveryAction::Combine => debug!("Recovery command, combine"),
+            RecoveryAction::Status => debug!("Recovery command, status"),
+        }
+        ()
+    }
+}
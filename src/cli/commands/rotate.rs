@@ -0,0 +1,136 @@
+use crate::backup::Backup;
+use crate::cipher;
+use crate::cli::Command;
+use crate::clipboard;
+use crate::context::Context;
+use crate::db::Db::Entry;
+use crate::generator::GeneratorRules;
+use crate::validator::core::{Sensitive, Sensitivity, ValidationResult, ValidationType};
+use crate::validator::pwd_req::PasswordRequirementValidator;
+use crate::validator::registry::ValidationRegistry;
+use chrono::Utc;
+use log::{debug, error, info, warn};
+
+/// Replaces the password of an entry with one generated from its stored rules,
+/// the old password is kept in the entry history.
+pub struct RotateCmd {
+    pub name: String,
+    // print the new password instead of copying it
+    pub print: bool,
+}
+
+impl RotateCmd {
+    pub fn new(name: String, print: bool) -> Self {
+        RotateCmd { name, print }
+    }
+}
+
+impl Sensitive for RotateCmd {
+    // a printed password ends up in the terminal scrollback
+    fn sensitivity(&self) -> Sensitivity {
+        if self.print { Sensitivity::Sensitive } else { Sensitivity::Normal }
+    }
+}
+
+impl Command for RotateCmd {
+    fn execute(&self, context: &Context) -> bool {
+        let existing = match context.db.get_entry_by_name(&self.name) {
+            Ok(entry) => entry,
+            Err(e) => {
+                error!("Error retrieving entry by name: {}", e);
+                return false;
+            }
+        };
+
+        let password = match PasswordRequirementValidator::suggest(context, &self.name, &GeneratorRules::default()) {
+            Ok(password) => password,
+            Err(e) => {
+                error!("{}", e);
+                return false;
+            }
+        };
+
+        let master_key_hash = match context.vault_key() {
+            Ok(key) => key,
+            Err(e) => {
+                error!("{}", e);
+                return false;
+            }
+        };
+        let password_hash = match cipher::encrypt_password(&master_key_hash, &password) {
+            Ok(hash) => hash,
+            Err(e) => {
+                error!("{}", e);
+                return false;
+            }
+        };
+
+        // sealed with its own nonce, not kept as the keystream shared with the new password
+        let archived_password = match cipher::decrypt_password(&master_key_hash, &existing.password_hash)
+            .and_then(|old| cipher::seal(&master_key_hash, &old))
+        {
+            Ok(sealed) => sealed,
+            Err(e) => {
+                error!("Error archiving the old password: {}", e);
+                return false;
+            }
+        };
+
+        let now = Utc::now().to_rfc3339();
+
+        let rotated = Entry {
+            id: existing.id,
+            ent_name: existing.ent_name.clone(),
+            password_hash,
+            timestamp: now,
+            username: existing.username.clone(),
+            url: existing.url.clone(),
+        };
+        if let Err(e) = context.db.rotate_entry(&existing, &archived_password, rotated) {
+            error!("Error updating entry: {}", e);
+            return false;
+        }
+
+        info!("Password of {} rotated, the old one is archived", self.name);
+
+        Backup::after_write(context);
+
+        if self.print {
+            println!("New password: {}", password);
+        } else if let Err(e) = clipboard::copy_with_settings(&context.kgc.borrow().get_settings(), &password) {
+            error!("{}, use `kofl get {} --print` to show the new password", e, self.name);
+            return false;
+        }
+
+        true
+    }
+
+    fn validate(&self, context: &Context) -> bool {
+        let val_reg = ValidationRegistry::<RotateCmd>::new();
+
+        let val_checks = vec![
+            ValidationType::MasterKeyCheck,
+            ValidationType::SessionCheck,
+            ValidationType::EntryExistsCheck,
+            ValidationType::ReauthCheck,
+        ];
+
+        for a_check in val_checks {
+            match val_reg.validators.get(&a_check).unwrap().validate(context, &self) {
+                ValidationResult::Failure(msg) => {
+                    error!("{msg}");
+                    return false
+                },
+                ValidationResult::Warning(msg) => warn!("{msg}"),
+                ValidationResult::Success => debug!("test passed ✅")
+            }
+        }
+
+        true
+    }
+
+    fn display(&self) {
+        debug!("Rotate command with entry name = {}", self.name);
+        ()
+    }
+}
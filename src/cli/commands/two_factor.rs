@@ -0,0 +1,195 @@
+use crate::cli::commands::LogInCmd;
+use crate::cli::Command;
+use crate::context::Context;
+use crate::two_factor::{qr_code, Factor, TwoFactor};
+use crate::validator::core::{ValidationResult, ValidationType};
+use crate::validator::registry::ValidationRegistry;
+use chrono::Utc;
+use log::{debug, error, info, warn};
+use std::path::{Path, PathBuf};
+
+pub enum TwoFactorAction {
+    Enable,
+    // the key file is needed to check the master password of vaults bound to one
+    Disable { keyfile: Option<PathBuf> },
+    Status,
+}
+
+/// TOTP as a second factor at login, a gate in front of the master password
+/// rather than part of the vault key, see `crate::two_factor`.
+pub struct TwoFactorCmd {
+    pub action: TwoFactorAction,
+}
+
+impl TwoFactorCmd {
+    pub fn new(action: TwoFactorAction) -> Self {
+        TwoFactorCmd { action }
+    }
+
+    /// Shows a new seed and, once a code from it is typed back, saves it with its recovery codes.
+    pub fn enroll(context: &Context) -> bool {
+        let master_key_hash = match context.vault_key() {
+            Ok(key) => key,
+            Err(e) => {
+                error!("{}", e);
+                return false;
+            }
+        };
+        let username = context.kgc.borrow().get_user_login();
+        let (mut two_factor, seed, recovery_codes) = match TwoFactor::enroll(&master_key_hash, &username) {
+            Ok(enrollment) => enrollment,
+            Err(e) => {
+                error!("{}", e);
+                return false;
+            }
+        };
+
+        let uri = seed.to_uri();
+        match qr_code(&uri) {
+            Ok(qr) => println!("{}", qr),
+            Err(e) => warn!("Cannot draw the QR code: {}", e),
+        }
+        println!("Scan the code or add this URI to your authenticator app:\n{}\n", uri);
+
+        let code = rpassword::prompt_password("Enter the code shown by the app to confirm ===> ").unwrap();
+        let now = Utc::now().timestamp().max(0) as u64;
+        if let Err(e) = two_factor.verify(&master_key_hash, &code, now) {
+            error!("{}, two-factor authentication was not enabled", e);
+            return false;
+        }
+
+        println!("Recovery codes, each works once when the app is not at hand. Keep them somewhere safe:");
+        for code in &recovery_codes {
+            println!("    {}", code);
+        }
+
+        context.kgc.borrow_mut().set_two_factor(Some(two_factor));
+        context.kgc.borrow().update();
+        info!("Two-factor authentication enabled, `kofl login` now asks for a code");
+        info!("The code guards the login, a copy of the vault files opens without it");
+        true
+    }
+
+    /// Asks for the second factor when it is enabled, failures count as failed logins.
+    pub fn challenge(context: &Context) -> bool {
+        if context.kgc.borrow().get_two_factor().is_none() {
+            return true;
+        }
+
+        let input = rpassword::prompt_password("Enter the 2FA code or a recovery code ===> ").unwrap();
+        let master_key_hash = match context.vault_key() {
+            Ok(key) => key,
+            Err(e) => {
+                error!("{}", e);
+                return false;
+            }
+        };
+        let now = Utc::now().timestamp().max(0) as u64;
+        let result = context
+            .kgc
+            .borrow_mut()
+            .get_two_factor_mut()
+            .map(|two_factor| two_factor.verify(&master_key_hash, &input, now))
+            .unwrap();
+
+        match result {
+            Ok(Factor::Totp) => {}
+            Ok(Factor::RecoveryCode { remaining: 0 }) => {
+                warn!("That was the last recovery code, run `kofl 2fa disable` then `kofl 2fa enable` for new ones")
+            }
+            Ok(Factor::RecoveryCode { remaining }) => warn!("Recovery code used, {} left", remaining),
+            Err(e) => {
+                error!("{}", e);
+                let locked = context.kgc.borrow_mut().record_failed_login();
+                if locked {
+                    warn!("Too many failed attempts, login is locked for a while");
+                }
+                context.kgc.borrow().update();
+                return false;
+            }
+        }
+
+        // the used code or step is remembered
+        context.kgc.borrow().update();
+        true
+    }
+
+    fn disable(&self, context: &Context, keyfile: Option<&Path>) -> bool {
+        if context.kgc.borrow().get_two_factor().is_none() {
+            error!("Two-factor authentication is not enabled");
+            return false;
+        }
+
+        if LogInCmd::verify_master_password(context, keyfile).is_none() {
+            return false;
+        }
+
+        if !TwoFactorCmd::challenge(context) {
+            return false;
+        }
+
+        context.kgc.borrow_mut().set_two_factor(None);
+        context.kgc.borrow().update();
+        info!("Two-factor authentication disabled");
+        true
+    }
+
+    fn status(&self, context: &Context) -> bool {
+        match context.kgc.borrow().get_two_factor() {
+            Some(two_factor) => println!(
+                "Two-factor authentication is enabled, {} recovery code(s) left",
+                two_factor.recovery_codes_left()
+            ),
+            None => println!("Two-factor authentication is disabled, `kofl 2fa enable` turns it on"),
+        }
+        true
+    }
+}
+
+impl Command for TwoFactorCmd {
+    fn execute(&self, context: &Context) -> bool {
+        match &self.action {
+            TwoFactorAction::Enable => {
+                if context.kgc.borrow().get_two_factor().is_some() {
+                    error!("Two-factor authentication is already enabled, disable it first to get a new seed");
+                    return false;
+                }
+                TwoFactorCmd::enroll(context)
+            }
+            TwoFactorAction::Disable { keyfile } => self.disable(context, keyfile.as_deref()),
+            TwoFactorAction::Status => self.status(context),
+        }
+    }
+
+    fn validate(&self, context: &Context) -> bool {
+        let val_reg = ValidationRegistry::<TwoFactorCmd>::new();
+
+        // disabling asks for both factors again, so it works without a session
+        let val_checks = match self.action {
+            TwoFactorAction::Disable { .. } => vec![ValidationType::MasterKeyCheck, ValidationType::RateLimitCheck],
+            _ => vec![ValidationType::MasterKeyCheck, ValidationType::SessionCheck],
+        };
+
+        for a_check in val_checks {
+            match val_reg.validators.get(&a_check).unwrap().validate(context, &self) {
+                ValidationResult::Failure(msg) => {
+                    error!("{msg}");
+                    return false
+                },
+                ValidationResult::Warning(msg) => warn!("{msg}"),
+                ValidationResult::Success => debug!("test passed ✅")
+            }
+        }
+
+        true
+    }
+
+    fn display(&self) {
+        match self.action {
+            TwoFactorAction::Enable => debug!("2fa command, enable"),
+            TwoFactorAction::Disable { .. } => debug!("2fa command, disable"),
+            TwoFactorAction::Status => debug!("2fa command, status"),
+        }
+        ()
+    }
+}
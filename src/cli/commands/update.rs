@@ -1,11 +1,13 @@
 use crate::backup::Backup;
+use crate::clipboard;
 use crate::cli::Command;
-use crate::validator::core::{ValidationType, ValidationResult};
+use crate::validator::core::{Sensitive, Sensitivity, ValidationType, ValidationResult};
 use crate::validator::registry::ValidationRegistry;
 use std::fmt;
 use crate::errors::{ErrorExecution, ErrorValidation};
 use crate::context::Context;
 use crate::db::Db::Entry;
+use crate::generator::GeneratorRules;
 use chrono::prelude::*;
 use log::{debug, info, warn, error};
 use sha2::Digest;
@@ -22,14 +24,39 @@ pub struct UpdateCmd {
     pub password: String,
     pub suggest_flag: bool,
     pub suggested_pwd : Cell<String>,
+    pub generator_rules: GeneratorRules,
+    pub save_rules: bool,
+    // left unchanged when not given
+    pub username: Option<String>,
+    pub url: Option<String>,
+    // print the generated password instead of copying it
+    pub print: bool,
 }
 
 
 impl  UpdateCmd {
-    pub fn new(name: String, password: String,  suggest_flag: bool) -> UpdateCmd
+    pub fn new(name: String, password: String,  suggest_flag: bool, generator_rules: GeneratorRules, save_rules: bool, username: Option<String>, url: Option<String>, print: bool) -> UpdateCmd
     {
         UpdateCmd{name, password,suggest_flag,
-            suggested_pwd: Cell::new(String::new()),}
+            suggested_pwd: Cell::new(String::new()),
+            generator_rules, save_rules, username, url, print}
+    }
+
+    /// The password to store, the generated one once validation suggested it.
+    pub fn new_password(&self) -> String {
+        if !self.suggest_flag {
+            return self.password.clone();
+        }
+        let suggested = self.suggested_pwd.take();
+        self.suggested_pwd.set(suggested.clone());
+        suggested
+    }
+}
+
+impl Sensitive for UpdateCmd {
+    // a printed password ends up in the terminal scrollback
+    fn sensitivity(&self) -> Sensitivity {
+        if self.suggest_flag && self.print { Sensitivity::Sensitive } else { Sensitivity::Normal }
     }
 }
 
@@ -52,9 +79,12 @@ impl fmt::Debug for UpdateCmd {
 
 impl Command for UpdateCmd {
     fn execute(&self, context: &Context) -> bool  {
-        let master_key_hash = {
-            let kgc = context.kgc.borrow();
-            kgc.get_hashed_pwd()
+        let master_key_hash = match context.vault_key() {
+            Ok(key) => key,
+            Err(e) => {
+                error!("{}", e);
+                return false;
+            }
         };
 
         let master_key_bytes = match hex::decode(&master_key_hash) {
@@ -76,7 +106,7 @@ impl Command for UpdateCmd {
         let mut encrypted_password;
 
         if (self.suggest_flag) {
-            encrypted_password = self.suggested_pwd.take().into_bytes();
+            encrypted_password = self.new_password().into_bytes();
 
         }else {
             encrypted_password = self.password.clone().into_bytes();
@@ -87,20 +117,24 @@ impl Command for UpdateCmd {
         // Convert to hex for storage
         let encrypted_password_hex = hex::encode(encrypted_password);
 
-        let entry_id = match context.db.get_entry_by_name(&self.name) {
-            Ok(entry) => entry.id,
+        let existing = match context.db.get_entry_by_name(&self.name) {
+            Ok(entry) => entry,
             Err(e) => {
                 error!("Error retrieving entry by name: {}", e); // should shoul never happen as this check happened in validate
                 return false;
             }
         };
 
+        let entry_id = existing.id;
+
         // Create new entry with updated information
         let updated_entry = Entry {
             id: entry_id,
             ent_name: self.name.clone(),
             password_hash: encrypted_password_hex,
             timestamp: Utc::now().to_rfc3339(),
+            username: self.username.clone().or(existing.username),
+            url: self.url.clone().or(existing.url),
         };
 
         // Update the entry in the database
@@ -114,11 +148,33 @@ impl Command for UpdateCmd {
             }
         }
 
-        let bc = Backup::new().unwrap();
+        if self.save_rules {
+            let stored = context
+                .db
+                .get_generator_rules(&self.name)
+                .ok()
+                .flatten()
+                .and_then(|raw| GeneratorRules::from_toml(&raw).ok())
+                .unwrap_or_default();
+            let rules = stored.merge(&self.generator_rules);
+            if let Err(e) = context.db.set_generator_rules(&self.name, Some(&rules.to_toml())) {
+                error!("Error saving generator rules: {}", e);
+                return false;
+            }
+            info!("Generator rules saved for {}", self.name);
+        }
+
+        Backup::after_write(context);
 
-        bc.create_new_backup(&context.kgc.borrow().get_config_path(), 
-        &context.kgc.borrow().get_data_storage_path(), 
-        &context.kgc.borrow().get_config_path().with_extension("checksum")).unwrap();
+        if self.suggest_flag {
+            let suggested = self.suggested_pwd.take();
+            if self.print {
+                println!("Generated password: {}", suggested);
+            } else if let Err(e) = clipboard::copy_with_settings(&context.kgc.borrow().get_settings(), &suggested) {
+                error!("{}, use `kofl get {} --print` to show the generated password", e, self.name);
+                return false;
+            }
+        }
 
         true
     }
@@ -131,7 +187,10 @@ impl Command for UpdateCmd {
             ValidationType::MasterKeyCheck,
             ValidationType::SessionCheck,
             ValidationType::EntryExistsCheck,
+            ValidationType::ReauthCheck,
             ValidationType::PasswordRequirementCheck,
+            ValidationType::PasswordStrengthCheck,
+            ValidationType::BreachCheck,
         ];
 
 
@@ -0,0 +1,193 @@
+use crate::cli::commands::{DestroyCmd, InitCmd};
+use crate::cli::Command;
+use crate::context::Context;
+use crate::setting::registry::SettingsRegistry;
+use crate::validator::core::{Sensitive, Sensitivity, ValidationResult, ValidationType};
+use crate::validator::registry::ValidationRegistry;
+use crate::vault;
+use log::{debug, error, info, warn};
+use std::path::{Path, PathBuf};
+
+pub enum VaultAction {
+    Create { name: String, defaults: bool, config_file: Option<PathBuf>, two_factor: bool, keyfile: Option<PathBuf> },
+    List,
+    Use { name: String },
+    Delete { name: String, keyfile: Option<PathBuf> },
+}
+
+/// Manages the vaults, see `crate::vault`. The context of create and delete is
+/// the vault they name, main selects it before loading anything.
+pub struct VaultCmd {
+    pub action: VaultAction,
+}
+
+impl VaultCmd {
+    pub fn new(action: VaultAction) -> Self {
+        VaultCmd { action }
+    }
+
+    /// Runs `vault create`, which has no vault to load a context from. The name
+    /// is checked first, the directories and the context are made once it is free.
+    pub fn create_new(&self) -> bool {
+        let (name, init_command) = match &self.action {
+            VaultAction::Create { name, defaults, config_file, two_factor, keyfile } => {
+                (name, InitCmd::new(*defaults, config_file.clone(), *two_factor, keyfile.clone()))
+            }
+            VaultAction::List | VaultAction::Use { .. } | VaultAction::Delete { .. } => return false,
+        };
+        if let Err(e) = vault::check_new(name) {
+            error!("{}", e);
+            return false;
+        }
+        // a new vault starts from the defaults, so the file can be tried on them
+        if let Some(path) = &init_command.config_file {
+            if let Err(e) = InitCmd::apply_config_file(&mut SettingsRegistry::new(), path) {
+                error!("{}", e);
+                return false;
+            }
+        }
+
+        if let Err(e) = vault::create(name) {
+            error!("{}", e);
+            return false;
+        }
+        let created = match Context::new() {
+            Ok(context) => self.create(&context, name, init_command),
+            Err(e) => {
+                error!("Program terminated due to setup issues: {}", e);
+                false
+            }
+        };
+        // the directories were made above and hold no entries, so the name can be tried again
+        if !created {
+            if let Err(e) = vault::remove(name) {
+                warn!("{}", e);
+            }
+        }
+        created
+    }
+
+    fn create(&self, context: &Context, name: &str, init_command: InitCmd) -> bool {
+        if init_command.validate(context) && init_command.execute(context) {
+            info!("Vault {} created, use it with --vault {} or `kofl vault use {}`", name, name, name);
+            return true;
+        }
+        false
+    }
+
+    fn list(&self) -> bool {
+        let selected = vault::selected();
+        for name in vault::list() {
+            let paths = vault::paths(&name);
+            let state = if paths.config.exists() { "" } else { " (not initialized)" };
+            let marker = if name == selected { "*" } else { " " };
+            println!("{} {}{}  {}", marker, name, state, paths.config.display());
+        }
+        true
+    }
+
+    fn use_vault(&self, name: &str) -> bool {
+        if let Err(e) = vault::validate_name(name) {
+            error!("{}", e);
+            return false;
+        }
+        if !vault::exists(name) {
+            error!("No vault named {}, `kofl vault create {}` makes one", name, name);
+            return false;
+        }
+        if let Err(e) = vault::set_current(name) {
+            error!("{}", e);
+            return false;
+        }
+        info!("Using the vault {}, --vault and {} still override it", name, vault::VAULT_ENV);
+        true
+    }
+
+    fn delete(&self, context: &Context, name: &str) -> bool {
+        if name == vault::DEFAULT_VAULT {
+            error!("The default vault cannot be deleted, `kofl destroy` wipes it");
+            return false;
+        }
+        warn!("This deletes the entries and settings of the vault {}", name);
+        if !DestroyCmd::wipe(context) {
+            return false;
+        }
+        if let Err(e) = vault::remove(name) {
+            error!("{}", e);
+            return false;
+        }
+        info!("Vault {} deleted", name);
+        let backups = vault::paths(name).backups;
+        if backups.is_dir() {
+            info!("Its backups are kept in {}", backups.display());
+        }
+        true
+    }
+}
+
+impl Sensitive for VaultCmd {
+    // deleting wipes the vault, the master password is asked like for `kofl destroy`
+    fn sensitivity(&self) -> Sensitivity {
+        match self.action {
+            VaultAction::Delete { .. } => Sensitivity::Sensitive,
+            VaultAction::Create { .. } | VaultAction::List | VaultAction::Use { .. } => Sensitivity::Normal,
+        }
+    }
+
+    fn keyfile(&self) -> Option<&Path> {
+        match &self.action {
+            VaultAction::Delete { keyfile, .. } => keyfile.as_deref(),
+            VaultAction::Create { .. } | VaultAction::List | VaultAction::Use { .. } => None,
+        }
+    }
+}
+
+impl Command for VaultCmd {
+    fn execute(&self, context: &Context) -> bool {
+        match &self.action {
+            // main hands create to `create_new`, the vault has no context before it exists
+            VaultAction::Create { .. } => self.create_new(),
+            VaultAction::List => self.list(),
+            VaultAction::Use { name } => self.use_vault(name),
+            VaultAction::Delete { name, .. } => self.delete(context, name),
+        }
+    }
+
+    fn validate(&self, context: &Context) -> bool {
+        let val_reg = ValidationRegistry::<VaultCmd>::new();
+
+        // create checks its name in `create_new` and runs the checks of init itself
+        let val_checks = match self.action {
+            VaultAction::Create { .. } | VaultAction::List | VaultAction::Use { .. } => vec![],
+            VaultAction::Delete { .. } => vec![
+                ValidationType::MasterKeyCheck,
+                ValidationType::RateLimitCheck,
+                ValidationType::SessionCheck,
+                ValidationType::ReauthCheck,
+            ],
+        };
+
+        for a_check in val_checks {
+            match val_reg.validators.get(&a_check).unwrap().validate(context, &self) {
+                ValidationResult::Failure(msg) => {
+                    error!("{msg}");
+                    return false
+                },
+                ValidationResult::Warning(msg) => warn!("{msg}"),
+                ValidationResult::Success => debug!("test passed ✅")
+            }
+        }
+
+        true
+    }
+
+    fn display(&self) {
+        match &self.action {
+            VaultAction::Create { name, .. } => debug!("Vault command, create {}", name),
+            VaultAction::List => debug!("Vault command, list"),
+            VaultAction::Use { name } => debug!("Vault command, use {}", name),
+            VaultAction::Delete { name, .. } => debug!("Vault command, delete {}", name),
+        }
+        ()
+    }
+}
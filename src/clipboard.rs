@@ -0,0 +1,263 @@
+//! Copying passwords to the clipboard and clearing them again.
+//!
+//! On X11 and Wayland the clipboard content lives in the process that set it,
+//! so `kofl` exiting right after the copy would take the password with it. The
+//! copy is therefore handed to a detached `kofl clipboard-daemon` that serves it
+//! until the timeout, then clears it unless something else was copied since.
+//! The password reaches the daemon on stdin, never on its command line.
+//!
+//! With `clipboard_max_pastes` set, the daemon on X11 answers the paste requests
+//! itself and clears the clipboard after that many pastes, whichever of the two
+//! limits comes first. A clipboard manager reading each new selection counts as
+//! a paste too. Wayland and the other platforms only honour the timeout.
+
+use crate::setting::registry::SettingsRegistry;
+use arboard::Clipboard;
+use log::info;
+use std::io::{Read, Write};
+use std::process::{Command, Stdio};
+use std::time::{Duration, Instant};
+
+#[cfg(all(unix, not(any(target_os = "macos", target_os = "android", target_os = "emscripten"))))]
+use arboard::SetExtLinux;
+
+/// Name of the hidden subcommand running the daemon.
+pub const DAEMON_COMMAND: &str = "clipboard-daemon";
+
+/// Copies `password` for `timeout_secs` seconds or `max_pastes` pastes, 0 lifts either limit.
+pub fn copy(password: &str, timeout_secs: u32, max_pastes: u32) -> Result<(), String> {
+    // fail here rather than in the detached process when there is no clipboard at all
+    Clipboard::new().map_err(|e| format!("No clipboard available: {}", e))?;
+
+    let exe = std::env::current_exe().map_err(|e| format!("Cannot locate the kofl binary: {}", e))?;
+    let mut command = Command::new(exe);
+    command
+        .arg(DAEMON_COMMAND)
+        .arg("--timeout")
+        .arg(timeout_secs.to_string())
+        .arg("--pastes")
+        .arg(max_pastes.to_string())
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null());
+
+    // its own process group, so Ctrl-C in the terminal does not take it down
+    #[cfg(unix)]
+    {
+        use std::os::unix::process::CommandExt;
+        command.process_group(0);
+    }
+
+    let mut child = command.spawn().map_err(|e| format!("Cannot start the clipboard helper: {}", e))?;
+    let mut stdin = child.stdin.take().ok_or("Cannot talk to the clipboard helper")?;
+    stdin
+        .write_all(password.as_bytes())
+        .map_err(|e| format!("Cannot talk to the clipboard helper: {}", e))?;
+    Ok(())
+}
+
+/// Copies `password` with the clipboard limits of `settings` and tells the user when it is cleared.
+pub fn copy_with_settings(settings: &SettingsRegistry, password: &str) -> Result<(), String> {
+    let timeout = settings.clipboard_timeout().unwrap_or(45);
+    let max_pastes = settings.clipboard_max_pastes().unwrap_or(0);
+    copy(password, timeout, max_pastes)?;
+    match (timeout, max_pastes) {
+        (0, 0) => info!("Password is copied to clipboard"),
+        (_, 0) => info!("Password is copied to clipboard, it will be cleared in {} seconds", timeout),
+        (0, _) => info!("Password is copied to clipboard, it will be cleared after {} pastes", max_pastes),
+        _ => info!(
+            "Password is copied to clipboard, it will be cleared after {} pastes or {} seconds",
+            max_pastes, timeout
+        ),
+    }
+    Ok(())
+}
+
+/// Body of the daemon: reads the password from stdin, owns the clipboard, then clears it.
+pub fn serve(timeout_secs: u32, max_pastes: u32) -> Result<(), String> {
+    let mut password = String::new();
+    std::io::stdin().read_to_string(&mut password).map_err(|e| e.to_string())?;
+
+    let deadline = (timeout_secs > 0).then(|| Instant::now() + Duration::from_secs(timeout_secs as u64));
+    if max_pastes > 0 {
+        if let Some(result) = hold_counted(&password, deadline, max_pastes) {
+            return result;
+        }
+    }
+
+    let mut clipboard = Clipboard::new().map_err(|e| e.to_string())?;
+    hold(&mut clipboard, &password, deadline)?;
+    if deadline.is_none() {
+        return Ok(());
+    }
+
+    // someone copied something else in the meantime, leave it alone
+    if clipboard.get_text().ok().as_deref() == Some(password.as_str()) {
+        clipboard.clear().map_err(|e| e.to_string())?;
+    }
+    Ok(())
+}
+
+/// Serves `password` until `deadline`, returning early once another program takes the clipboard.
+#[cfg(all(unix, not(any(target_os = "macos", target_os = "android", target_os = "emscripten"))))]
+fn hold(clipboard: &mut Clipboard, password: &str, deadline: Option<Instant>) -> Result<(), String> {
+    let set = clipboard.set();
+    let set = match deadline {
+        Some(deadline) => set.wait_until(deadline),
+        None => set.wait(),
+    };
+    set.text(password.to_string()).map_err(|e| e.to_string())
+}
+
+/// The clipboard keeps its content on its own here, the daemon only has to wait.
+#[cfg(not(all(unix, not(any(target_os = "macos", target_os = "android", target_os = "emscripten")))))]
+fn hold(clipboard: &mut Clipboard, password: &str, deadline: Option<Instant>) -> Result<(), String> {
+    clipboard.set_text(password.to_string()).map_err(|e| e.to_string())?;
+    let deadline = match deadline {
+        Some(deadline) => deadline,
+        None => return Ok(()),
+    };
+    while Instant::now() < deadline {
+        std::thread::sleep(Duration::from_millis(500));
+        if clipboard.get_text().ok().as_deref() != Some(password) {
+            break;
+        }
+    }
+    Ok(())
+}
+
+/// Serves `password` over X11 and clears it after `max_pastes` pastes or at `deadline`.
+/// Gives `None` without an X server, so the caller falls back to the timeout alone.
+#[cfg(all(unix, not(any(target_os = "macos", target_os = "android", target_os = "emscripten"))))]
+fn hold_counted(password: &str, deadline: Option<Instant>, max_pastes: u32) -> Option<Result<(), String>> {
+    let (conn, screen) = x11rb::connect(None).ok()?;
+    Some(x11::serve(&conn, screen, password, deadline, max_pastes).map_err(|e| e.to_string()))
+}
+
+/// Pastes cannot be counted here, only the timeout applies.
+#[cfg(not(all(unix, not(any(target_os = "macos", target_os = "android", target_os = "emscripten")))))]
+fn hold_counted(_password: &str, _deadline: Option<Instant>, _max_pastes: u32) -> Option<Result<(), String>> {
+    None
+}
+
+/// Owning the CLIPBOARD selection by hand, since arboard does not tell when it is read.
+#[cfg(all(unix, not(any(target_os = "macos", target_os = "android", target_os = "emscripten"))))]
+mod x11 {
+    use std::error::Error;
+    use std::time::{Duration, Instant};
+    use x11rb::connection::Connection;
+    use x11rb::protocol::xproto::{
+        Atom, AtomEnum, ConnectionExt as _, CreateWindowAux, EventMask, PropMode, SelectionNotifyEvent,
+        SelectionRequestEvent, WindowClass, SELECTION_NOTIFY_EVENT,
+    };
+    use x11rb::protocol::Event;
+    use x11rb::rust_connection::RustConnection;
+    use x11rb::wrapper::ConnectionExt as _;
+    use x11rb::{COPY_DEPTH_FROM_PARENT, CURRENT_TIME, NONE};
+
+    struct Atoms {
+        clipboard: Atom,
+        targets: Atom,
+        utf8_string: Atom,
+    }
+
+    pub fn serve(
+        conn: &RustConnection,
+        screen: usize,
+        password: &str,
+        deadline: Option<Instant>,
+        max_pastes: u32,
+    ) -> Result<(), Box<dyn Error>> {
+        let root = conn.setup().roots[screen].root;
+        let window = conn.generate_id()?;
+        conn.create_window(
+            COPY_DEPTH_FROM_PARENT,
+            window,
+            root,
+            0,
+            0,
+            1,
+            1,
+            0,
+            WindowClass::INPUT_OUTPUT,
+            0,
+            &CreateWindowAux::new(),
+        )?;
+        let atoms = Atoms {
+            clipboard: conn.intern_atom(false, b"CLIPBOARD")?.reply()?.atom,
+            targets: conn.intern_atom(false, b"TARGETS")?.reply()?.atom,
+            utf8_string: conn.intern_atom(false, b"UTF8_STRING")?.reply()?.atom,
+        };
+
+        conn.set_selection_owner(window, atoms.clipboard, CURRENT_TIME)?;
+        if conn.get_selection_owner(atoms.clipboard)?.reply()?.owner != window {
+            return Err("Cannot take over the clipboard".into());
+        }
+        conn.flush()?;
+
+        let mut pastes = 0;
+        while pastes < max_pastes && deadline.map_or(true, |deadline| Instant::now() < deadline) {
+            match conn.poll_for_event()? {
+                Some(Event::SelectionRequest(request)) => {
+                    if answer(conn, &atoms, &request, password)? {
+                        pastes += 1;
+                    }
+                }
+                // another program took the clipboard, nothing of ours is left to clear
+                Some(Event::SelectionClear(_)) => return Ok(()),
+                Some(_) => {}
+                None => std::thread::sleep(Duration::from_millis(20)),
+            }
+        }
+
+        conn.set_selection_owner(NONE, atoms.clipboard, CURRENT_TIME)?;
+        conn.destroy_window(window)?;
+        conn.flush()?;
+        Ok(())
+    }
+
+    /// Replies to one selection request, telling whether it handed out the password.
+    fn answer(
+        conn: &RustConnection,
+        atoms: &Atoms,
+        request: &SelectionRequestEvent,
+        password: &str,
+    ) -> Result<bool, Box<dyn Error>> {
+        // obsolete clients leave the property out and expect the target to be used
+        let property = if request.property == NONE { request.target } else { request.property };
+        let text_targets = [atoms.utf8_string, AtomEnum::STRING.into()];
+
+        let (served, pasted) = if request.selection != atoms.clipboard {
+            (false, false)
+        } else if request.target == atoms.targets {
+            let mut targets = vec![atoms.targets];
+            targets.extend(text_targets);
+            conn.change_property32(PropMode::REPLACE, request.requestor, property, AtomEnum::ATOM, &targets)?;
+            (true, false)
+        } else if text_targets.contains(&request.target) {
+            conn.change_property8(
+                PropMode::REPLACE,
+                request.requestor,
+                property,
+                request.target,
+                password.as_bytes(),
+            )?;
+            (true, true)
+        } else {
+            (false, false)
+        };
+
+        let notify = SelectionNotifyEvent {
+            response_type: SELECTION_NOTIFY_EVENT,
+            sequence: 0,
+            time: request.time,
+            requestor: request.requestor,
+            selection: request.selection,
+            target: request.target,
+            property: if served { property } else { NONE },
+        };
+        conn.send_event(false, request.requestor, EventMask::NO_EVENT, notify)?;
+        conn.flush()?;
+        Ok(pasted)
+    }
+}
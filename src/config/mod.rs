@@ -1,10 +1,19 @@
 pub mod Config {
 
-    use crate::backup::Backup;
-    use crate::utils::Utils::{check_existing_config, get_config_path, get_home_dir};
+    use crate::backup::{Backup, BackupKey};
+    use crate::kdf;
+    use crate::layout;
+    use crate::policy::PolicyOverride;
+    use crate::setting::registry::SettingsRegistry;
+    use crate::setting::setting_key::SettingKey;
+    use crate::setting::setting_value::SettingValue;
+    use crate::recovery::RecoveryKit;
+    use crate::two_factor::TwoFactor;
+    use chrono::{DateTime, Utc};
     use log::{debug, error, info};
     use serde::{Deserialize, Serialize};
     use sha2::{Digest, Sha256};
+    use std::collections::BTreeMap;
     use std::env;
     use std::fmt::Debug;
     use std::fs;
@@ -12,7 +21,7 @@ pub mod Config {
     use std::path::PathBuf;
     use toml;
 
-    #[derive(Serialize, Deserialize)]
+    #[derive(Serialize, Deserialize, Clone)]
     pub struct KoflGlobalConfig {
         config_path: PathBuf,
         data_storage_path: PathBuf,
@@ -21,15 +30,43 @@ pub mod Config {
         salt: String,
         hashed_pwd: String,
         master_key_provided: bool,
+        // PBKDF2 rounds behind the vault key, 0 for vaults using the legacy single sha256
+        #[serde(default)]
+        kdf_iterations: u32,
+        // hashed_pwd is `kdf::key_verifier` of the vault key, false for configs
+        // written before that which kept the key itself, see `matches_vault_key`
+        #[serde(default)]
+        key_verifier: bool,
+        // the vault key also depends on a key file, see `keyfile`
+        #[serde(default)]
+        keyfile: bool,
+        #[serde(default)]
+        failed_login_attempts: u32,
+        #[serde(default)]
+        locked_until: Option<DateTime<Utc>>,
+        #[serde(default)]
+        backup_key: Option<BackupKey>,
+        // user tunable settings, only the ones that differ from the default are stored
+        #[serde(default)]
+        settings: BTreeMap<String, String>,
+        // password policy overrides keyed by folder (`name/`) or entry name
+        #[serde(default)]
+        policy_overrides: BTreeMap<String, PolicyOverride>,
+        // TOTP required at login when set
+        #[serde(default)]
+        two_factor: Option<TwoFactor>,
+        // Shamir recovery kit for a forgotten master password
+        #[serde(default)]
+        recovery_kit: Option<RecoveryKit>,
     }
 
     impl KoflGlobalConfig {
         pub fn new() -> KoflGlobalConfig {
-            let home_dir = get_home_dir().expect("Home directory not found");
+            let paths = crate::vault::active();
             let key = "USER";
             KoflGlobalConfig {
-                config_path: home_dir.join(".kofl"),
-                data_storage_path: home_dir.join("kofl.sqlite"),
+                config_path: paths.config,
+                data_storage_path: paths.storage,
                 user_id: String::from("1234567"),
                 username: match env::var(key) {
                     Ok(val) => val,
@@ -38,6 +75,16 @@ pub mod Config {
                 salt: String::from(""),
                 hashed_pwd: String::from(""),
                 master_key_provided: false,
+                kdf_iterations: 0,
+                key_verifier: true,
+                failed_login_attempts: 0,
+                locked_until: None,
+                backup_key: None,
+                settings: BTreeMap::new(),
+                policy_overrides: BTreeMap::new(),
+                two_factor: None,
+                recovery_kit: None,
+                keyfile: false,
             }
         }
 
@@ -80,6 +127,26 @@ pub mod Config {
             self.hashed_pwd.clone()
         }
 
+        /// Stores a verifier of `key`, the salt it was derived with must be set first.
+        pub fn set_vault_key(&mut self, key: &str) {
+            self.hashed_pwd = kdf::key_verifier(key, &self.salt);
+            self.key_verifier = true;
+        }
+
+        /// Whether `key` is the vault key, also for configs that still hold the key itself.
+        pub fn matches_vault_key(&self, key: &str) -> bool {
+            if self.key_verifier {
+                kdf::key_verifier(key, &self.salt) == self.hashed_pwd
+            } else {
+                key == self.hashed_pwd
+            }
+        }
+
+        /// False for configs from before the verifier, `kofl login` upgrades them.
+        pub fn has_key_verifier(&self) -> bool {
+            self.key_verifier
+        }
+
         pub fn set_master_key_provided(&mut self, is_set: bool) {
             self.master_key_provided = true;
         }
@@ -87,6 +154,111 @@ pub mod Config {
             self.master_key_provided
         }
 
+        pub fn set_kdf_iterations(&mut self, iterations: u32) {
+            self.kdf_iterations = iterations;
+        }
+        pub fn get_kdf_iterations(&self) -> u32 {
+            self.kdf_iterations
+        }
+
+        pub fn set_keyfile(&mut self, required: bool) {
+            self.keyfile = required;
+        }
+        pub fn has_keyfile(&self) -> bool {
+            self.keyfile
+        }
+
+        /// Derives the vault key from a candidate master password and key file digest, see `matches_vault_key`.
+        pub fn hash_master_password(&self, password: &str, keyfile_digest: Option<&str>) -> String {
+            kdf::derive_master_hash(&kdf::with_keyfile(password, keyfile_digest), &self.salt, self.kdf_iterations)
+        }
+
+        pub fn get_settings(&self) -> SettingsRegistry {
+            SettingsRegistry::from_values(&self.settings)
+        }
+
+        pub fn set_setting(&mut self, key: SettingKey, value: &SettingValue) {
+            self.settings.insert(key.to_string(), value.to_string());
+        }
+
+        pub fn reset_setting(&mut self, key: SettingKey) {
+            self.settings.remove(&key.to_string());
+        }
+
+        pub fn reset_all_settings(&mut self) {
+            self.settings.clear();
+        }
+
+        pub fn get_policy_overrides(&self) -> &BTreeMap<String, PolicyOverride> {
+            &self.policy_overrides
+        }
+
+        /// Stores the override for `target`, `None` or an empty override removes it.
+        pub fn set_policy_override(&mut self, target: &str, policy_override: Option<PolicyOverride>) {
+            match policy_override {
+                Some(policy_override) if !policy_override.is_empty() => {
+                    self.policy_overrides.insert(target.to_string(), policy_override);
+                }
+                _ => {
+                    self.policy_overrides.remove(target);
+                }
+            }
+        }
+
+        /// Counts a failed master password attempt, and locks the vault once
+        /// `max_login_attempts` is reached. Returns true if the vault is now locked.
+        pub fn record_failed_login(&mut self) -> bool {
+            let settings = self.get_settings();
+            let max_attempts = settings.max_login_attempts().unwrap_or(5);
+            let lockout_minutes = settings.failed_login_lockout_duration().unwrap_or(15);
+
+            self.failed_login_attempts += 1;
+            if self.failed_login_attempts >= max_attempts {
+                self.failed_login_attempts = 0;
+                self.locked_until = Some(Utc::now() + chrono::Duration::minutes(lockout_minutes as i64));
+                return true;
+            }
+            false
+        }
+
+        pub fn reset_failed_logins(&mut self) {
+            self.failed_login_attempts = 0;
+            self.locked_until = None;
+        }
+
+        pub fn get_failed_login_attempts(&self) -> u32 {
+            self.failed_login_attempts
+        }
+
+        /// Returns the end of the lockout if one is in effect.
+        pub fn get_locked_until(&self) -> Option<DateTime<Utc>> {
+            self.locked_until.filter(|until| *until > Utc::now())
+        }
+
+        pub fn set_backup_key(&mut self, backup_key: Option<BackupKey>) {
+            self.backup_key = backup_key;
+        }
+        pub fn get_backup_key(&self) -> Option<&BackupKey> {
+            self.backup_key.as_ref()
+        }
+
+        pub fn set_two_factor(&mut self, two_factor: Option<TwoFactor>) {
+            self.two_factor = two_factor;
+        }
+        pub fn get_two_factor(&self) -> Option<&TwoFactor> {
+            self.two_factor.as_ref()
+        }
+        pub fn get_two_factor_mut(&mut self) -> Option<&mut TwoFactor> {
+            self.two_factor.as_mut()
+        }
+
+        pub fn set_recovery_kit(&mut self, recovery_kit: Option<RecoveryKit>) {
+            self.recovery_kit = recovery_kit;
+        }
+        pub fn get_recovery_kit(&self) -> Option<&RecoveryKit> {
+            self.recovery_kit.as_ref()
+        }
+
         pub fn get_config_checksum(&self) -> String {
             let content =
                 fs::read_to_string(self.get_config_path()).unwrap_or_else(|_| String::new());
@@ -146,7 +318,7 @@ pub mod Config {
         }
 
         pub fn load(&mut self) {
-            if !check_existing_config() {
+            if !self.get_config_path().exists() {
                 debug!("no existing config");
                 self.write_config_to_toml_file();
                 return;
@@ -166,7 +338,7 @@ pub mod Config {
                 );
 
                 // I should later decide what's gonna happen if no backup found.
-                let bc = Backup::new().unwrap();
+                let bc = Backup::from_settings(&self.get_settings()).unwrap();
 
                 match bc.get_last_backup().unwrap() {
                     Some(backup_dir_path) => {
@@ -211,7 +383,14 @@ pub mod Config {
 
             match self.read_config_from_toml_file() {
                 Ok(config) => {
+                    // the paths in the file are stale when it was moved, e.g. out of the home directory
+                    let (config_path, data_storage_path) = (self.config_path.clone(), self.data_storage_path.clone());
                     *self = config;
+                    if self.config_path != config_path || self.data_storage_path != data_storage_path {
+                        self.config_path = config_path;
+                        self.data_storage_path = data_storage_path;
+                        self.update();
+                    }
                     // create a backup for now this is only for testing;
                     // let bc = Backup::new().unwrap();
                     // bc.create_new_backup(&self.get_config_path(), &self.get_data_storage_path(), &self.get_config_path().with_extension("checksum"));
@@ -228,7 +407,38 @@ pub mod Config {
             // Save checksum
             let checksum = self.get_config_checksum();
             let checksum_path = self.get_config_path().with_extension("checksum");
-            fs::write(checksum_path, checksum).expect("Failed to write checksum file");
+            layout::write_private(&checksum_path, checksum).expect("Failed to write checksum file");
+        }
+
+        /// Writes the config and its checksum beside the current files, for changes
+        /// that must not land before something else commits, see `install_staged`.
+        pub fn stage(&self) -> Result<(), String> {
+            let toml_str = toml::to_string(self).map_err(|e| e.to_string())?;
+            let checksum = hex::encode(Sha256::digest(toml_str.as_bytes()));
+            layout::write_private(&self.staged_path("toml"), &toml_str)
+                .and_then(|_| layout::write_private(&self.staged_path("checksum"), checksum))
+                .map_err(|e| format!("Cannot write the new config: {}", e))
+        }
+
+        /// Moves the files written by `stage` over the current ones.
+        pub fn install_staged(&self) -> Result<(), String> {
+            for (staged, current) in [
+                (self.staged_path("toml"), self.config_path.clone()),
+                (self.staged_path("checksum"), self.config_path.with_extension("checksum")),
+            ] {
+                fs::rename(&staged, &current)
+                    .map_err(|e| format!("Cannot move {} to {}: {}", staged.display(), current.display(), e))?;
+            }
+            Ok(())
+        }
+
+        pub fn discard_staged(&self) {
+            let _ = fs::remove_file(self.staged_path("toml"));
+            let _ = fs::remove_file(self.staged_path("checksum"));
+        }
+
+        fn staged_path(&self, extension: &str) -> PathBuf {
+            self.config_path.with_extension(format!("{}.new", extension))
         }
 
         pub fn serialize_to_toml(&self) -> String {
@@ -239,7 +449,7 @@ pub mod Config {
             let toml_str = self.serialize_to_toml();
             debug!("toml str =\n{}", toml_str);
             let config_pth = &self.config_path;
-            fs::write(config_pth, toml_str).expect("could not create toml file for config");
+            layout::write_private(config_pth, toml_str).expect("could not create toml file for config");
         }
 
         pub fn read_config_from_toml_file(
@@ -296,6 +506,9 @@ pub mod Config {
 #[cfg(test)]
 mod tests {
     use super::Config::KoflGlobalConfig;
+    use crate::kdf;
+    use crate::setting::setting_key::SettingKey;
+    use crate::setting::setting_value::SettingValue;
     use serial_test::serial;
     use std::path::PathBuf;
     use std::{env, fs};
@@ -387,6 +600,8 @@ mod tests {
     fn test_verify_integrity() {
         let _guard = EnvGuard::new("USER");
         let temp_dir = TempDir::new().expect("Failed to create temp dir");
+        let kofl_home_guard = EnvGuard::new("KOFL_HOME");
+        kofl_home_guard.set_var(temp_dir.path().to_str().unwrap());
 
         let config = KoflGlobalConfig::new();
 
@@ -594,6 +809,48 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_vault_key_is_not_stored() {
+        let mut config = KoflGlobalConfig::new();
+        config.set_salt("salt".to_string());
+        let key = kdf::derive_master_hash("password", "salt", 1000);
+        config.set_vault_key(&key);
+
+        assert_ne!(config.get_hashed_pwd(), key);
+        assert!(config.has_key_verifier());
+        assert!(config.matches_vault_key(&key));
+        assert!(!config.matches_vault_key(&kdf::derive_master_hash("wrong", "salt", 1000)));
+
+        // configs written before the verifier kept the key itself
+        config.set_master_key_hash(key.clone());
+        let legacy = toml::to_string(&config).unwrap().replace("key_verifier = true", "key_verifier = false");
+        let legacy: KoflGlobalConfig = toml::from_str(&legacy).unwrap();
+        assert!(!legacy.has_key_verifier());
+        assert!(legacy.matches_vault_key(&key));
+    }
+
+    #[test]
+    fn test_staged_config_replaces_the_current_one() {
+        let temp_dir = TempDir::new().expect("Failed to create temp dir");
+        let mut config = create_test_config(&temp_dir);
+        config.set_salt("old".to_string());
+        config.update();
+        let checksum_path = config.get_config_path().with_extension("checksum");
+
+        config.set_salt("new".to_string());
+        config.stage().unwrap();
+        config.discard_staged();
+        assert!(fs::read_to_string(config.get_config_path()).unwrap().contains("salt = \"old\""));
+        assert_eq!(fs::read_dir(temp_dir.path()).unwrap().count(), 2);
+
+        config.stage().unwrap();
+        assert!(fs::read_to_string(config.get_config_path()).unwrap().contains("salt = \"old\""));
+        config.install_staged().unwrap();
+        assert!(fs::read_to_string(config.get_config_path()).unwrap().contains("salt = \"new\""));
+        assert_eq!(fs::read_to_string(&checksum_path).unwrap(), config.get_config_checksum());
+        assert_eq!(fs::read_dir(temp_dir.path()).unwrap().count(), 2);
+    }
+
     #[test]
     fn test_master_key_provided_flag() {
         // Arrange
@@ -664,15 +921,16 @@ mod tests {
     }
 
     #[test]
+    #[serial]
     fn test_security_data_consistency() {
-        // Set up environment - we need both USER and HOME
+        // Set up environment - we need both USER and KOFL_HOME
         let user_guard = EnvGuard::new("USER");
-        let home_guard = EnvGuard::new("HOME");
+        let kofl_home_guard = EnvGuard::new("KOFL_HOME");
 
         let temp_dir = TempDir::new().expect("Failed to create temp dir");
 
-        // Set HOME to our temp directory
-        home_guard.set_var(temp_dir.path().to_str().unwrap());
+        // Set KOFL_HOME to our temp directory
+        kofl_home_guard.set_var(temp_dir.path().to_str().unwrap());
         user_guard.set_var("lk-kheir");
 
         // Create initial config with temp directory
@@ -712,6 +970,42 @@ mod tests {
         );
     }
 
+    #[test]
+    #[serial]
+    fn test_settings_persistence() {
+        let _guard = setup_test_env();
+        let temp_dir = TempDir::new().expect("Failed to create temp dir");
+        let mut config = create_test_config(&temp_dir);
+
+        config.set_setting(SettingKey::SessionDuration, &SettingValue::UnsignedInteger(45));
+        config.set_setting(SettingKey::RequireSpecialChars, &SettingValue::Boolean(false));
+        config.update();
+
+        let mut new_config = create_test_config(&temp_dir);
+        new_config.load();
+        let settings = new_config.get_settings();
+        assert_eq!(settings.session_duration(), Some(45));
+        assert_eq!(settings.require_special_chars(), Some(false));
+        assert_eq!(settings.max_login_attempts(), Some(5), "Untouched settings keep their default");
+
+        new_config.reset_setting(SettingKey::SessionDuration);
+        assert_eq!(new_config.get_settings().session_duration(), Some(30));
+    }
+
+    #[test]
+    fn test_failed_logins_lock_the_vault() {
+        let mut config = KoflGlobalConfig::new();
+        config.set_setting(SettingKey::MaxLoginAttempts, &SettingValue::UnsignedInteger(3));
+
+        assert!(!config.record_failed_login());
+        assert!(!config.record_failed_login());
+        assert!(config.get_locked_until().is_none());
+
+        assert!(config.record_failed_login(), "Third failure should lock the vault");
+        assert!(config.get_locked_until().is_some());
+        assert_eq!(config.get_failed_login_attempts(), 0);
+    }
+
     #[test]
     fn test_invalid_security_operations() {
         // Arrange
@@ -751,12 +1045,12 @@ mod tests {
     #[serial]
     fn test_load_with_existing_config() {
         let user_guard = EnvGuard::new("USER");
-        let home_guard = EnvGuard::new("HOME");
+        let kofl_home_guard = EnvGuard::new("KOFL_HOME");
 
         let temp_dir = TempDir::new().expect("Failed to create temp dir");
 
-        // Set HOME to our temp directory and USER to test user
-        home_guard.set_var(temp_dir.path().to_str().unwrap());
+        // Set KOFL_HOME to our temp directory and USER to test user
+        kofl_home_guard.set_var(temp_dir.path().to_str().unwrap());
         user_guard.set_var("lk-kheir");
 
         // Arrange
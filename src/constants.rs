@@ -2,8 +2,9 @@ pub struct CONS;
 
 impl CONS {
     pub const MIN_PASSWORD_LENGTH: usize = 8;
-    pub const PASSWORD_UPPERCASE_REQ: &str = ".*[A-Z].*";
-    pub const PASSWORD_LOWERCASE_REQ: &str = ".*[a-z].*";
-    pub const PASSWORD_DIGIT_REQ: &str = ".*[0-9].*";
-    pub const PASSWORD_SPECIAL_CHAR_REQ: &str = ".*[!@#\\$%\\^&\\*].*";
+    pub const BACKUP_DIR_NAME_FORMAT: &str = "%Y-%m-%d_%H_%M_%S";
+    pub const BACKUP_DAILY_GENERATIONS: usize = 7;
+    pub const BACKUP_WEEKLY_GENERATIONS: usize = 4;
+    pub const BACKUP_KDF_ITERATIONS: u32 = 600_000;
+    pub const MASTER_KDF_ITERATIONS: u32 = 600_000;
 }
\ No newline at end of file
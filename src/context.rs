@@ -16,6 +16,10 @@ pub struct Context {
     pub kgc: RefCell<KoflGlobalConfig>,
     pub ss: Session,
     pub db: Database,
+    // vault key from a master password entered by this command, see `vault_key`
+    unlocked: RefCell<Option<String>>,
+    // the master password itself once checked, see `master_password`
+    entered_password: RefCell<Option<String>>,
 }
 
 impl Context {
@@ -45,64 +49,61 @@ impl Context {
             Err(_) => String::from("default_user"),
         };
         
-        let mut allow_recreation_of_session = false;
-        let mut session;
-        
-        
-        if (c.borrow().is_master_key_provided()) {
-            allow_recreation_of_session = true;
-            session = Session::new(user_login.clone(), true);
-        }
-        else {
-            session = Session::new(user_login.clone(), false);
-        }
-        
+        let (session_duration, idle_timeout) = {
+            let settings = c.borrow().get_settings();
+            let idle_timeout = if settings.auto_lock_on_idle().unwrap_or(true) {
+                Some(settings.idle_timeout().unwrap_or(5))
+            } else {
+                None
+            };
+            (settings.session_duration().unwrap_or(30), idle_timeout)
+        };
 
+        // only `kofl login` and `kofl init` start an active session, a missing or
+        // unreadable session file never counts as logged in
+        let mut session = Session::new(user_login.clone(), false, session_duration);
+        session.set_idle_timeout(idle_timeout);
 
         match session.load() {
             Ok(_) => {
                 debug!("Successfully loaded the session file.");
             }
-            Err(SessionError::SessionFileMissingError) => {
-                debug!("Session config file missing, creating a new session.");
-                if allow_recreation_of_session {
-                    session = Session::new(user_login, true);
-                    session.write_session_config_to_toml_file();
-                }else {
-                    session = Session::new(user_login, false);
-                    session.write_session_config_to_toml_file(); 
-                }
-            }
-            Err(SessionError::FailedLoadingError) => {
-                debug!("Failed to load the session details, creating a new session.");
-                if allow_recreation_of_session {
-                    session = Session::new(user_login, true);
-                    session.write_session_config_to_toml_file();
-                }else {
-                    session = Session::new(user_login, false);
-                    session.write_session_config_to_toml_file(); 
-                }
-            }
             Err(SessionError::ExpiredSession) => {
-                // warn!("Session expired");
-                // if the session is expired we should ask the user to login again.
-                // return Err(ErrorSetup::Session);
-                // session = Session::new(user_login);
-                // session.write_session_config_to_toml_file();
+                debug!("Session expired or locked.");
             }
             Err(_) => {
-                // warn!("No existing session, creating a new session.");
-                if allow_recreation_of_session {
-                    session = Session::new(user_login, true);
-                    session.write_session_config_to_toml_file();
-                }else {
-                    session = Session::new(user_login, false);
-                    session.write_session_config_to_toml_file(); 
-                }
+                debug!("No usable session file, creating an inactive session.");
+                session = Session::new(user_login, false, session_duration);
+                session.set_idle_timeout(idle_timeout);
+                session.write_session_config_to_toml_file();
             }
         }
 
         // Return the new Context
-        Ok(Context { kgc: c, db: dbase, ss: session })
+        Ok(Context { kgc: c, db: dbase, ss: session, unlocked: RefCell::new(None), entered_password: RefCell::new(None) })
+    }
+
+    /// Hex key of the unlocked vault, what entries, the 2FA seed and archives are encrypted with.
+    /// It comes from the master password typed by this command, or else from the session,
+    /// the config only keeps a verifier.
+    pub fn vault_key(&self) -> Result<String, String> {
+        if let Some(key) = self.unlocked.borrow().clone() {
+            return Ok(key);
+        }
+        self.ss.vault_key().ok_or_else(|| "The vault is locked, run `kofl login` first".to_string())
+    }
+
+    /// Keeps `key` for the rest of this command, once checked against the config.
+    pub fn unlock(&self, key: String) {
+        *self.unlocked.borrow_mut() = Some(key);
+    }
+
+    /// The master password if this command already had it checked, e.g. by `ReauthValidator`.
+    pub fn master_password(&self) -> Option<String> {
+        self.entered_password.borrow().clone()
+    }
+
+    pub fn remember_master_password(&self, password: String) {
+        *self.entered_password.borrow_mut() = Some(password);
     }
 }
\ No newline at end of file
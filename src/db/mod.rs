@@ -4,6 +4,23 @@ pub mod Db {
     
 
 
+    // schema changes applied in order on top of the initial table,
+    // PRAGMA user_version records how many of them ran
+    const MIGRATIONS: &[&str] = &[
+        "ALTER TABLE entry ADD COLUMN generator_rules TEXT;",
+        "ALTER TABLE entry ADD COLUMN username TEXT; ALTER TABLE entry ADD COLUMN url TEXT;",
+        "CREATE TABLE IF NOT EXISTS entry_history (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            ent_name TEXT NOT NULL,
+            password_hash TEXT NOT NULL,
+            timestamp TEXT NOT NULL,
+            archived_at TEXT NOT NULL
+        );",
+        "ALTER TABLE entry ADD COLUMN otp_secret TEXT;",
+        // rows archived before it share the keystream of the password that replaced them
+        "ALTER TABLE entry_history ADD COLUMN sealed INTEGER NOT NULL DEFAULT 0;",
+    ];
+
     #[warn(unused_variables)]
     #[warn(unused_imports)]
 
@@ -14,6 +31,8 @@ pub mod Db {
     impl Database {
         pub fn new(path :&PathBuf) -> Result<Self, rusqlite::Error>
         {
+            // a failure here shows up again when SQLite opens the file
+            let _ = crate::layout::create_private_file(path);
             Ok(Database {
                 connection: Connection::open(path)?
             })
@@ -30,37 +49,50 @@ pub mod Db {
                 );
                 "
             )?;
+            self.migrate()
+        }
+
+        fn migrate(&self) -> Result<(), rusqlite::Error> {
+            let version: usize = self.connection.query_row("PRAGMA user_version", [], |row| row.get(0))?;
+            for (index, migration) in MIGRATIONS.iter().enumerate().skip(version) {
+                self.connection.execute_batch(migration)?;
+                self.connection.pragma_update(None, "user_version", index + 1)?;
+            }
             Ok(())
         }
         pub fn add_entry(&self, entry: Entry) -> Result<(), rusqlite::Error> {
             self.connection.execute(
-                "INSERT INTO entry (ent_name, password_hash, timestamp) VALUES (?1, ?2, ?3)",
-                params![entry.ent_name, entry.password_hash, entry.timestamp],
+                "INSERT INTO entry (ent_name, password_hash, timestamp, username, url) VALUES (?1, ?2, ?3, ?4, ?5)",
+                params![entry.ent_name, entry.password_hash, entry.timestamp, entry.username, entry.url],
             )?;
             Ok(())
         }
 
         pub fn get_entry_by_name(&self, ent_name: &str) -> Result<Entry, rusqlite::Error> {
-            let mut stmt = self.connection.prepare("SELECT id, ent_name, password_hash, timestamp FROM entry WHERE ent_name = ?1")?;
+            let mut stmt = self.connection.prepare("SELECT id, ent_name, password_hash, timestamp, username, url FROM entry WHERE ent_name = ?1")?;
             let entry = stmt.query_row(params![ent_name], |row| {
                 Ok(Entry {
                     id: row.get(0)?,
                     ent_name: row.get(1)?,
                     password_hash: row.get(2)?,
                     timestamp: row.get(3)?,
+                    username: row.get(4)?,
+                    url: row.get(5)?,
                 })
             })?;
             Ok(entry)
         }
 
         pub fn get_entry(&self, id: u32) -> Result<Entry, rusqlite::Error> {
-            let mut stmt = self.connection.prepare("SELECT id, ent_name, password_hash, timestamp FROM entry WHERE id = ?1")?;
+            let mut stmt = self.connection.prepare("SELECT id, ent_name, password_hash, timestamp, username, url FROM entry WHERE id = ?1")?;
             let entry = stmt.query_row(params![id], |row| {
                 Ok(Entry {
                     id: row.get(0)?,
                     ent_name: row.get(1)?,
                     password_hash: row.get(2)?,
                     timestamp: row.get(3)?,
+                    username: row.get(4)?,
+                    url: row.get(5)?,
                 })
             })?;
             Ok(entry)
@@ -68,8 +100,8 @@ pub mod Db {
 
         pub fn update_entry(&self, id: u32, entry: Entry) -> Result<(), rusqlite::Error> {
             self.connection.execute(
-                "UPDATE entry SET ent_name = ?1, password_hash = ?2, timestamp = ?3 WHERE id = ?4",
-                params![entry.ent_name, entry.password_hash, entry.timestamp, id],
+                "UPDATE entry SET ent_name = ?1, password_hash = ?2, timestamp = ?3, username = ?4, url = ?5 WHERE id = ?6",
+                params![entry.ent_name, entry.password_hash, entry.timestamp, entry.username, entry.url, id],
             )?;
             Ok(())
         }
@@ -86,14 +118,111 @@ pub mod Db {
 
         }
 
+        pub fn get_generator_rules(&self, ent_name: &str) -> Result<Option<String>, rusqlite::Error> {
+            self.connection.query_row(
+                "SELECT generator_rules FROM entry WHERE ent_name = ?1",
+                params![ent_name],
+                |row| row.get(0),
+            )
+        }
+
+        pub fn set_generator_rules(&self, ent_name: &str, rules: Option<&str>) -> Result<(), rusqlite::Error> {
+            self.connection.execute(
+                "UPDATE entry SET generator_rules = ?1 WHERE ent_name = ?2",
+                params![rules, ent_name],
+            )?;
+            Ok(())
+        }
+
+        /// The sealed `otpauth://` URI of the entry, see `cipher::seal`.
+        pub fn get_otp_secret(&self, ent_name: &str) -> Result<Option<String>, rusqlite::Error> {
+            self.connection.query_row(
+                "SELECT otp_secret FROM entry WHERE ent_name = ?1",
+                params![ent_name],
+                |row| row.get(0),
+            )
+        }
+
+        pub fn set_otp_secret(&self, ent_name: &str, sealed: Option<&str>) -> Result<(), rusqlite::Error> {
+            self.connection.execute(
+                "UPDATE entry SET otp_secret = ?1 WHERE ent_name = ?2",
+                params![sealed, ent_name],
+            )?;
+            Ok(())
+        }
+
+        /// Replaces `existing` with `rotated` and keeps the old password in the history,
+        /// both or neither. `archived_password` is the old password sealed with
+        /// `cipher::seal`, the `password_hash` of the entry would share the keystream
+        /// of the new one.
+        pub fn rotate_entry(&self, existing: &Entry, archived_password: &str, rotated: Entry) -> Result<(), rusqlite::Error> {
+            let tx = self.connection.unchecked_transaction()?;
+            tx.execute(
+                "INSERT INTO entry_history (ent_name, password_hash, timestamp, archived_at, sealed) VALUES (?1, ?2, ?3, ?4, 1)",
+                params![existing.ent_name, archived_password, existing.timestamp, rotated.timestamp],
+            )?;
+            tx.execute(
+                "UPDATE entry SET ent_name = ?1, password_hash = ?2, timestamp = ?3, username = ?4, url = ?5 WHERE id = ?6",
+                params![rotated.ent_name, rotated.password_hash, rotated.timestamp, rotated.username, rotated.url, existing.id],
+            )?;
+            tx.commit()
+        }
+
+        /// Rewrites every encrypted column in one transaction, for when the vault key changes.
+        /// `password` converts a `password_hash` value, `secret` a sealed `otp_secret` or
+        /// history row. Nothing is committed unless `before_commit` succeeds.
+        pub fn reencrypt<P, S, C>(&self, password: P, secret: S, before_commit: C) -> Result<(), String>
+        where
+            P: Fn(&str) -> Result<String, String>,
+            S: Fn(&str) -> Result<String, String>,
+            C: FnOnce() -> Result<(), String>,
+        {
+            let tx = self.connection.unchecked_transaction().map_err(|e| e.to_string())?;
+
+            let rows: Vec<(u32, String, Option<String>)> = {
+                let mut stmt = tx.prepare("SELECT id, password_hash, otp_secret FROM entry").map_err(|e| e.to_string())?;
+                let rows = stmt
+                    .query_map([], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)))
+                    .map_err(|e| e.to_string())?;
+                rows.collect::<Result<_, _>>().map_err(|e| e.to_string())?
+            };
+            for (id, password_hash, otp_secret) in rows {
+                let otp_secret = otp_secret.as_deref().map(&secret).transpose()?;
+                tx.execute(
+                    "UPDATE entry SET password_hash = ?1, otp_secret = ?2 WHERE id = ?3",
+                    params![password(&password_hash)?, otp_secret, id],
+                )
+                .map_err(|e| e.to_string())?;
+            }
+
+            let history: Vec<(u32, String, bool)> = {
+                let mut stmt = tx.prepare("SELECT id, password_hash, sealed FROM entry_history").map_err(|e| e.to_string())?;
+                let rows = stmt.query_map([], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?))).map_err(|e| e.to_string())?;
+                rows.collect::<Result<_, _>>().map_err(|e| e.to_string())?
+            };
+            for (id, password_hash, sealed) in history {
+                let converted = if sealed { secret(&password_hash)? } else { password(&password_hash)? };
+                tx.execute(
+                    "UPDATE entry_history SET password_hash = ?1 WHERE id = ?2",
+                    params![converted, id],
+                )
+                .map_err(|e| e.to_string())?;
+            }
+
+            before_commit()?;
+            tx.commit().map_err(|e| e.to_string())
+        }
+
         pub fn list_entries(&self) -> Result<Vec<Entry>, rusqlite::Error> {
-            let mut stmt = self.connection.prepare("SELECT id, ent_name, password_hash, timestamp FROM entry")?;
+            let mut stmt = self.connection.prepare("SELECT id, ent_name, password_hash, timestamp, username, url FROM entry")?;
             let entry_iter = stmt.query_map([], |row| {
                 Ok(Entry {
                     id: row.get(0)?,
                     ent_name: row.get(1)?,
                     password_hash: row.get(2)?,
                     timestamp: row.get(3)?,
+                    username: row.get(4)?,
+                    url: row.get(5)?,
                 })
             })?;
     
@@ -110,6 +239,8 @@ pub mod Db {
         pub ent_name: String, 
         pub password_hash: String,
         pub timestamp: String,
+        pub username: Option<String>,
+        pub url: Option<String>,
     }
 
     impl Entry {
@@ -119,7 +250,9 @@ pub mod Db {
                 id,
                 ent_name,
                 password_hash,
-                timestamp: String::from("12-12-12")
+                timestamp: String::from("12-12-12"),
+                username: None,
+                url: None,
             }
         }
     }
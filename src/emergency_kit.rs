@@ -0,0 +1,227 @@
+//! Printable emergency kit.
+//!
+//! A single HTML file, made locally and meant to be printed, with what is
+//! needed to get back into the vault: where it lives, the KDF parameters,
+//! fresh 2FA recovery codes and optionally the vault key sealed with a
+//! separate kit passphrase, also drawn as a QR code.
+//!
+//! The same data is embedded as JSON so `kofl emergency-kit verify` can read
+//! the file back and check it still matches the vault.
+//!
+//! The config only keeps a verifier of the vault key, so the sealed key is the
+//! one copy of the key outside a session. It stands in for the master password
+//! and the key file: the kit and its passphrase together decrypt the vault
+//! files and any archive made with the master password.
+
+use crate::cipher;
+use crate::kdf;
+use chrono::{DateTime, Utc};
+use qrcode::render::svg;
+use qrcode::QrCode;
+use rand::distributions::Alphanumeric;
+use rand::{thread_rng, Rng};
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+const FORMAT_VERSION: u32 = 1;
+const DATA_TAG: &str = r#"<script type="application/json" id="kofl-emergency-kit">"#;
+const SEALED_KEY_PREFIX: &str = "KOFLKEY1";
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct EmergencyKit {
+    pub version: u32,
+    pub created_at: DateTime<Utc>,
+    pub user: String,
+    pub config_path: PathBuf,
+    pub storage_path: PathBuf,
+    pub backup_dir: Option<PathBuf>,
+    pub kdf_salt: String,
+    pub kdf_iterations: u32,
+    pub keyfile: bool,
+    pub two_factor: bool,
+    #[serde(default)]
+    pub recovery_codes: Vec<String>,
+    pub recovery_kit_id: Option<String>,
+    pub sealed_key: Option<SealedKey>,
+}
+
+/// The vault key sealed with a key derived from the kit passphrase, as strong as that passphrase.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct SealedKey {
+    pub salt: String,
+    pub iterations: u32,
+    pub sealed: String,
+}
+
+impl SealedKey {
+    pub fn new(vault_key_hex: &str, passphrase: &str, iterations: u32) -> Result<SealedKey, String> {
+        let salt: String = thread_rng().sample_iter(&Alphanumeric).take(16).map(char::from).collect();
+        let key = kdf::derive_master_hash(passphrase, &salt, iterations);
+        Ok(SealedKey { sealed: cipher::seal(&key, vault_key_hex)?, salt, iterations })
+    }
+
+    /// The vault key, if `passphrase` is the kit passphrase.
+    pub fn open(&self, passphrase: &str) -> Result<String, String> {
+        let key = kdf::derive_master_hash(passphrase, &self.salt, self.iterations);
+        cipher::open(&key, &self.sealed).map_err(|_| "Wrong kit passphrase".to_string())
+    }
+
+    /// One line form, printed and put in the QR code.
+    pub fn to_text(&self) -> String {
+        format!("{}:{}:{}:{}", SEALED_KEY_PREFIX, self.salt, self.iterations, self.sealed)
+    }
+}
+
+impl EmergencyKit {
+    pub fn new(user: String, config_path: PathBuf, storage_path: PathBuf, kdf_salt: String, kdf_iterations: u32) -> Self {
+        EmergencyKit {
+            version: FORMAT_VERSION,
+            created_at: Utc::now(),
+            user,
+            config_path,
+            storage_path,
+            backup_dir: None,
+            kdf_salt,
+            kdf_iterations,
+            keyfile: false,
+            two_factor: false,
+            recovery_codes: Vec::new(),
+            recovery_kit_id: None,
+            sealed_key: None,
+        }
+    }
+
+    pub fn to_html(&self) -> Result<String, String> {
+        let mut rows = vec![
+            ("Made on", self.created_at.format("%Y-%m-%d %H:%M UTC").to_string()),
+            ("User", self.user.clone()),
+            ("Config file", self.config_path.display().to_string()),
+            ("Vault database", self.storage_path.display().to_string()),
+        ];
+        if let Some(backup_dir) = &self.backup_dir {
+            rows.push(("Backups", backup_dir.display().to_string()));
+        }
+        rows.push(("KDF", format!("PBKDF2-HMAC-SHA256, {} iterations", self.kdf_iterations)));
+        rows.push(("KDF salt", self.kdf_salt.clone()));
+        rows.push(("Key file", if self.keyfile { "required, keep a copy apart from this kit" } else { "not used" }.to_string()));
+        rows.push(("Two-factor unlock", if self.two_factor { "enabled" } else { "disabled" }.to_string()));
+        if let Some(kit_id) = &self.recovery_kit_id {
+            rows.push(("Shamir recovery kit", format!("{}, see `kofl recovery combine`", kit_id)));
+        }
+
+        let mut body = String::new();
+        body.push_str("<h1>kofl emergency kit</h1>\n");
+        body.push_str("<p>Keep this sheet somewhere safe, such as a locked drawer or a safe. ");
+        body.push_str("Anyone holding it and the kit passphrase can take over the vault.</p>\n");
+        body.push_str("<h2>Vault</h2>\n<table>\n");
+        for (label, value) in rows {
+            body.push_str(&format!("<tr><th>{}</th><td>{}</td></tr>\n", label, escape(&value)));
+        }
+        body.push_str("</table>\n");
+
+        if !self.recovery_codes.is_empty() {
+            body.push_str("<h2>2FA recovery codes</h2>\n<p>Each code replaces the authenticator once.</p>\n<ol class=\"codes\">\n");
+            for code in &self.recovery_codes {
+                body.push_str(&format!("<li>{}</li>\n", escape(code)));
+            }
+            body.push_str("</ol>\n");
+        }
+
+        if let Some(sealed_key) = &self.sealed_key {
+            let text = sealed_key.to_text();
+            let code = QrCode::new(text.as_bytes()).map_err(|e| e.to_string())?;
+            let image = code.render::<svg::Color>().min_dimensions(240, 240).build();
+            // the XML declaration is not allowed inside HTML
+            let image = image.split_once("?>").map(|(_, rest)| rest).unwrap_or(&image);
+            body.push_str("<h2>Sealed vault key</h2>\n");
+            body.push_str("<p>The vault key, encrypted with the kit passphrase. ");
+            body.push_str("<code>kofl emergency-kit restore</code> with this file and the passphrase sets a new master password.</p>\n");
+            body.push_str("<p>With the passphrase it opens the vault without the master password or the key file. ");
+            body.push_str("Never keep the passphrase with this kit.</p>\n");
+            body.push_str(&format!("<div class=\"qr\">{}</div>\n<pre>{}</pre>\n", image, escape(&text)));
+        }
+
+        body.push_str("<h2>Checking this kit</h2>\n<p>Run <code>kofl emergency-kit verify</code> with this file ");
+        body.push_str("after any master password, key file or 2FA change. A kit that fails the check must be replaced.</p>\n");
+
+        // "</" cannot appear inside the script element, "\/" is the same string in JSON
+        let data = serde_json::to_string(self).map_err(|e| e.to_string())?.replace("</", "<\\/");
+        Ok(format!(
+            concat!(
+                "<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n",
+                "<title>kofl emergency kit - {}</title>\n<style>\n",
+                "body {{ font-family: sans-serif; max-width: 46em; margin: 2em auto; }}\n",
+                "th {{ text-align: left; padding-right: 1em; vertical-align: top; }}\n",
+                "pre, .codes {{ font-family: monospace; font-size: 1.1em; word-break: break-all; white-space: pre-wrap; }}\n",
+                ".qr svg {{ width: 6cm; height: 6cm; }}\n",
+                "</style>\n</head>\n<body>\n{}{}{}</script>\n</body>\n</html>\n"
+            ),
+            escape(&self.user),
+            body,
+            DATA_TAG,
+            data
+        ))
+    }
+
+    /// Reads back a kit written by `to_html`.
+    pub fn from_html(html: &str) -> Result<EmergencyKit, String> {
+        let start = html.find(DATA_TAG).ok_or("Not a kofl emergency kit")? + DATA_TAG.len();
+        let end = html[start..].find("</script>").ok_or("The emergency kit is truncated")? + start;
+        let kit: EmergencyKit =
+            serde_json::from_str(&html[start..end]).map_err(|e| format!("The emergency kit is damaged: {}", e))?;
+        if kit.version > FORMAT_VERSION {
+            return Err(format!("The emergency kit format {} is newer than this kofl", kit.version));
+        }
+        Ok(kit)
+    }
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;").replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const VAULT_KEY: &str = "000102030405060708090a0b0c0d0e0f101112131415161718191a1b1c1d1e1f";
+
+    fn kit() -> EmergencyKit {
+        let mut kit = EmergencyKit::new(
+            "alice".to_string(),
+            PathBuf::from("/home/alice/.kofl"),
+            PathBuf::from("/home/alice/</script><b>kofl.sqlite"),
+            "saltsaltsaltsalt".to_string(),
+            1000,
+        );
+        kit.two_factor = true;
+        kit.recovery_codes = vec!["abcde-fghjk".to_string()];
+        kit
+    }
+
+    #[test]
+    fn test_html_round_trip() {
+        let kit = kit();
+        let html = kit.to_html().unwrap();
+        assert!(html.contains("abcde-fghjk"));
+        assert!(html.contains("&lt;/script&gt;&lt;b&gt;kofl"));
+        assert_eq!(html.matches("</script>").count(), 1);
+        assert_eq!(EmergencyKit::from_html(&html).unwrap(), kit);
+
+        assert!(EmergencyKit::from_html("<html></html>").is_err());
+        assert!(EmergencyKit::from_html(&html[..html.len() / 2]).is_err());
+    }
+
+    #[test]
+    fn test_sealed_key() {
+        let mut kit = kit();
+        kit.sealed_key = Some(SealedKey::new(VAULT_KEY, "correct horse battery", 1000).unwrap());
+        let html = kit.to_html().unwrap();
+        assert!(html.contains("<svg"));
+
+        let sealed_key = EmergencyKit::from_html(&html).unwrap().sealed_key.unwrap();
+        assert_eq!(sealed_key.open("correct horse battery").unwrap(), VAULT_KEY);
+        assert!(sealed_key.open("wrong horse battery").is_err());
+        assert!(sealed_key.to_text().starts_with("KOFLKEY1:"));
+    }
+}
@@ -0,0 +1,436 @@
+//! Random password generation.
+//!
+//! Characters are drawn from the OS CSPRNG with `gen_range`, which rejects
+//! out-of-range samples instead of reducing modulo the alphabet size, so every
+//! character of the alphabet is equally likely. Besides random characters the
+//! generator builds diceware passphrases and pronounceable passwords, see the
+//! `passphrase` and `pronounceable` modules.
+
+mod passphrase;
+mod pronounceable;
+
+use crate::policy::PasswordPolicy;
+use crate::setting::registry::SettingsRegistry;
+use rand::rngs::OsRng;
+use rand::seq::SliceRandom;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+
+const LOWERCASE: &str = "abcdefghijklmnopqrstuvwxyz";
+const UPPERCASE: &str = "ABCDEFGHIJKLMNOPQRSTUVWXYZ";
+const DIGITS: &str = "0123456789";
+const AMBIGUOUS: &str = "0O1lI|";
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum Strategy {
+    Random,
+    Passphrase,
+    Pronounceable,
+}
+
+impl FromStr for Strategy {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "random" => Ok(Strategy::Random),
+            "passphrase" => Ok(Strategy::Passphrase),
+            "pronounceable" => Ok(Strategy::Pronounceable),
+            _ => Err(format!("Unknown strategy '{}', expected random, passphrase or pronounceable", s)),
+        }
+    }
+}
+
+impl fmt::Display for Strategy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Strategy::Random => write!(f, "random"),
+            Strategy::Passphrase => write!(f, "passphrase"),
+            Strategy::Pronounceable => write!(f, "pronounceable"),
+        }
+    }
+}
+
+/// Fully resolved generator settings.
+#[derive(Debug, Clone, PartialEq)]
+pub struct GeneratorOptions {
+    pub length: usize,
+    pub lowercase: bool,
+    pub uppercase: bool,
+    pub digits: bool,
+    pub symbols: bool,
+    pub symbol_set: String,
+    pub exclude_ambiguous: bool,
+    pub min_lowercase: usize,
+    pub min_uppercase: usize,
+    pub min_digits: usize,
+    pub min_symbols: usize,
+    // replaces the character classes entirely when set
+    pub alphabet: Option<String>,
+    pub strategy: Strategy,
+    // passphrase only
+    pub words: usize,
+    pub separator: String,
+    // passphrase and pronounceable: capitalize the words, add one digit / symbol
+    pub capitalize: bool,
+    pub add_digit: bool,
+    pub add_symbol: bool,
+}
+
+/// Partial options, used for command line flags and for the rules stored on an entry.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct GeneratorRules {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub length: Option<usize>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub lowercase: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub uppercase: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub digits: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub symbols: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub symbol_set: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub exclude_ambiguous: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub min_lowercase: Option<usize>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub min_uppercase: Option<usize>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub min_digits: Option<usize>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub min_symbols: Option<usize>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub alphabet: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub strategy: Option<Strategy>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub words: Option<usize>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub separator: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub capitalize: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub add_digit: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub add_symbol: Option<bool>,
+}
+
+impl GeneratorRules {
+    /// `other` wins wherever it sets a field.
+    pub fn merge(&self, other: &GeneratorRules) -> GeneratorRules {
+        GeneratorRules {
+            length: other.length.or(self.length),
+            lowercase: other.lowercase.or(self.lowercase),
+            uppercase: other.uppercase.or(self.uppercase),
+            digits: other.digits.or(self.digits),
+            symbols: other.symbols.or(self.symbols),
+            symbol_set: other.symbol_set.clone().or_else(|| self.symbol_set.clone()),
+            exclude_ambiguous: other.exclude_ambiguous.or(self.exclude_ambiguous),
+            min_lowercase: other.min_lowercase.or(self.min_lowercase),
+            min_uppercase: other.min_uppercase.or(self.min_uppercase),
+            min_digits: other.min_digits.or(self.min_digits),
+            min_symbols: other.min_symbols.or(self.min_symbols),
+            alphabet: other.alphabet.clone().or_else(|| self.alphabet.clone()),
+            strategy: other.strategy.or(self.strategy),
+            words: other.words.or(self.words),
+            separator: other.separator.clone().or_else(|| self.separator.clone()),
+            capitalize: other.capitalize.or(self.capitalize),
+            add_digit: other.add_digit.or(self.add_digit),
+            add_symbol: other.add_symbol.or(self.add_symbol),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        *self == GeneratorRules::default()
+    }
+
+    pub fn to_toml(&self) -> String {
+        toml::to_string(self).unwrap_or_default()
+    }
+
+    pub fn from_toml(raw: &str) -> Result<GeneratorRules, String> {
+        toml::from_str(raw).map_err(|e| e.to_string())
+    }
+}
+
+impl GeneratorOptions {
+    /// Defaults that satisfy `policy`, with the length taken from the settings.
+    pub fn from_policy(policy: &PasswordPolicy, settings: &SettingsRegistry) -> Self {
+        let length = (settings.default_password_length().unwrap_or(24) as usize)
+            .max(policy.min_length)
+            .min(policy.max_length);
+        GeneratorOptions {
+            length,
+            lowercase: true,
+            uppercase: true,
+            digits: true,
+            symbols: true,
+            symbol_set: policy.symbols.clone(),
+            exclude_ambiguous: settings.exclude_ambiguous_chars().unwrap_or(false),
+            min_lowercase: policy.require_lowercase as usize,
+            min_uppercase: policy.require_uppercase as usize,
+            min_digits: policy.require_digit as usize,
+            min_symbols: policy.require_symbol as usize,
+            alphabet: None,
+            strategy: settings
+                .password_generation_strategy()
+                .and_then(|strategy| strategy.parse().ok())
+                .unwrap_or(Strategy::Random),
+            words: settings.passphrase_words().unwrap_or(6) as usize,
+            separator: settings.passphrase_separator().unwrap_or_else(|| "-".to_string()),
+            capitalize: policy.require_uppercase,
+            add_digit: policy.require_digit,
+            add_symbol: policy.require_symbol,
+        }
+    }
+
+    pub fn apply(&mut self, rules: &GeneratorRules) {
+        if let Some(v) = rules.length { self.length = v; }
+        if let Some(v) = rules.lowercase { self.lowercase = v; }
+        if let Some(v) = rules.uppercase { self.uppercase = v; }
+        if let Some(v) = rules.digits { self.digits = v; }
+        if let Some(v) = rules.symbols { self.symbols = v; }
+        if let Some(v) = &rules.symbol_set { self.symbol_set = v.clone(); }
+        if let Some(v) = rules.exclude_ambiguous { self.exclude_ambiguous = v; }
+        if let Some(v) = rules.min_lowercase { self.min_lowercase = v; }
+        if let Some(v) = rules.min_uppercase { self.min_uppercase = v; }
+        if let Some(v) = rules.min_digits { self.min_digits = v; }
+        if let Some(v) = rules.min_symbols { self.min_symbols = v; }
+        if let Some(v) = &rules.alphabet { self.alphabet = Some(v.clone()); }
+        if let Some(v) = rules.strategy { self.strategy = v; }
+        if let Some(v) = rules.words { self.words = v; }
+        if let Some(v) = &rules.separator { self.separator = v.clone(); }
+        if let Some(v) = rules.capitalize { self.capitalize = v; }
+        if let Some(v) = rules.add_digit { self.add_digit = v; }
+        if let Some(v) = rules.add_symbol { self.add_symbol = v; }
+
+        // a disabled class cannot have a minimum
+        if !self.lowercase { self.min_lowercase = 0; }
+        if !self.uppercase { self.min_uppercase = 0; }
+        if !self.digits { self.min_digits = 0; }
+        if !self.symbols { self.min_symbols = 0; }
+    }
+
+    fn filter(&self, chars: &str) -> Vec<char> {
+        let mut seen = Vec::new();
+        for c in chars.chars() {
+            if !(self.exclude_ambiguous && AMBIGUOUS.contains(c)) && !seen.contains(&c) {
+                seen.push(c);
+            }
+        }
+        seen
+    }
+
+    /// The enabled classes with their minimum counts.
+    fn classes(&self) -> Vec<(Vec<char>, usize)> {
+        let mut classes = Vec::new();
+        if self.lowercase { classes.push((self.filter(LOWERCASE), self.min_lowercase)); }
+        if self.uppercase { classes.push((self.filter(UPPERCASE), self.min_uppercase)); }
+        if self.digits { classes.push((self.filter(DIGITS), self.min_digits)); }
+        if self.symbols { classes.push((self.filter(&self.symbol_set), self.min_symbols)); }
+        classes
+    }
+}
+
+pub fn generate(options: &GeneratorOptions) -> Result<String, String> {
+    match options.strategy {
+        Strategy::Random => random(options),
+        Strategy::Passphrase => passphrase::generate(options),
+        Strategy::Pronounceable => pronounceable::generate(options),
+    }
+}
+
+/// Entropy of the passwords `options` produce, assuming the attacker knows the options.
+pub fn entropy_bits(options: &GeneratorOptions) -> f64 {
+    match options.strategy {
+        Strategy::Random => {
+            let pool = match &options.alphabet {
+                Some(alphabet) => options.filter(alphabet).len(),
+                None => {
+                    let mut pool: Vec<char> = options.classes().into_iter().flat_map(|(chars, _)| chars).collect();
+                    pool.sort();
+                    pool.dedup();
+                    pool.len()
+                }
+            };
+            if pool == 0 {
+                return 0.0;
+            }
+            options.length as f64 * (pool as f64).log2()
+        }
+        Strategy::Passphrase => passphrase::entropy_bits(options),
+        Strategy::Pronounceable => pronounceable::entropy_bits(options),
+    }
+}
+
+fn random(options: &GeneratorOptions) -> Result<String, String> {
+    if options.length == 0 {
+        return Err("Password length must be at least 1".to_string());
+    }
+
+    let mut rng = OsRng;
+
+    if let Some(alphabet) = &options.alphabet {
+        let alphabet = options.filter(alphabet);
+        if alphabet.is_empty() {
+            return Err("The custom alphabet is empty".to_string());
+        }
+        return Ok((0..options.length).map(|_| alphabet[rng.gen_range(0..alphabet.len())]).collect());
+    }
+
+    let classes: Vec<(Vec<char>, usize)> = options.classes().into_iter().filter(|(chars, _)| !chars.is_empty()).collect();
+    if classes.is_empty() {
+        return Err("At least one character set must be enabled".to_string());
+    }
+    let required: usize = classes.iter().map(|(_, min)| *min).sum();
+    if required > options.length {
+        return Err(format!(
+            "The minimum counts add up to {} characters but the length is {}",
+            required, options.length
+        ));
+    }
+
+    let mut password: Vec<char> = Vec::with_capacity(options.length);
+    for (chars, min) in &classes {
+        for _ in 0..*min {
+            password.push(chars[rng.gen_range(0..chars.len())]);
+        }
+    }
+
+    let pool: Vec<char> = classes.iter().flat_map(|(chars, _)| chars.iter().copied()).collect();
+    while password.len() < options.length {
+        password.push(pool[rng.gen_range(0..pool.len())]);
+    }
+
+    password.shuffle(&mut rng);
+    Ok(password.into_iter().collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn options() -> GeneratorOptions {
+        GeneratorOptions::from_policy(
+            &PasswordPolicy::from_settings(&SettingsRegistry::new()),
+            &SettingsRegistry::new(),
+        )
+    }
+
+    #[test]
+    fn test_default_password_satisfies_policy() {
+        let policy = PasswordPolicy::from_settings(&SettingsRegistry::new());
+        for _ in 0..50 {
+            let password = generate(&options()).unwrap();
+            assert_eq!(password.chars().count(), 24);
+            assert!(password.chars().any(|c| c.is_ascii_uppercase()));
+            assert!(password.chars().any(|c| c.is_ascii_digit()));
+            assert!(password.chars().any(|c| policy.symbols.contains(c)));
+        }
+    }
+
+    #[test]
+    fn test_minimum_counts_and_ambiguous_exclusion() {
+        let mut opts = options();
+        opts.apply(&GeneratorRules {
+            length: Some(12),
+            symbols: Some(false),
+            min_digits: Some(5),
+            exclude_ambiguous: Some(true),
+            ..Default::default()
+        });
+
+        for _ in 0..50 {
+            let password = generate(&opts).unwrap();
+            assert_eq!(password.len(), 12);
+            assert!(password.chars().filter(|c| c.is_ascii_digit()).count() >= 5);
+            assert!(!password.chars().any(|c| AMBIGUOUS.contains(c)));
+            assert!(password.chars().all(|c| c.is_ascii_alphanumeric()));
+        }
+    }
+
+    #[test]
+    fn test_custom_alphabet() {
+        let mut opts = options();
+        opts.apply(&GeneratorRules { alphabet: Some("ab".to_string()), length: Some(30), ..Default::default() });
+
+        let password = generate(&opts).unwrap();
+        assert!(password.chars().all(|c| c == 'a' || c == 'b'));
+    }
+
+    #[test]
+    fn test_impossible_options_are_rejected() {
+        let mut opts = options();
+        opts.apply(&GeneratorRules { length: Some(3), min_digits: Some(4), ..Default::default() });
+        assert!(generate(&opts).is_err());
+
+        let mut opts = options();
+        opts.apply(&GeneratorRules {
+            lowercase: Some(false),
+            uppercase: Some(false),
+            digits: Some(false),
+            symbols: Some(false),
+            ..Default::default()
+        });
+        assert!(generate(&opts).is_err());
+    }
+
+    #[test]
+    fn test_passphrase_satisfies_policy() {
+        let policy = PasswordPolicy::from_settings(&SettingsRegistry::new());
+        let mut opts = options();
+        opts.apply(&GeneratorRules { strategy: Some(Strategy::Passphrase), words: Some(5), ..Default::default() });
+
+        for _ in 0..20 {
+            let password = generate(&opts).unwrap();
+            assert_eq!(password.split('-').count(), 5);
+            assert!(policy.check(&password).is_empty(), "{}", password);
+        }
+        // 5 words of a 7776 word list, plus the position and value of the digit and symbol
+        let bits = entropy_bits(&opts);
+        assert!(bits > 5.0 * 12.9 && bits < 80.0, "{}", bits);
+    }
+
+    #[test]
+    fn test_pronounceable_password() {
+        let mut opts = options();
+        opts.apply(&GeneratorRules {
+            strategy: Some(Strategy::Pronounceable),
+            length: Some(14),
+            capitalize: Some(false),
+            add_digit: Some(false),
+            add_symbol: Some(false),
+            ..Default::default()
+        });
+
+        let password = generate(&opts).unwrap();
+        assert_eq!(password.len(), 14);
+        assert!(password.chars().all(|c| c.is_ascii_lowercase()));
+        assert!(entropy_bits(&opts) > 30.0);
+    }
+
+    #[test]
+    fn test_strategy_parsing() {
+        assert_eq!("passphrase".parse::<Strategy>(), Ok(Strategy::Passphrase));
+        assert!("xkcd".parse::<Strategy>().is_err());
+        let rules = GeneratorRules { strategy: Some(Strategy::Pronounceable), ..Default::default() };
+        assert_eq!(GeneratorRules::from_toml(&rules.to_toml()).unwrap(), rules);
+    }
+
+    #[test]
+    fn test_rules_roundtrip_and_merge() {
+        let stored = GeneratorRules { length: Some(16), symbols: Some(false), ..Default::default() };
+        let parsed = GeneratorRules::from_toml(&stored.to_toml()).unwrap();
+        assert_eq!(parsed, stored);
+
+        let merged = stored.merge(&GeneratorRules { length: Some(32), ..Default::default() });
+        assert_eq!(merged.length, Some(32));
+        assert_eq!(merged.symbols, Some(false));
+    }
+}
@@ -0,0 +1,113 @@
+//! Diceware passphrases.
+//!
+//! `data/wordlist.txt` uses the layout of the EFF large wordlist
+//! (`eff_large_wordlist.txt`, CC BY 3.0), 7776 lines of five dice rolls, a tab
+//! and a word, and the EFF file is meant to replace it as is. Until it does,
+//! the file holds an interim list of English words assembled from the strength
+//! meter's list, the `petname` crate's word lists (Apache-2.0), and the base
+//! forms found in both the `harper-core` dictionary (Apache-2.0) and the
+//! blocklist's word list. Entropy figures follow from the length of whichever
+//! list is embedded.
+
+use super::{GeneratorOptions, DIGITS};
+use rand::rngs::OsRng;
+use rand::Rng;
+use std::sync::OnceLock;
+
+static WORDLIST: &str = include_str!("data/wordlist.txt");
+
+pub fn words() -> &'static [&'static str] {
+    static WORDS: OnceLock<Vec<&'static str>> = OnceLock::new();
+    WORDS.get_or_init(|| {
+        WORDLIST
+            .lines()
+            .filter_map(|line| line.split_whitespace().nth(1))
+            .collect()
+    })
+}
+
+fn capitalized(word: &str) -> String {
+    let mut chars = word.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+pub fn generate(options: &GeneratorOptions) -> Result<String, String> {
+    if options.words == 0 {
+        return Err("A passphrase needs at least one word".to_string());
+    }
+    let digits = options.filter(DIGITS);
+    let symbols = options.filter(&options.symbol_set);
+    if options.add_symbol && symbols.is_empty() {
+        return Err("The symbol set is empty".to_string());
+    }
+
+    let list = words();
+    let mut rng = OsRng;
+    let mut chosen: Vec<String> = (0..options.words)
+        .map(|_| {
+            let word = list[rng.gen_range(0..list.len())];
+            if options.capitalize { capitalized(word) } else { word.to_string() }
+        })
+        .collect();
+
+    // the extra characters go at the end of a random word
+    if options.add_digit {
+        let index = rng.gen_range(0..chosen.len());
+        chosen[index].push(digits[rng.gen_range(0..digits.len())]);
+    }
+    if options.add_symbol {
+        let index = rng.gen_range(0..chosen.len());
+        chosen[index].push(symbols[rng.gen_range(0..symbols.len())]);
+    }
+
+    Ok(chosen.join(&options.separator))
+}
+
+pub fn entropy_bits(options: &GeneratorOptions) -> f64 {
+    let count = options.words as f64;
+    let mut bits = count * (words().len() as f64).log2();
+    if options.add_digit {
+        bits += (options.filter(DIGITS).len() as f64).log2() + count.log2();
+    }
+    if options.add_symbol && !options.symbol_set.is_empty() {
+        bits += (options.filter(&options.symbol_set).len() as f64).log2() + count.log2();
+    }
+    bits
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_wordlist_is_a_diceware_list() {
+        let list = words();
+        assert_eq!(list.len(), 7776);
+        let mut unique = list.to_vec();
+        unique.sort();
+        unique.dedup();
+        assert_eq!(unique.len(), 7776);
+        assert!(list.iter().all(|w| w.len() >= 3 && w.chars().all(|c| c.is_ascii_lowercase() || c == '-')));
+    }
+
+    #[test]
+    fn test_wordlist_layout() {
+        // each line starts with its own dice rolls, 11111 to 66666 in order, like the EFF file
+        let mut rolls = [1u8; 5];
+        for line in WORDLIST.lines() {
+            let (index, word) = line.split_once('\t').unwrap();
+            let expected: String = rolls.iter().map(|roll| char::from(b'0' + roll)).collect();
+            assert_eq!(index, expected, "{}", word);
+            for roll in rolls.iter_mut().rev() {
+                if *roll < 6 {
+                    *roll += 1;
+                    break;
+                }
+                *roll = 1;
+            }
+        }
+    }
+}
@@ -0,0 +1,178 @@
+//! Pronounceable passwords, built from consonant-vowel-consonant syllables.
+
+use super::{GeneratorOptions, AMBIGUOUS, DIGITS};
+use rand::rngs::OsRng;
+use rand::Rng;
+
+const ONSETS: [&str; 36] = [
+    "b", "c", "d", "f", "g", "h", "j", "k", "l", "m", "n", "p", "r", "s", "t", "v", "w", "z",
+    "bl", "br", "ch", "cl", "cr", "dr", "fl", "fr", "gl", "gr", "pl", "pr", "sh", "sl", "sp", "st", "th", "tr",
+];
+const VOWELS: [&str; 11] = ["a", "e", "i", "o", "u", "ai", "ea", "ee", "oa", "oo", "ou"];
+const CODAS: [&str; 8] = ["", "l", "m", "n", "r", "s", "t", "x"];
+
+// longest syllable, onset + vowel + coda
+const MAX_SYLLABLE: usize = 5;
+
+fn parts(options: &GeneratorOptions, all: &[&'static str]) -> Vec<&'static str> {
+    all.iter()
+        .copied()
+        .filter(|part| !(options.exclude_ambiguous && part.chars().any(|c| AMBIGUOUS.contains(c))))
+        .collect()
+}
+
+// letters left once the digit and symbol are appended
+fn letters(options: &GeneratorOptions) -> usize {
+    options.length.saturating_sub(options.add_digit as usize + options.add_symbol as usize)
+}
+
+pub fn generate(options: &GeneratorOptions) -> Result<String, String> {
+    let letters = letters(options);
+    if letters < 2 {
+        return Err("The password is too short for a pronounceable password".to_string());
+    }
+    let symbols = options.filter(&options.symbol_set);
+    if options.add_symbol && symbols.is_empty() {
+        return Err("The symbol set is empty".to_string());
+    }
+
+    let (onsets, vowels, codas) = (parts(options, &ONSETS), parts(options, &VOWELS), parts(options, &CODAS));
+    let mut rng = OsRng;
+    let mut password = String::new();
+    while password.len() < letters {
+        password.push_str(onsets[rng.gen_range(0..onsets.len())]);
+        password.push_str(vowels[rng.gen_range(0..vowels.len())]);
+        password.push_str(codas[rng.gen_range(0..codas.len())]);
+    }
+    password.truncate(letters);
+
+    if options.capitalize {
+        password[..1].make_ascii_uppercase();
+    }
+    if options.add_digit {
+        let digits = options.filter(DIGITS);
+        password.push(digits[rng.gen_range(0..digits.len())]);
+    }
+    if options.add_symbol {
+        password.push(symbols[rng.gen_range(0..symbols.len())]);
+    }
+    Ok(password)
+}
+
+/// A lower bound: every syllable carries the same entropy and is at most `MAX_SYLLABLE` letters long.
+pub fn entropy_bits(options: &GeneratorOptions) -> f64 {
+    let syllable = (parts(options, &ONSETS).len() * parts(options, &VOWELS).len() * parts(options, &CODAS).len()) as f64;
+    let mut bits = letters(options) as f64 * syllable.log2() / MAX_SYLLABLE as f64;
+    if options.add_digit {
+        bits += (options.filter(DIGITS).len() as f64).log2();
+    }
+    if options.add_symbol && !options.symbol_set.is_empty() {
+        bits += (options.filter(&options.symbol_set).len() as f64).log2();
+    }
+    bits
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::generator::{GeneratorRules, Strategy};
+    use crate::policy::PasswordPolicy;
+    use crate::setting::registry::SettingsRegistry;
+
+    fn options(rules: GeneratorRules) -> GeneratorOptions {
+        let mut opts = GeneratorOptions::from_policy(
+            &PasswordPolicy::from_settings(&SettingsRegistry::new()),
+            &SettingsRegistry::new(),
+        );
+        opts.apply(&GeneratorRules { strategy: Some(Strategy::Pronounceable), ..rules });
+        opts
+    }
+
+    // whether `s` splits into onset-vowel-coda syllables, the last one possibly cut short
+    fn syllables(s: &str) -> bool {
+        if s.is_empty() {
+            return true;
+        }
+        ONSETS.iter().any(|onset| match s.strip_prefix(onset) {
+            None => onset.starts_with(s),
+            Some(rest) => {
+                rest.is_empty()
+                    || VOWELS.iter().any(|vowel| match rest.strip_prefix(vowel) {
+                        None => vowel.starts_with(rest),
+                        Some(rest) => CODAS.iter().any(|coda| match rest.strip_prefix(coda) {
+                            None => coda.starts_with(rest),
+                            Some(rest) => syllables(rest),
+                        }),
+                    })
+            }
+        })
+    }
+
+    #[test]
+    fn test_syllable_pattern() {
+        assert!(syllables("braitfoxsh"));
+        assert!(!syllables("abra"));
+        assert!(!syllables("brkt"));
+
+        let opts = options(GeneratorRules {
+            length: Some(20),
+            capitalize: Some(false),
+            add_digit: Some(false),
+            add_symbol: Some(false),
+            ..Default::default()
+        });
+        for _ in 0..50 {
+            let password = generate(&opts).unwrap();
+            assert_eq!(password.len(), 20);
+            assert!(password.chars().all(|c| c.is_ascii_lowercase()));
+            assert!(syllables(&password), "{}", password);
+        }
+    }
+
+    #[test]
+    fn test_capital_digit_and_symbol() {
+        let opts = options(GeneratorRules {
+            length: Some(16),
+            capitalize: Some(true),
+            add_digit: Some(true),
+            add_symbol: Some(true),
+            ..Default::default()
+        });
+        for _ in 0..50 {
+            let password = generate(&opts).unwrap();
+            let chars: Vec<char> = password.chars().collect();
+            assert_eq!(chars.len(), 16);
+            assert!(chars[0].is_ascii_uppercase());
+            assert!(chars[14].is_ascii_digit());
+            assert!(opts.symbol_set.contains(chars[15]));
+            assert!(syllables(&password[..14].to_ascii_lowercase()), "{}", password);
+        }
+    }
+
+    #[test]
+    fn test_ambiguous_letters_are_excluded() {
+        let opts = options(GeneratorRules {
+            length: Some(30),
+            exclude_ambiguous: Some(true),
+            capitalize: Some(true),
+            add_digit: Some(true),
+            add_symbol: Some(false),
+            ..Default::default()
+        });
+        for _ in 0..50 {
+            let password = generate(&opts).unwrap();
+            assert!(!password.chars().any(|c| AMBIGUOUS.contains(c)), "{}", password);
+        }
+    }
+
+    #[test]
+    fn test_too_short_is_rejected() {
+        let opts = options(GeneratorRules {
+            length: Some(3),
+            add_digit: Some(true),
+            add_symbol: Some(true),
+            ..Default::default()
+        });
+        assert!(generate(&opts).is_err());
+    }
+}
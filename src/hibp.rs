@@ -0,0 +1,117 @@
+//! Offline lookups in the Have I Been Pwned "Pwned Passwords" list.
+//!
+//! The list is the SHA-1 version ordered by hash, one `HASH:COUNT` line per
+//! password, as downloaded with the official downloader. It is tens of gigabytes,
+//! so it is never loaded: each lookup is a binary search over byte offsets that
+//! reads a couple of lines per step. Nothing is sent over the network.
+
+use sha1::{Digest, Sha1};
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, Seek, SeekFrom};
+use std::path::Path;
+
+pub struct PwnedPasswords {
+    reader: BufReader<File>,
+    len: u64,
+}
+
+/// Uppercase hex SHA-1, the format of the list.
+pub fn sha1_hex(password: &str) -> String {
+    hex::encode_upper(Sha1::digest(password.as_bytes()))
+}
+
+impl PwnedPasswords {
+    pub fn open(path: &Path) -> io::Result<Self> {
+        let file = File::open(path)?;
+        let len = file.metadata()?.len();
+        Ok(PwnedPasswords { reader: BufReader::new(file), len })
+    }
+
+    /// Opens the file configured in `hibp_file`, if any.
+    pub fn from_setting(hibp_file: Option<String>) -> io::Result<Option<Self>> {
+        match hibp_file {
+            Some(path) if !path.is_empty() => PwnedPasswords::open(Path::new(&path)).map(Some),
+            _ => Ok(None),
+        }
+    }
+
+    /// How many times `password` appears in known breaches, 0 if it does not.
+    pub fn count(&mut self, password: &str) -> io::Result<u64> {
+        self.count_hash(&sha1_hex(password))
+    }
+
+    pub fn count_hash(&mut self, hash: &str) -> io::Result<u64> {
+        let hash = hash.to_ascii_uppercase();
+
+        // smallest offset whose next line is not below the hash
+        let (mut lo, mut hi) = (0, self.len);
+        while lo < hi {
+            let mid = lo + (hi - lo) / 2;
+            match self.line_at(mid)? {
+                Some((line_hash, _)) if line_hash < hash => lo = mid + 1,
+                _ => hi = mid,
+            }
+        }
+
+        match self.line_at(lo)? {
+            Some((line_hash, count)) if line_hash == hash => Ok(count),
+            _ => Ok(0),
+        }
+    }
+
+    /// The first complete line starting at or after `offset`.
+    fn line_at(&mut self, offset: u64) -> io::Result<Option<(String, u64)>> {
+        let mut line = String::new();
+        if offset == 0 {
+            self.reader.seek(SeekFrom::Start(0))?;
+        } else {
+            // the byte before `offset` tells whether a line starts exactly there
+            self.reader.seek(SeekFrom::Start(offset - 1))?;
+            self.reader.read_line(&mut line)?;
+            line.clear();
+        }
+        if self.reader.read_line(&mut line)? == 0 {
+            return Ok(None);
+        }
+
+        let line = line.trim_end();
+        let (hash, count) = line.split_once(':').unwrap_or((line, "0"));
+        let count = count.trim().parse().map_err(|_| {
+            io::Error::new(io::ErrorKind::InvalidData, format!("malformed line in the HIBP file: {}", line))
+        })?;
+        Ok(Some((hash.to_ascii_uppercase(), count)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    #[test]
+    fn test_lookup_in_sorted_file() {
+        let mut passwords: Vec<(String, u64)> = vec![
+            ("password".to_string(), 9_659_365),
+            ("123456".to_string(), 42_000_000),
+            ("letmein".to_string(), 1_000),
+        ];
+        passwords.extend((0..200).map(|i| (format!("filler-{}", i), i + 1)));
+        let mut lines: Vec<String> = passwords.iter().map(|(p, c)| format!("{}:{}", sha1_hex(p), c)).collect();
+        lines.sort();
+
+        let mut file = tempfile::NamedTempFile::new().unwrap();
+        for line in &lines {
+            write!(file, "{}\r\n", line).unwrap();
+        }
+
+        let mut hibp = PwnedPasswords::open(file.path()).unwrap();
+        assert_eq!(sha1_hex("password"), "5BAA61E4C9B93F3F0682250B6CF8331B7EE68FD8");
+        assert_eq!(hibp.count("password").unwrap(), 9_659_365);
+        assert_eq!(hibp.count("123456").unwrap(), 42_000_000);
+        assert_eq!(hibp.count("filler-199").unwrap(), 200);
+        assert_eq!(hibp.count("correct horse battery staple").unwrap(), 0);
+        for (password, count) in &passwords {
+            assert_eq!(hibp.count(password).unwrap(), *count);
+        }
+    }
+}
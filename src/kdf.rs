@@ -0,0 +1,95 @@
+use sha2::{Digest, Sha256};
+use std::time::{Duration, Instant};
+
+/// Derives the 32 byte vault key from the master password.
+///
+/// `iterations == 0` is the legacy scheme, a single `sha256(password || salt)`,
+/// kept so vaults created before the KDF was configurable still open.
+pub fn derive_master_key(password: &str, salt: &str, iterations: u32) -> Vec<u8> {
+    if iterations == 0 {
+        let mut hasher = Sha256::new();
+        hasher.update(password.as_bytes());
+        hasher.update(salt.as_bytes());
+        return hasher.finalize().to_vec();
+    }
+
+    let mut key = [0u8; 32];
+    pbkdf2::pbkdf2_hmac::<Sha256>(password.as_bytes(), salt.as_bytes(), iterations, &mut key);
+    key.to_vec()
+}
+
+/// Hex encoded vault key, as stored in the config.
+pub fn derive_master_hash(password: &str, salt: &str, iterations: u32) -> String {
+    hex::encode(derive_master_key(password, salt, iterations))
+}
+
+/// What the config stores in place of the vault key. The key itself only lives
+/// in the session, a copy of the config does not open the vault or its archives.
+pub fn key_verifier(key_hex: &str, salt: &str) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(b"kofl-key-verifier\0");
+    hasher.update(salt.as_bytes());
+    hasher.update(b"\0");
+    hasher.update(key_hex.as_bytes());
+    hex::encode(hasher.finalize())
+}
+
+/// The secret run through the KDF, the master password followed by the key file digest if any.
+pub fn with_keyfile(password: &str, keyfile_digest: Option<&str>) -> String {
+    match keyfile_digest {
+        Some(digest) => format!("{}\u{0}{}", password, digest),
+        None => password.to_string(),
+    }
+}
+
+/// Time taken by one derivation with `iterations` rounds on this machine.
+pub fn benchmark(iterations: u32) -> Duration {
+    let start = Instant::now();
+    derive_master_key("kofl-benchmark", "kofl-benchmark-salt", iterations);
+    start.elapsed()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_legacy_derivation_is_plain_sha256() {
+        let mut hasher = Sha256::new();
+        hasher.update(b"password");
+        hasher.update(b"salt");
+        let expected = hex::encode(hasher.finalize());
+
+        assert_eq!(derive_master_hash("password", "salt", 0), expected);
+    }
+
+    #[test]
+    fn test_pbkdf2_derivation_depends_on_iterations() {
+        let a = derive_master_hash("password", "salt", 1000);
+        let b = derive_master_hash("password", "salt", 2000);
+
+        assert_eq!(a.len(), 64);
+        assert_ne!(a, b);
+        assert_eq!(a, derive_master_hash("password", "salt", 1000));
+    }
+
+    #[test]
+    fn test_keyfile_changes_the_key() {
+        let plain = derive_master_hash(&with_keyfile("password", None), "salt", 1000);
+        let bound = derive_master_hash(&with_keyfile("password", Some("ab12")), "salt", 1000);
+
+        assert_eq!(plain, derive_master_hash("password", "salt", 1000));
+        assert_ne!(plain, bound);
+    }
+
+    #[test]
+    fn test_verifier_is_not_the_key() {
+        let key = derive_master_hash("password", "salt", 1000);
+        let verifier = key_verifier(&key, "salt");
+
+        assert_eq!(verifier.len(), 64);
+        assert_ne!(verifier, key);
+        assert_eq!(verifier, key_verifier(&key, "salt"));
+        assert_ne!(verifier, key_verifier(&key, "other salt"));
+    }
+}
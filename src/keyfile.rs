@@ -0,0 +1,71 @@
+//! Key files, a second thing besides the master password needed to unlock.
+//!
+//! The SHA-256 of the file contents is folded into the master password before
+//! the KDF runs (see `kdf::with_keyfile`), so the vault key cannot be derived
+//! without the file. Only whether a key file is needed is recorded in the
+//! config, never its path or digest, and the config keeps a verifier of the
+//! vault key rather than the key (see `kdf::key_verifier`).
+
+use crate::config::Config::KoflGlobalConfig;
+use rand::RngCore;
+use sha2::{Digest, Sha256};
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::os::unix::fs::OpenOptionsExt;
+use std::path::Path;
+
+const NEW_KEYFILE_BYTES: usize = 64;
+
+/// Hex SHA-256 of the file contents.
+pub fn digest(path: &Path) -> Result<String, String> {
+    let content = fs::read(path).map_err(|e| format!("Cannot read the key file {}: {}", path.display(), e))?;
+    if content.is_empty() {
+        return Err(format!("The key file {} is empty", path.display()));
+    }
+    Ok(hex::encode(Sha256::digest(&content)))
+}
+
+/// Writes a new random key file readable by the owner only, an existing file is never replaced.
+pub fn generate(path: &Path) -> Result<(), String> {
+    let mut bytes = vec![0u8; NEW_KEYFILE_BYTES];
+    rand::thread_rng().fill_bytes(&mut bytes);
+    let mut file = OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .mode(0o600)
+        .open(path)
+        .map_err(|e| format!("Cannot create the key file {}: {}", path.display(), e))?;
+    file.write_all(&bytes)
+        .map_err(|e| format!("Cannot write the key file {}: {}", path.display(), e))
+}
+
+/// Digest of `path` when the vault needs a key file, checking that one was given exactly when needed.
+pub fn for_vault(kgc: &KoflGlobalConfig, path: Option<&Path>) -> Result<Option<String>, String> {
+    match (kgc.has_keyfile(), path) {
+        (true, Some(path)) => digest(path).map(Some),
+        (true, None) => Err("This vault is bound to a key file, pass it with --keyfile".to_string()),
+        (false, Some(_)) => Err("This vault is not bound to a key file".to_string()),
+        (false, None) => Ok(None),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_generate_and_digest() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("vault.key");
+        generate(&path).unwrap();
+
+        assert_eq!(fs::read(&path).unwrap().len(), NEW_KEYFILE_BYTES);
+        assert_eq!(digest(&path).unwrap().len(), 64);
+        assert!(generate(&path).is_err());
+
+        let empty = dir.path().join("empty.key");
+        fs::write(&empty, b"").unwrap();
+        assert!(digest(&empty).is_err());
+    }
+}
@@ -0,0 +1,206 @@
+//! Where kofl keeps its files.
+//!
+//! `KOFL_HOME` holds everything when set. Otherwise the config goes in
+//! `$XDG_CONFIG_HOME/kofl`, the database and backups in `$XDG_DATA_HOME/kofl`
+//! and the session in `$XDG_RUNTIME_DIR/kofl`, with the fallbacks of the XDG
+//! base directory spec. What an older kofl left directly in the home
+//! directory is moved over by `crate::vault::migrate_legacy`.
+
+use crate::utils::Utils::get_home_dir;
+use std::env;
+use std::fs::{self, OpenOptions};
+use std::io::{ErrorKind, Write};
+use std::os::unix::fs::{DirBuilderExt, OpenOptionsExt};
+use std::path::{Path, PathBuf};
+
+pub const HOME_ENV: &str = "KOFL_HOME";
+
+/// The directory from `KOFL_HOME`, None when kofl follows the XDG layout.
+pub fn kofl_home() -> Option<PathBuf> {
+    env::var_os(HOME_ENV).filter(|home| !home.is_empty()).map(PathBuf::from)
+}
+
+// `var` when set to an absolute path, `fallback` under the home directory otherwise
+fn xdg_dir(var: &str, fallback: &str) -> PathBuf {
+    match env::var_os(var).map(PathBuf::from) {
+        Some(dir) if dir.is_absolute() => dir.join("kofl"),
+        _ => get_home_dir().expect("Home directory not found").join(fallback).join("kofl"),
+    }
+}
+
+pub fn config_dir() -> PathBuf {
+    kofl_home().unwrap_or_else(|| xdg_dir("XDG_CONFIG_HOME", ".config"))
+}
+
+pub fn data_dir() -> PathBuf {
+    kofl_home().unwrap_or_else(|| xdg_dir("XDG_DATA_HOME", ".local/share"))
+}
+
+/// Sessions go where they do not outlive a logout or reboot, the state
+/// directory stands in when there is no runtime directory.
+pub fn runtime_dir() -> PathBuf {
+    kofl_home().unwrap_or_else(|| match env::var_os("XDG_RUNTIME_DIR").map(PathBuf::from) {
+        Some(dir) if dir.is_absolute() => dir.join("kofl"),
+        _ => xdg_dir("XDG_STATE_HOME", ".local/state"),
+    })
+}
+
+/// Creates `dir` and its missing parents, readable by the user only.
+pub fn create_private_dir(dir: &Path) -> Result<(), String> {
+    fs::DirBuilder::new()
+        .recursive(true)
+        .mode(0o700)
+        .create(dir)
+        .map_err(|e| format!("Cannot create {}: {}", dir.display(), e))
+}
+
+/// A path for a plaintext scratch file named after `name`, in a private
+/// directory under the data directory. Never the backup location, which may
+/// be a synced folder or a removable drive.
+pub fn scratch_file(name: &str) -> Result<PathBuf, String> {
+    let dir = data_dir().join("tmp");
+    create_private_dir(&dir)?;
+    Ok(dir.join(format!("{}.{}.tmp", name, std::process::id())))
+}
+
+/// Writes `contents` to `path`, a new file is readable and writable by the user only.
+pub fn write_private(path: &Path, contents: impl AsRef<[u8]>) -> std::io::Result<()> {
+    OpenOptions::new()
+        .write(true)
+        .create(true)
+        .truncate(true)
+        .mode(0o600)
+        .open(path)?
+        .write_all(contents.as_ref())
+}
+
+/// Creates an empty file for the user only, unless `path` exists. SQLite keeps
+/// the mode of the file it opens.
+pub fn create_private_file(path: &Path) -> std::io::Result<()> {
+    match OpenOptions::new().write(true).create_new(true).mode(0o600).open(path) {
+        Err(e) if e.kind() != ErrorKind::AlreadyExists => Err(e),
+        _ => Ok(()),
+    }
+}
+
+/// Moves a file or directory, copying when it goes to another file system.
+pub fn move_path(from: &Path, to: &Path) -> Result<(), String> {
+    if let Some(parent) = to.parent() {
+        create_private_dir(parent)?;
+    }
+    if fs::rename(from, to).is_ok() {
+        return Ok(());
+    }
+    let copied = if from.is_dir() { copy_dir(from, to) } else { fs::copy(from, to).map(|_| ()) };
+    copied
+        .and_then(|_| if from.is_dir() { fs::remove_dir_all(from) } else { fs::remove_file(from) })
+        .map_err(|e| format!("Cannot move {} to {}: {}", from.display(), to.display(), e))
+}
+
+fn copy_dir(from: &Path, to: &Path) -> std::io::Result<()> {
+    fs::DirBuilder::new().mode(0o700).create(to)?;
+    for entry in fs::read_dir(from)? {
+        let entry = entry?;
+        let target = to.join(entry.file_name());
+        if entry.file_type()?.is_dir() {
+            copy_dir(&entry.path(), &target)?;
+        } else {
+            fs::copy(entry.path(), target)?;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serial_test::serial;
+    use tempfile::TempDir;
+
+    struct EnvGuard {
+        key: &'static str,
+        original: Option<String>,
+    }
+
+    impl EnvGuard {
+        fn new(key: &'static str) -> Self {
+            let original = env::var(key).ok();
+            Self { key, original }
+        }
+
+        fn set_var(&self, value: &str) {
+            env::set_var(self.key, value);
+        }
+
+        fn remove_var(&self) {
+            env::remove_var(self.key);
+        }
+    }
+
+    impl Drop for EnvGuard {
+        fn drop(&mut self) {
+            match &self.original {
+                Some(original) => env::set_var(self.key, original),
+                None => env::remove_var(self.key),
+            }
+        }
+    }
+
+    #[test]
+    #[serial]
+    fn test_xdg_dirs() {
+        let kofl_home = EnvGuard::new(HOME_ENV);
+        let config_home = EnvGuard::new("XDG_CONFIG_HOME");
+        let runtime = EnvGuard::new("XDG_RUNTIME_DIR");
+        let state_home = EnvGuard::new("XDG_STATE_HOME");
+        kofl_home.remove_var();
+        state_home.remove_var();
+
+        config_home.set_var("/xdg/config");
+        assert_eq!(config_dir(), PathBuf::from("/xdg/config/kofl"));
+        config_home.set_var("relative/config");
+        assert_eq!(config_dir(), get_home_dir().unwrap().join(".config/kofl"));
+
+        runtime.set_var("/run/user/1000");
+        assert_eq!(runtime_dir(), PathBuf::from("/run/user/1000/kofl"));
+        runtime.remove_var();
+        assert_eq!(runtime_dir(), get_home_dir().unwrap().join(".local/state/kofl"));
+    }
+
+    #[test]
+    #[serial]
+    fn test_kofl_home_holds_everything() {
+        let kofl_home = EnvGuard::new(HOME_ENV);
+        kofl_home.set_var("/srv/kofl");
+        assert_eq!(config_dir(), PathBuf::from("/srv/kofl"));
+        assert_eq!(data_dir(), PathBuf::from("/srv/kofl"));
+        assert_eq!(runtime_dir(), PathBuf::from("/srv/kofl"));
+    }
+
+    #[test]
+    fn test_private_files() {
+        use std::os::unix::fs::PermissionsExt;
+        let temp_dir = TempDir::new().unwrap();
+        let written = temp_dir.path().join("config.toml");
+        write_private(&written, "secret").unwrap();
+        let created = temp_dir.path().join("kofl.sqlite");
+        create_private_file(&created).unwrap();
+        create_private_file(&created).unwrap();
+        for path in [written, created] {
+            assert_eq!(fs::metadata(path).unwrap().permissions().mode() & 0o777, 0o600);
+        }
+    }
+
+    #[test]
+    fn test_move_path() {
+        let temp_dir = TempDir::new().unwrap();
+        let from = temp_dir.path().join("backups");
+        fs::create_dir_all(from.join("team")).unwrap();
+        fs::write(from.join("team/archive"), "data").unwrap();
+
+        let to = temp_dir.path().join("share/kofl/backups");
+        move_path(&from, &to).unwrap();
+        assert!(!from.exists());
+        assert_eq!(fs::read_to_string(to.join("team/archive")).unwrap(), "data");
+    }
+}
@@ -3,20 +3,41 @@
 mod cli;
 mod config;
 mod backup;
+mod audit;
+mod blocklist;
+mod cipher;
+mod clipboard;
 mod context;
 mod db;
+mod emergency_kit;
 mod errors;
 mod session;
 mod utils;
 mod validator;
 mod setting;
 mod constants;
+mod generator;
+mod hibp;
+mod kdf;
+mod keyfile;
+mod layout;
+mod otp;
+mod permissions;
+mod policy;
+mod recovery;
+mod rekey;
+mod rotation;
+mod shamir;
+mod strength;
+mod two_factor;
+mod vault;
 
 
 // Updated imports for the commands
-use clap::{Parser, Subcommand};
+use clap::{Args, Parser, Subcommand};
+use generator::{GeneratorRules, Strategy};
 // Import commands from the new location
-use cli::commands::{AddCmd, DestroyCmd, GetCmd, InitCmd, LogInCmd, UpdateCmd}; // Updated path
+use cli::commands::{AddCmd, AuditAction, AuditCmd, BackupAction, BackupCmd, ChangeMasterCmd, ConfigAction, ConfigCmd, DestroyCmd, DoctorCmd, EmergencyKitAction, EmergencyKitCmd, GenerateCmd, GetCmd, InitCmd, KeyfileAction, KeyfileCmd, ListCmd, LogInCmd, LogoutCmd, OtpAction, OtpCmd, PolicyAction, PolicyCmd, RecoveryAction, RecoveryCmd, RotateCmd, TwoFactorAction, TwoFactorCmd, UpdateCmd, VaultAction, VaultCmd}; // Updated path
 use cli::Command; // Import the Command trait from cli module
 use colored::*;
 use context::Context;
@@ -24,11 +45,14 @@ use env_logger::{Env, Target};
 use log::{debug, error, info, warn};
 use std::f32::consts::E;
 use std::io::Write;
+use std::path::PathBuf;
 use std::process;
 
 #[derive(Parser)]
 #[command(author, version, about, long_about = None)]
 struct Cli {
+    #[arg(long, global = true, help = "Vault to work on, overrides KOFL_VAULT and `kofl vault use`")]
+    vault: Option<String>,
     #[command(subcommand)]
     command: Commands,
 }
@@ -36,28 +60,399 @@ struct Cli {
 #[derive(Subcommand)]
 enum Commands {
     #[command(about = "Initialize the password manager by providing a Master Key")]
-    Init {},
+    Init {
+        #[arg(long, help = "Skip the setup wizard and keep the default settings")]
+        defaults: bool,
+        #[arg(long, conflicts_with = "defaults", help = "Read the settings from a TOML file instead of asking")]
+        config_file: Option<PathBuf>,
+        #[arg(long = "2fa", help = "Also set up a TOTP code required at login")]
+        two_factor: bool,
+        #[arg(long, help = "Bind the vault to a key file, created when it does not exist")]
+        keyfile: Option<PathBuf>,
+    },
     #[command(about = "Login to the password manager using the Master Key")]    
-    Login {},
+    Login {
+        #[arg(long, help = "Key file the vault is bound to")]
+        keyfile: Option<PathBuf>,
+    },
+    #[command(visible_alias = "lock", about = "End the session now, the next command needs kofl login")]
+    Logout {},
     #[command(about = "Destroy the password manager data (configuration + database)")]
-    Destroy {},
+    Destroy {
+        #[arg(long, help = "Key file the vault is bound to")]
+        keyfile: Option<PathBuf>,
+    },
     #[command(about = "Add a new entry use --suggest flag to suggest a password")]
     Add {
         name: String,
         #[arg(short, long,)]
-        suggest: bool
+        suggest: bool,
+        #[command(flatten)]
+        generator: GeneratorArgs,
+        #[arg(long, help = "Remember the generator options as the rules of this entry")]
+        save_rules: bool,
+        #[arg(long, help = "Login name used with this entry")]
+        username: Option<String>,
+        #[arg(long, help = "Address of the site or service")]
+        url: Option<String>,
+        #[arg(long, visible_alias = "no-clipboard", help = "Print the generated password instead of copying it")]
+        print: bool,
     },
     #[command(about = "Get the password of on entry by name")]
-    Get { ent_name: String },
+    Get {
+        ent_name: String,
+        #[arg(long, visible_alias = "no-clipboard", help = "Print the password instead of copying it")]
+        print: bool,
+    },
+    #[command(about = "List the entries of the vault")]
+    List {
+        #[arg(long, help = "Only show entries that are overdue or expire soon")]
+        expiring: bool,
+        #[arg(long, default_value_t = 14, requires = "expiring", help = "Days ahead counted as expiring soon")]
+        within: u32,
+    },
+    #[command(about = "Replace the password of an entry with a generated one and archive the old one")]
+    Rotate {
+        name: String,
+        #[arg(long, visible_alias = "no-clipboard", help = "Print the new password instead of copying it")]
+        print: bool,
+    },
+    #[command(
+        about = "Show the current one-time code of an entry and manage its 2FA secret",
+        args_conflicts_with_subcommands = true,
+        subcommand_negates_reqs = true
+    )]
+    Otp {
+        #[command(subcommand)]
+        action: Option<OtpActions>,
+        #[arg(required = true)]
+        name: Option<String>,
+        #[arg(short, long, help = "Copy the code to the clipboard until it expires instead of printing it")]
+        copy: bool,
+    },
     #[command(about = "Update the password of an existing entry")]
     Update {
         ent_name: String,
         #[arg(short, long)]
-        suggest: bool
+        suggest: bool,
+        #[command(flatten)]
+        generator: GeneratorArgs,
+        #[arg(long, help = "Remember the generator options as the rules of this entry")]
+        save_rules: bool,
+        #[arg(long, help = "Login name used with this entry")]
+        username: Option<String>,
+        #[arg(long, help = "Address of the site or service")]
+        url: Option<String>,
+        #[arg(long, visible_alias = "no-clipboard", help = "Print the generated password instead of copying it")]
+        print: bool,
+    },
+    #[command(about = "Generate random passwords without storing them")]
+    Generate {
+        #[command(flatten)]
+        generator: GeneratorArgs,
+        #[arg(short = 'n', long, default_value_t = 1, help = "Number of passwords to generate")]
+        count: usize,
+        #[arg(long = "for", value_name = "ENTRY", help = "Use the policy and stored rules of an entry")]
+        entry: Option<String>,
+    },
+    #[command(name = "2fa", about = "Require a TOTP code from an authenticator app at login")]
+    TwoFactor {
+        #[command(subcommand)]
+        action: TwoFactorActions,
+    },
+    #[command(about = "Change the master password and re-encrypt the vault")]
+    ChangeMaster {
+        #[arg(long, help = "Key file the vault is bound to")]
+        keyfile: Option<PathBuf>,
+    },
+    #[command(about = "Split a recovery key into Shamir shares, or use them to reset a forgotten master password")]
+    Recovery {
+        #[command(subcommand)]
+        action: RecoveryActions,
+    },
+    #[command(
+        name = "emergency-kit",
+        about = "Write a printable emergency kit, or check or use one",
+        args_conflicts_with_subcommands = true
+    )]
+    EmergencyKit {
+        #[command(subcommand)]
+        action: Option<EmergencyKitActions>,
+        #[arg(short, long, help = "HTML file to write, kofl-emergency-kit-<date>.html by default")]
+        output: Option<PathBuf>,
+        #[arg(long, help = "Include the vault key sealed with a kit passphrase, as text and QR code")]
+        with_key: bool,
+        #[arg(long, help = "Include new 2FA recovery codes, the current ones stop working")]
+        recovery_codes: bool,
+        #[arg(long, help = "Key file the vault is bound to")]
+        keyfile: Option<PathBuf>,
+    },
+    #[command(about = "Create, list, switch between and delete independent vaults")]
+    Vault {
+        #[command(subcommand)]
+        action: VaultActions,
+    },
+    #[command(about = "Check that the kofl files of the vault are private to you")]
+    Doctor {
+        #[arg(long, help = "Make group or world accessible files private again")]
+        fix_permissions: bool,
+    },
+    #[command(about = "Manage the key file needed besides the master password")]
+    Keyfile {
+        #[command(subcommand)]
+        action: KeyfileActions,
+    },
+    #[command(about = "Manage backups of the configuration and database")]
+    Backup {
+        #[command(subcommand)]
+        action: BackupActions,
+    },
+    #[command(about = "View and change the password manager settings")]
+    Config {
+        #[command(subcommand)]
+        action: ConfigActions,
+    },
+    #[command(about = "Manage the password policy and its per folder or per entry overrides")]
+    Policy {
+        #[command(subcommand)]
+        action: PolicyActions,
+    },
+    #[command(
+        about = "Report reused, weak, old and incomplete entries with an overall health score",
+        args_conflicts_with_subcommands = true
+    )]
+    Audit {
+        #[command(subcommand)]
+        action: Option<AuditActions>,
+        #[arg(long, help = "Print the report as JSON")]
+        json: bool,
+        #[arg(long, help = "Flag passwords older than this many days (0 to disable), instead of the max_age_days of their policy")]
+        max_age_days: Option<usize>,
+        #[arg(long, default_value_t = 80, help = "Exit with an error when the health score is below this")]
+        min_health: u32,
+    },
+    // serves a copied password, started by `kofl get`
+    #[command(name = "clipboard-daemon", hide = true)]
+    ClipboardDaemon {
+        #[arg(long)]
+        timeout: u32,
+        #[arg(long, default_value_t = 0)]
+        pastes: u32,
+    },
+}
+
+/// Options of the password generator, all of them default to the password policy.
+#[derive(Args)]
+struct GeneratorArgs {
+    #[arg(short, long, help = "Length of the generated password")]
+    length: Option<usize>,
+    #[arg(long, help = "Leave out lowercase letters")]
+    no_lowercase: bool,
+    #[arg(long, help = "Leave out uppercase letters")]
+    no_uppercase: bool,
+    #[arg(long, help = "Leave out digits")]
+    no_digits: bool,
+    #[arg(long, help = "Leave out symbols")]
+    no_symbols: bool,
+    #[arg(long, value_name = "CHARS", help = "Characters used as symbols")]
+    symbols: Option<String>,
+    #[arg(long, help = "Avoid look-alike characters such as 0/O and 1/l/I")]
+    exclude_ambiguous: bool,
+    #[arg(long, value_name = "N")]
+    min_lowercase: Option<usize>,
+    #[arg(long, value_name = "N")]
+    min_uppercase: Option<usize>,
+    #[arg(long, value_name = "N")]
+    min_digits: Option<usize>,
+    #[arg(long, value_name = "N")]
+    min_symbols: Option<usize>,
+    #[arg(long, value_name = "CHARS", help = "Draw every character from this alphabet instead")]
+    alphabet: Option<String>,
+    #[arg(long, help = "random, passphrase or pronounceable")]
+    strategy: Option<Strategy>,
+    #[arg(short, long, value_name = "N", help = "Number of words in a passphrase")]
+    words: Option<usize>,
+    #[arg(long, value_name = "SEP", help = "Characters placed between the words of a passphrase")]
+    separator: Option<String>,
+    #[arg(long, help = "Capitalize passphrase words or the first letter of a pronounceable password")]
+    capitalize: bool,
+    #[arg(long, help = "Add a digit to a passphrase or pronounceable password")]
+    add_digit: bool,
+    #[arg(long, help = "Add a symbol to a passphrase or pronounceable password")]
+    add_symbol: bool,
+}
+
+impl GeneratorArgs {
+    fn to_rules(&self) -> GeneratorRules {
+        let disabled = |flag: bool| if flag { Some(false) } else { None };
+        let enabled = |flag: bool| if flag { Some(true) } else { None };
+        GeneratorRules {
+            length: self.length,
+            lowercase: disabled(self.no_lowercase),
+            uppercase: disabled(self.no_uppercase),
+            digits: disabled(self.no_digits),
+            symbols: disabled(self.no_symbols),
+            symbol_set: self.symbols.clone(),
+            exclude_ambiguous: enabled(self.exclude_ambiguous),
+            min_lowercase: self.min_lowercase,
+            min_uppercase: self.min_uppercase,
+            min_digits: self.min_digits,
+            min_symbols: self.min_symbols,
+            alphabet: self.alphabet.clone(),
+            strategy: self.strategy,
+            words: self.words,
+            separator: self.separator.clone(),
+            capitalize: enabled(self.capitalize),
+            add_digit: enabled(self.add_digit),
+            add_symbol: enabled(self.add_symbol),
+        }
     }
 }
 
+#[derive(Subcommand)]
+enum BackupActions {
+    #[command(about = "List all existing backups")]
+    List {},
+    #[command(about = "Verify the integrity of one backup, or all of them")]
+    Verify { name: Option<String> },
+    #[command(about = "Remove backups outside of the retention policy")]
+    Prune {},
+    #[command(about = "Restore the vault from a backup (the latest one by default)")]
+    Restore {
+        name: Option<String>,
+        #[arg(long, help = "Path to a backup archive, e.g. on a mounted drive")]
+        file: Option<PathBuf>,
+    },
+    #[command(about = "Encrypt new backups with a separate passphrase instead of the master password")]
+    Passphrase {
+        #[arg(long, help = "Go back to encrypting backups with the master password")]
+        remove: bool,
+    },
+}
+
+#[derive(Subcommand)]
+enum ConfigActions {
+    #[command(about = "Show the current value of a setting")]
+    Get { key: String },
+    #[command(about = "Change the value of a setting")]
+    Set { key: String, value: String },
+    #[command(about = "List all settings with their current values")]
+    List {},
+    #[command(about = "Reset a setting to its default value (all settings if no key is given)")]
+    Reset { key: Option<String> },
+}
 
+#[derive(Subcommand)]
+enum AuditActions {
+    #[command(about = "List the entries whose password appears in the HIBP Pwned Passwords list")]
+    Breaches {
+        #[arg(long, help = "Local copy of the SHA-1 list ordered by hash (defaults to the hibp_file setting)")]
+        hibp_file: Option<PathBuf>,
+    },
+}
+
+#[derive(Subcommand)]
+enum OtpActions {
+    #[command(about = "Store an otpauth:// URI or base32 secret for an entry, read from a prompt")]
+    Set { name: String },
+    #[command(about = "Remove the 2FA secret of an entry")]
+    Remove { name: String },
+    #[command(about = "Import an otpauth-migration:// export from Google Authenticator")]
+    Import {
+        #[arg(help = "The export URI, prompted for when omitted so it stays out of the shell history")]
+        uri: Option<String>,
+    },
+}
+
+#[derive(Subcommand)]
+enum TwoFactorActions {
+    #[command(about = "Generate a TOTP seed and recovery codes")]
+    Enable {},
+    #[command(about = "Stop asking for a code, needs the master password and a code")]
+    Disable {
+        #[arg(long, help = "Key file the vault is bound to")]
+        keyfile: Option<PathBuf>,
+    },
+    #[command(about = "Show whether a code is required and how many recovery codes are left")]
+    Status {},
+}
+
+#[derive(Subcommand)]
+enum RecoveryActions {
+    #[command(about = "Make a new recovery kit, the shares of a previous one stop working")]
+    Split {
+        #[arg(long, default_value_t = 5, help = "Number of shares to hand out")]
+        shares: u8,
+        #[arg(long, default_value_t = 3, help = "Number of shares needed to recover")]
+        threshold: u8,
+        #[arg(long, help = "Also print each share as a QR code")]
+        qr: bool,
+        #[arg(long, help = "Key file the vault is bound to")]
+        keyfile: Option<PathBuf>,
+    },
+    #[command(about = "Enter enough shares to set a new master password")]
+    Combine {},
+    #[command(about = "Show the current recovery kit")]
+    Status {},
+}
+
+#[derive(Subcommand)]
+enum EmergencyKitActions {
+    #[command(about = "Check that a kit still matches the vault and its sealed key opens it")]
+    Verify { file: PathBuf },
+    #[command(about = "Set a new master password using the sealed key of a kit")]
+    Restore { file: PathBuf },
+}
+
+#[derive(Subcommand)]
+enum VaultActions {
+    #[command(about = "Create a vault with its own master password, like kofl init")]
+    Create {
+        name: String,
+        #[arg(long, help = "Skip the setup wizard and keep the default settings")]
+        defaults: bool,
+        #[arg(long, conflicts_with = "defaults", help = "Read the settings from a TOML file instead of asking")]
+        config_file: Option<PathBuf>,
+        #[arg(long = "2fa", help = "Also set up a TOTP code required at login")]
+        two_factor: bool,
+        #[arg(long, help = "Bind the vault to a key file, created when it does not exist")]
+        keyfile: Option<PathBuf>,
+    },
+    #[command(about = "List the vaults, the one in use is marked with *")]
+    List {},
+    #[command(about = "Use this vault when neither --vault nor KOFL_VAULT is given")]
+    Use { name: String },
+    #[command(about = "Delete a vault with its session, its backups are kept")]
+    Delete {
+        name: String,
+        #[arg(long, help = "Key file the vault is bound to")]
+        keyfile: Option<PathBuf>,
+    },
+}
+
+#[derive(Subcommand)]
+enum KeyfileActions {
+    #[command(about = "Bind the vault to a new key file and re-encrypt it, also binds a vault without one")]
+    Rotate {
+        #[arg(long, help = "Key file the vault is currently bound to")]
+        keyfile: Option<PathBuf>,
+        #[arg(long, help = "New key file, created when it does not exist")]
+        new: PathBuf,
+    },
+}
+
+#[derive(Subcommand)]
+enum PolicyActions {
+    #[command(about = "Show the policy of an entry or folder (ending with /), or the base policy and all overrides")]
+    Show { target: Option<String> },
+    #[command(about = "Override one policy field for an entry or a folder (ending with /)")]
+    Set { target: String, field: String, value: String },
+    #[command(about = "Remove one overridden field, or the whole override if no field is given")]
+    Unset { target: String, field: Option<String> },
+}
+
+
+// logs go to stderr, stdout carries what the commands print, like `kofl audit --json`
 fn init_logger() {
     #[cfg(feature = "prod")]
     {
@@ -70,7 +465,7 @@ fn init_logger() {
                     record.args()
                 )
             })
-            .target(Target::Stdout)
+            .target(Target::Stderr)
             .init();
     }
 
@@ -95,14 +490,52 @@ fn init_logger() {
                     record.args()
                 )
             })
-            .target(Target::Stdout)
+            .target(Target::Stderr)
             .init();
     }
 }
 
+/// One line on every command while some password is past its max age.
+fn warn_about_overdue_passwords(context: &Context) {
+    if !context.kgc.borrow().is_master_key_provided() {
+        return;
+    }
+    if let Ok(entries) = context.db.list_entries() {
+        let overdue = rotation::overdue(&context.kgc.borrow(), &entries, chrono::Utc::now());
+        if !overdue.is_empty() {
+            warn!("{} password(s) are overdue for rotation, see `kofl list --expiring`", overdue.len());
+        }
+    }
+}
+
+// files others can change refuse the start, files others can read only warn
+fn check_file_permissions() {
+    // only reads the config, loading it would write a fresh one before the check
+    let backup_dir = match config::Config::KoflGlobalConfig::new().read_config_from_toml_file() {
+        Ok(kgc) => permissions::backup_dir(&kgc.get_settings()),
+        Err(_) => vault::active().backups,
+    };
+    let findings = permissions::inspect(&permissions::kofl_paths(&backup_dir));
+    for finding in &findings {
+        if finding.is_fatal() {
+            error!("{}", finding.describe());
+        } else {
+            warn!("{}", finding.describe());
+        }
+    }
+    if findings.iter().any(|finding| finding.is_fatal()) {
+        error!("Refusing to continue, run `kofl doctor --fix-permissions` to make the files private");
+        process::exit(1);
+    }
+    if !findings.is_empty() {
+        warn!("Run `kofl doctor --fix-permissions` to make them private");
+    }
+}
+
 fn execute_command<T: Command>(cmd: &T, context: &Context) {
     if cmd.validate(context) {
         if cmd.execute(context) {
+            context.ss.record_activity();
             cmd.display();
         } else {
             debug!("Error during execution");
@@ -116,6 +549,57 @@ fn main() {
     // Initialize the logger
     init_logger();
 
+    let cli = Cli::parse();
+
+    // runs detached without a terminal, it does not need the vault
+    if let Commands::ClipboardDaemon { timeout, pastes } = &cli.command {
+        let code = if clipboard::serve(*timeout, *pastes).is_ok() { 0 } else { 1 };
+        process::exit(code);
+    }
+
+    if let Err(e) = vault::migrate_legacy() {
+        error!("Could not move the kofl files out of the home directory: {}", e);
+        process::exit(1);
+    }
+
+    // create and delete work on the vault they name
+    let vault_name = match &cli.command {
+        Commands::Vault { action: VaultActions::Create { name, .. } | VaultActions::Delete { name, .. } } => name.clone(),
+        _ => vault::resolve(cli.vault.as_deref()),
+    };
+    if let Err(e) = vault::validate_name(&vault_name) {
+        error!("{}", e);
+        process::exit(1);
+    }
+    // nothing of a new vault exists to load, its command makes the files once the name is free
+    if let Commands::Vault { action: VaultActions::Create { name, defaults, config_file, two_factor, keyfile } } = &cli.command {
+        vault::select(name);
+        let vault_command = VaultCmd::new(VaultAction::Create {
+            name: name.clone(),
+            defaults: *defaults,
+            config_file: config_file.clone(),
+            two_factor: *two_factor,
+            keyfile: keyfile.clone(),
+        });
+        if !vault_command.create_new() {
+            process::exit(1);
+        }
+        vault_command.display();
+        return;
+    }
+    if !vault::exists(&vault_name) {
+        error!("No vault named {}, `kofl vault create {}` makes one", vault_name, vault_name);
+        process::exit(1);
+    }
+    vault::select(&vault_name);
+    if let Err(e) = vault::active().create_dirs() {
+        error!("{}", e);
+        process::exit(1);
+    }
+    if !matches!(cli.command, Commands::Doctor { .. }) {
+        check_file_permissions();
+    }
+
     let context = Context::new().unwrap_or_else(|err| {
         error!("Program terminated due to setup issues: {}", err);
         process::exit(1);
@@ -124,46 +608,198 @@ fn main() {
     debug!("{:?}", context.kgc);
     debug!("{:?}", context.ss);
 
-    let cli = Cli::parse();
+    warn_about_overdue_passwords(&context);
 
     match &cli.command {
-        Commands::Init {} => {
-            let init_command = InitCmd::new();
+        Commands::Init { defaults, config_file, two_factor, keyfile } => {
+            let init_command = InitCmd::new(*defaults, config_file.clone(), *two_factor, keyfile.clone());
             execute_command(&init_command, &context);
         }
-        Commands::Add { name, suggest   } => {
+        Commands::Add { name, suggest, generator, save_rules, username, url, print } => {
             //info!("add commend with name {} and suggest flag is set to {}", name , suggest);
-            if (*suggest) {
-                let add_command = AddCmd::new(name.to_string(), String::from(""), *suggest);
+            // any generator option implies --suggest
+            let rules = generator.to_rules();
+            let suggest = *suggest || !rules.is_empty();
+            if (suggest) {
+                let add_command = AddCmd::new(name.to_string(), String::from(""), suggest, rules, *save_rules, username.clone(), url.clone(), *print);
                 execute_command(&add_command, &context);
             }else {
                 let pwd = rpassword::prompt_password("Enter the password for the entry ===> ").unwrap();
-                let add_command = AddCmd::new(name.to_string(), pwd, *suggest);
+                let add_command = AddCmd::new(name.to_string(), pwd, suggest, rules, *save_rules, username.clone(), url.clone(), *print);
                 execute_command(&add_command, &context);
             }
         }
-        Commands::Update { ent_name, suggest} => {
+        Commands::Update { ent_name, suggest, generator, save_rules, username, url, print } => {
 
-            if (*suggest) {
-                let update_command = UpdateCmd::new(ent_name.to_string(), String::from(""), *suggest);
+            let rules = generator.to_rules();
+            let suggest = *suggest || !rules.is_empty();
+            if (suggest) {
+                let update_command = UpdateCmd::new(ent_name.to_string(), String::from(""), suggest, rules, *save_rules, username.clone(), url.clone(), *print);
                 execute_command(&update_command, &context);
             }else {
                 let pwd = rpassword::prompt_password("Enter the password for the entry ===> ").unwrap();
-                let update_command = UpdateCmd::new(ent_name.to_string(), pwd, *suggest);
+                let update_command = UpdateCmd::new(ent_name.to_string(), pwd, suggest, rules, *save_rules, username.clone(), url.clone(), *print);
                 execute_command(&update_command, &context);
             }
         }
-        Commands::Get { ent_name } => {
-            let get_command = GetCmd::new(ent_name.to_string());
+        Commands::Generate { generator, count, entry } => {
+            let generate_command = GenerateCmd::new(generator.to_rules(), *count, entry.clone());
+            execute_command(&generate_command, &context);
+        }
+        Commands::Get { ent_name, print } => {
+            let get_command = GetCmd::new(ent_name.to_string(), *print);
             execute_command(&get_command, &context);
         }
-        Commands::Login {} => {
-            let login_command = LogInCmd::new();
+        Commands::List { expiring, within } => {
+            let list_command = ListCmd::new(*expiring, *within);
+            execute_command(&list_command, &context);
+        }
+        Commands::ClipboardDaemon { .. } => unreachable!("handled before the context is loaded"),
+        Commands::Rotate { name, print } => {
+            let rotate_command = RotateCmd::new(name.to_string(), *print);
+            execute_command(&rotate_command, &context);
+        }
+        Commands::Otp { action, name, copy } => {
+            let otp_action = match action {
+                None => OtpAction::Code { name: name.clone().unwrap_or_default(), copy: *copy },
+                Some(OtpActions::Set { name }) => {
+                    let secret = rpassword::prompt_password("Enter the otpauth:// URI or base32 secret ===> ").unwrap();
+                    OtpAction::Set { name: name.clone(), secret }
+                }
+                Some(OtpActions::Remove { name }) => OtpAction::Remove { name: name.clone() },
+                Some(OtpActions::Import { uri }) => {
+                    let uri = match uri {
+                        Some(uri) => uri.clone(),
+                        None => rpassword::prompt_password("Enter the otpauth-migration:// URI ===> ").unwrap(),
+                    };
+                    OtpAction::Import { uri }
+                }
+            };
+            let otp_command = OtpCmd::new(otp_action);
+            execute_command(&otp_command, &context);
+        }
+        Commands::TwoFactor { action } => {
+            let two_factor_action = match action {
+                TwoFactorActions::Enable {} => TwoFactorAction::Enable,
+                TwoFactorActions::Disable { keyfile } => TwoFactorAction::Disable { keyfile: keyfile.clone() },
+                TwoFactorActions::Status {} => TwoFactorAction::Status,
+            };
+            let two_factor_command = TwoFactorCmd::new(two_factor_action);
+            execute_command(&two_factor_command, &context);
+        }
+        Commands::ChangeMaster { keyfile } => {
+            let change_master_command = ChangeMasterCmd::new(keyfile.clone());
+            execute_command(&change_master_command, &context);
+        }
+        Commands::Recovery { action } => {
+            let recovery_action = match action {
+                RecoveryActions::Split { shares, threshold, qr, keyfile } => RecoveryAction::Split {
+                    shares: *shares,
+                    threshold: *threshold,
+                    qr: *qr,
+                    keyfile: keyfile.clone(),
+                },
+                RecoveryActions::Combine {} => RecoveryAction::Combine,
+                RecoveryActions::Status {} => RecoveryAction::Status,
+            };
+            let recovery_command = RecoveryCmd::new(recovery_action);
+            execute_command(&recovery_command, &context);
+        }
+        Commands::EmergencyKit { action, output, with_key, recovery_codes, keyfile } => {
+            let emergency_kit_action = match action {
+                None => EmergencyKitAction::Create {
+                    output: output.clone(),
+                    with_key: *with_key,
+                    recovery_codes: *recovery_codes,
+                    keyfile: keyfile.clone(),
+                },
+                Some(EmergencyKitActions::Verify { file }) => EmergencyKitAction::Verify { file: file.clone() },
+                Some(EmergencyKitActions::Restore { file }) => EmergencyKitAction::Restore { file: file.clone() },
+            };
+            let emergency_kit_command = EmergencyKitCmd::new(emergency_kit_action);
+            execute_command(&emergency_kit_command, &context);
+        }
+        Commands::Vault { action } => {
+            let vault_action = match action {
+                VaultActions::Create { .. } => unreachable!("vault create runs before the context is loaded"),
+                VaultActions::List {} => VaultAction::List,
+                VaultActions::Use { name } => VaultAction::Use { name: name.clone() },
+                VaultActions::Delete { name, keyfile } => VaultAction::Delete { name: name.clone(), keyfile: keyfile.clone() },
+            };
+            let vault_command = VaultCmd::new(vault_action);
+            execute_command(&vault_command, &context);
+        }
+        Commands::Doctor { fix_permissions } => {
+            let doctor_command = DoctorCmd::new(*fix_permissions);
+            execute_command(&doctor_command, &context);
+        }
+        Commands::Keyfile { action } => {
+            let keyfile_action = match action {
+                KeyfileActions::Rotate { keyfile, new } => KeyfileAction::Rotate { current: keyfile.clone(), new: new.clone() },
+            };
+            let keyfile_command = KeyfileCmd::new(keyfile_action);
+            execute_command(&keyfile_command, &context);
+        }
+        Commands::Login { keyfile } => {
+            let login_command = LogInCmd::new(keyfile.clone());
             execute_command(&login_command, &context);
         }
-        Commands::Destroy {  } => {
-            let destroy_command = DestroyCmd::new();
+        Commands::Logout {} => {
+            let logout_command = LogoutCmd::new();
+            execute_command(&logout_command, &context);
+        }
+        Commands::Destroy { keyfile } => {
+            let destroy_command = DestroyCmd::new(keyfile.clone());
             execute_command(&destroy_command, &context);
         }
+        Commands::Backup { action } => {
+            let backup_action = match action {
+                BackupActions::List {} => BackupAction::List,
+                BackupActions::Verify { name } => BackupAction::Verify(name.clone()),
+                BackupActions::Prune {} => BackupAction::Prune,
+                BackupActions::Restore { name, file } => BackupAction::Restore { name: name.clone(), file: file.clone() },
+                BackupActions::Passphrase { remove } => BackupAction::Passphrase { remove: *remove },
+            };
+            let backup_command = BackupCmd::new(backup_action);
+            execute_command(&backup_command, &context);
+        }
+        Commands::Config { action } => {
+            let config_action = match action {
+                ConfigActions::Get { key } => ConfigAction::Get(key.clone()),
+                ConfigActions::Set { key, value } => ConfigAction::Set(key.clone(), value.clone()),
+                ConfigActions::List {} => ConfigAction::List,
+                ConfigActions::Reset { key } => ConfigAction::Reset(key.clone()),
+            };
+            let config_command = ConfigCmd::new(config_action);
+            execute_command(&config_command, &context);
+        }
+        Commands::Policy { action } => {
+            let policy_action = match action {
+                PolicyActions::Show { target } => PolicyAction::Show(target.clone()),
+                PolicyActions::Set { target, field, value } => PolicyAction::Set {
+                    target: target.clone(),
+                    field: field.clone(),
+                    value: value.clone(),
+                },
+                PolicyActions::Unset { target, field } => PolicyAction::Unset {
+                    target: target.clone(),
+                    field: field.clone(),
+                },
+            };
+            let policy_command = PolicyCmd::new(policy_action);
+            execute_command(&policy_command, &context);
+        }
+        Commands::Audit { action, json, max_age_days, min_health } => {
+            let audit_action = match action {
+                None => AuditAction::Report { json: *json, max_age_days: *max_age_days, min_health: *min_health },
+                Some(AuditActions::Breaches { hibp_file }) => AuditAction::Breaches { hibp_file: hibp_file.clone() },
+            };
+            let audit_command = AuditCmd::new(audit_action);
+            execute_command(&audit_command, &context);
+            // scheduled jobs rely on the exit code
+            if !audit_command.passed() {
+                process::exit(1);
+            }
+        }
     }
 }
@@ -0,0 +1,204 @@
+//! Import of Google Authenticator `otpauth-migration://offline?data=...` exports.
+//!
+//! `data` is a base64 `MigrationPayload` protobuf. Only the few fields needed
+//! are decoded by hand:
+//!
+//! ```text
+//! MigrationPayload { repeated OtpParameters otp_parameters = 1; ... }
+//! OtpParameters {
+//!     bytes secret = 1; string name = 2; string issuer = 3;
+//!     Algorithm algorithm = 4;   // 1 SHA1, 2 SHA256, 3 SHA512
+//!     DigitCount digits = 5;     // 1 six, 2 eight
+//!     OtpType type = 6;          // 1 HOTP, 2 TOTP
+//!     int64 counter = 7;
+//! }
+//! ```
+
+use super::{percent_decode, Algorithm, OtpKind, OtpSecret};
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+
+const SCHEME: &str = "otpauth-migration://";
+
+enum Value<'a> {
+    Varint(u64),
+    Bytes(&'a [u8]),
+}
+
+struct Reader<'a> {
+    buf: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn new(buf: &'a [u8]) -> Self {
+        Reader { buf, pos: 0 }
+    }
+
+    fn varint(&mut self) -> Result<u64, String> {
+        let mut value = 0u64;
+        for shift in (0..64).step_by(7) {
+            let byte = *self.buf.get(self.pos).ok_or("Truncated migration data")?;
+            self.pos += 1;
+            value |= ((byte & 0x7f) as u64) << shift;
+            if byte & 0x80 == 0 {
+                return Ok(value);
+            }
+        }
+        Err("Malformed varint in migration data".to_string())
+    }
+
+    fn take(&mut self, len: usize) -> Result<&'a [u8], String> {
+        let end = self.pos.checked_add(len).filter(|&end| end <= self.buf.len()).ok_or("Truncated migration data")?;
+        let bytes = &self.buf[self.pos..end];
+        self.pos = end;
+        Ok(bytes)
+    }
+
+    /// Next field number and value, fixed width fields are skipped.
+    fn field(&mut self) -> Result<Option<(u64, Value<'a>)>, String> {
+        while self.pos < self.buf.len() {
+            let key = self.varint()?;
+            let (number, wire_type) = (key >> 3, key & 0x07);
+            match wire_type {
+                0 => return Ok(Some((number, Value::Varint(self.varint()?)))),
+                2 => {
+                    let len = self.varint()? as usize;
+                    return Ok(Some((number, Value::Bytes(self.take(len)?))));
+                }
+                1 => {
+                    self.take(8)?;
+                }
+                5 => {
+                    self.take(4)?;
+                }
+                _ => return Err(format!("Unsupported protobuf wire type {}", wire_type)),
+            }
+        }
+        Ok(None)
+    }
+}
+
+/// Decodes every account of a migration URI.
+pub fn parse_uri(uri: &str) -> Result<Vec<OtpSecret>, String> {
+    let uri = uri.trim();
+    if !uri.to_lowercase().starts_with(SCHEME) {
+        return Err("Not an otpauth-migration:// URI".to_string());
+    }
+    let query = uri.split_once('?').map(|(_, query)| query).unwrap_or("");
+    let data = query
+        .split('&')
+        .find_map(|pair| pair.strip_prefix("data="))
+        .ok_or("The migration URI has no data parameter")?;
+    // '+' is part of the base64 alphabet here, not an encoded space
+    let data = percent_decode(&data.replace('+', "%2B"));
+    let payload = STANDARD
+        .decode(data.trim())
+        .map_err(|e| format!("Invalid migration data: {}", e))?;
+    parse_payload(&payload)
+}
+
+pub fn parse_payload(payload: &[u8]) -> Result<Vec<OtpSecret>, String> {
+    let mut reader = Reader::new(payload);
+    let mut secrets = Vec::new();
+    while let Some((number, value)) = reader.field()? {
+        if let (1, Value::Bytes(parameters)) = (number, value) {
+            secrets.push(parse_parameters(parameters)?);
+        }
+    }
+    Ok(secrets)
+}
+
+fn parse_parameters(buf: &[u8]) -> Result<OtpSecret, String> {
+    let mut reader = Reader::new(buf);
+    let mut otp = OtpSecret::totp(Vec::new());
+    let mut hotp = false;
+    let mut counter = 0;
+    let mut name = String::new();
+
+    while let Some((number, value)) = reader.field()? {
+        match (number, value) {
+            (1, Value::Bytes(secret)) => otp.secret = secret.to_vec(),
+            (2, Value::Bytes(bytes)) => name = String::from_utf8_lossy(bytes).into_owned(),
+            (3, Value::Bytes(bytes)) => otp.issuer = Some(String::from_utf8_lossy(bytes).into_owned()).filter(|i| !i.is_empty()),
+            (4, Value::Varint(algorithm)) => {
+                otp.algorithm = match algorithm {
+                    0 | 1 => Algorithm::Sha1,
+                    2 => Algorithm::Sha256,
+                    3 => Algorithm::Sha512,
+                    _ => return Err(format!("Unsupported algorithm {} in migration data", algorithm)),
+                }
+            }
+            (5, Value::Varint(digits)) => otp.digits = if digits == 2 { 8 } else { 6 },
+            (6, Value::Varint(kind)) => hotp = kind == 1,
+            (7, Value::Varint(value)) => counter = value,
+            _ => {}
+        }
+    }
+
+    // the name is usually "Issuer:account", like an otpauth label
+    otp.account = match name.split_once(':') {
+        Some((issuer, account)) => {
+            if otp.issuer.is_none() {
+                otp.issuer = Some(issuer.trim().to_string());
+            }
+            Some(account.trim().to_string())
+        }
+        None => Some(name.trim().to_string()),
+    }
+    .filter(|account| !account.is_empty());
+
+    if hotp {
+        otp.kind = OtpKind::Hotp { counter };
+    }
+    otp.validate()?;
+    Ok(otp)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn field(number: u8, bytes: &[u8]) -> Vec<u8> {
+        let mut out = vec![(number << 3) | 2, bytes.len() as u8];
+        out.extend_from_slice(bytes);
+        out
+    }
+
+    fn varint_field(number: u8, value: u8) -> Vec<u8> {
+        vec![number << 3, value]
+    }
+
+    #[test]
+    fn test_parse_migration_uri() {
+        let totp = [
+            field(1, b"Hello!\xde\xad\xbe\xef"),
+            field(2, b"GitHub:alice"),
+            field(3, b"GitHub"),
+            varint_field(4, 1),
+            varint_field(5, 1),
+            varint_field(6, 2),
+        ]
+        .concat();
+        let hotp = [field(1, b"12345678901234567890"), field(2, b"bob@example.com"), varint_field(5, 2), varint_field(6, 1), varint_field(7, 5)].concat();
+        // version = 1, batch_size = 1 after the accounts
+        let payload = [field(1, &totp), field(1, &hotp), varint_field(2, 1), varint_field(3, 1)].concat();
+        let uri = format!("otpauth-migration://offline?data={}", STANDARD.encode(&payload).replace('=', "%3D"));
+
+        let secrets = parse_uri(&uri).unwrap();
+        assert_eq!(secrets.len(), 2);
+        assert_eq!(secrets[0].issuer.as_deref(), Some("GitHub"));
+        assert_eq!(secrets[0].account.as_deref(), Some("alice"));
+        assert_eq!(secrets[0].kind, OtpKind::Totp { period: 30 });
+        assert_eq!(secrets[1].issuer, None);
+        assert_eq!(secrets[1].digits, 8);
+        assert_eq!(secrets[1].kind, OtpKind::Hotp { counter: 5 });
+        assert_eq!(secrets[1].hotp(0), "84755224");
+    }
+
+    #[test]
+    fn test_rejects_truncated_data() {
+        assert!(parse_payload(&[0x0a, 0x10, 0x0a]).is_err());
+        assert!(parse_uri("otpauth://totp/x?secret=AAAA").is_err());
+    }
+}
@@ -0,0 +1,343 @@
+//! One-time passwords, HOTP (RFC 4226) and TOTP (RFC 6238).
+//!
+//! A secret is kept as the `otpauth://` URI authenticator apps read from QR
+//! codes (https://github.com/google/google-authenticator/wiki/Key-Uri-Format),
+//! so the algorithm, digits and period travel with it.
+
+pub mod migration;
+
+use hmac::{Hmac, Mac};
+use sha1::Sha1;
+use sha2::{Sha256, Sha512};
+use std::fmt;
+
+const BASE32_ALPHABET: &[u8; 32] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZ234567";
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Algorithm {
+    Sha1,
+    Sha256,
+    Sha512,
+}
+
+impl fmt::Display for Algorithm {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Algorithm::Sha1 => write!(f, "SHA1"),
+            Algorithm::Sha256 => write!(f, "SHA256"),
+            Algorithm::Sha512 => write!(f, "SHA512"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum OtpKind {
+    Totp { period: u64 },
+    // the counter of the next code
+    Hotp { counter: u64 },
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct OtpSecret {
+    pub kind: OtpKind,
+    pub secret: Vec<u8>,
+    pub algorithm: Algorithm,
+    pub digits: u32,
+    pub issuer: Option<String>,
+    pub account: Option<String>,
+}
+
+impl OtpSecret {
+    /// A TOTP secret with the usual defaults: SHA1, 6 digits, 30 seconds.
+    pub fn totp(secret: Vec<u8>) -> Self {
+        OtpSecret {
+            kind: OtpKind::Totp { period: 30 },
+            secret,
+            algorithm: Algorithm::Sha1,
+            digits: 6,
+            issuer: None,
+            account: None,
+        }
+    }
+
+    /// Accepts an `otpauth://` URI or a bare base32 TOTP secret.
+    pub fn parse(input: &str) -> Result<Self, String> {
+        let input = input.trim();
+        if input.to_lowercase().starts_with("otpauth://") {
+            OtpSecret::from_uri(input)
+        } else {
+            Ok(OtpSecret::totp(base32_decode(input)?))
+        }
+    }
+
+    pub fn from_uri(uri: &str) -> Result<Self, String> {
+        let rest = uri
+            .get("otpauth://".len()..)
+            .filter(|_| uri.to_lowercase().starts_with("otpauth://"))
+            .ok_or("Not an otpauth:// URI")?;
+        let (kind, rest) = rest.split_once('/').ok_or("The otpauth URI has no label")?;
+        let (label, query) = rest.split_once('?').unwrap_or((rest, ""));
+
+        let label = percent_decode(label);
+        let (label_issuer, account) = match label.split_once(':') {
+            Some((issuer, account)) => (Some(issuer.trim().to_string()), account.trim().to_string()),
+            None => (None, label.trim().to_string()),
+        };
+
+        let mut secret = None;
+        let mut otp = OtpSecret::totp(Vec::new());
+        let mut period = 30;
+        let mut counter = None;
+        otp.issuer = label_issuer;
+        otp.account = Some(account).filter(|a| !a.is_empty());
+
+        for pair in query.split('&').filter(|p| !p.is_empty()) {
+            let (key, value) = pair.split_once('=').unwrap_or((pair, ""));
+            let value = percent_decode(value);
+            match key.to_lowercase().as_str() {
+                "secret" => secret = Some(base32_decode(&value)?),
+                "issuer" => otp.issuer = Some(value).filter(|v| !v.is_empty()),
+                "algorithm" => {
+                    otp.algorithm = match value.to_uppercase().as_str() {
+                        "SHA1" => Algorithm::Sha1,
+                        "SHA256" => Algorithm::Sha256,
+                        "SHA512" => Algorithm::Sha512,
+                        _ => return Err(format!("Unsupported OTP algorithm '{}'", value)),
+                    }
+                }
+                "digits" => otp.digits = value.parse().map_err(|_| format!("Invalid digits '{}'", value))?,
+                "period" => period = value.parse().map_err(|_| format!("Invalid period '{}'", value))?,
+                "counter" => counter = Some(value.parse().map_err(|_| format!("Invalid counter '{}'", value))?),
+                // image, color and other app specific parameters
+                _ => {}
+            }
+        }
+
+        otp.secret = secret.ok_or("The otpauth URI has no secret")?;
+        otp.kind = match kind.to_lowercase().as_str() {
+            "totp" => OtpKind::Totp { period },
+            "hotp" => OtpKind::Hotp { counter: counter.ok_or("An HOTP URI needs a counter")? },
+            _ => return Err(format!("Unknown OTP type '{}'", kind)),
+        };
+        otp.validate()?;
+        Ok(otp)
+    }
+
+    pub fn validate(&self) -> Result<(), String> {
+        if self.secret.is_empty() {
+            return Err("The OTP secret is empty".to_string());
+        }
+        if !(6..=8).contains(&self.digits) {
+            return Err(format!("OTP codes have 6 to 8 digits, not {}", self.digits));
+        }
+        if self.kind == (OtpKind::Totp { period: 0 }) {
+            return Err("The TOTP period cannot be 0".to_string());
+        }
+        Ok(())
+    }
+
+    pub fn to_uri(&self) -> String {
+        let label = match (&self.issuer, &self.account) {
+            (Some(issuer), Some(account)) => format!("{}:{}", percent_encode(issuer), percent_encode(account)),
+            (Some(issuer), None) => percent_encode(issuer),
+            (None, Some(account)) => percent_encode(account),
+            (None, None) => String::new(),
+        };
+        let (kind, extra) = match self.kind {
+            OtpKind::Totp { period } => ("totp", format!("period={}", period)),
+            OtpKind::Hotp { counter } => ("hotp", format!("counter={}", counter)),
+        };
+        let mut uri = format!(
+            "otpauth://{}/{}?secret={}&algorithm={}&digits={}&{}",
+            kind,
+            label,
+            base32_encode(&self.secret),
+            self.algorithm,
+            self.digits,
+            extra
+        );
+        if let Some(issuer) = &self.issuer {
+            uri.push_str(&format!("&issuer={}", percent_encode(issuer)));
+        }
+        uri
+    }
+
+    /// "issuer (account)" or whichever part is known.
+    pub fn label(&self) -> String {
+        match (&self.issuer, &self.account) {
+            (Some(issuer), Some(account)) => format!("{} ({})", issuer, account),
+            (Some(name), None) | (None, Some(name)) => name.clone(),
+            (None, None) => "unnamed".to_string(),
+        }
+    }
+
+    /// RFC 4226 code for `counter`.
+    pub fn hotp(&self, counter: u64) -> String {
+        let message = counter.to_be_bytes();
+        let digest = match self.algorithm {
+            Algorithm::Sha1 => hmac_digest::<Hmac<Sha1>>(&self.secret, &message),
+            Algorithm::Sha256 => hmac_digest::<Hmac<Sha256>>(&self.secret, &message),
+            Algorithm::Sha512 => hmac_digest::<Hmac<Sha512>>(&self.secret, &message),
+        };
+
+        // dynamic truncation
+        let offset = (digest[digest.len() - 1] & 0x0f) as usize;
+        let binary = u32::from_be_bytes([digest[offset] & 0x7f, digest[offset + 1], digest[offset + 2], digest[offset + 3]]);
+        let code = binary as u64 % 10u64.pow(self.digits);
+        format!("{:0width$}", code, width = self.digits as usize)
+    }
+
+    /// RFC 6238 code at `unix_time` and the seconds it stays valid, None for HOTP secrets.
+    pub fn totp_at(&self, unix_time: u64) -> Option<(String, u64)> {
+        match self.kind {
+            OtpKind::Totp { period } => Some((self.hotp(unix_time / period), period - unix_time % period)),
+            OtpKind::Hotp { .. } => None,
+        }
+    }
+}
+
+fn hmac_digest<M: Mac + hmac::digest::KeyInit>(key: &[u8], message: &[u8]) -> Vec<u8> {
+    let mut mac = <M as hmac::digest::KeyInit>::new_from_slice(key).expect("HMAC accepts keys of any length");
+    mac.update(message);
+    mac.finalize().into_bytes().to_vec()
+}
+
+/// RFC 4648 base32, case-insensitive, spaces, dashes and padding are ignored.
+pub fn base32_decode(input: &str) -> Result<Vec<u8>, String> {
+    let mut bits: u64 = 0;
+    let mut bit_count = 0;
+    let mut out = Vec::new();
+    for c in input.chars().filter(|c| !c.is_whitespace() && *c != '-' && *c != '=') {
+        let value = BASE32_ALPHABET
+            .iter()
+            .position(|&a| a == c.to_ascii_uppercase() as u8)
+            .ok_or_else(|| format!("'{}' is not a base32 character", c))?;
+        bits = (bits << 5) | value as u64;
+        bit_count += 5;
+        if bit_count >= 8 {
+            bit_count -= 8;
+            out.push((bits >> bit_count) as u8);
+            bits &= (1 << bit_count) - 1;
+        }
+    }
+    if out.is_empty() {
+        return Err("The OTP secret is empty".to_string());
+    }
+    Ok(out)
+}
+
+/// Unpadded base32, as authenticator apps expect it.
+pub fn base32_encode(bytes: &[u8]) -> String {
+    let mut out = String::new();
+    let mut bits: u64 = 0;
+    let mut bit_count = 0;
+    for &byte in bytes {
+        bits = (bits << 8) | byte as u64;
+        bit_count += 8;
+        while bit_count >= 5 {
+            bit_count -= 5;
+            out.push(BASE32_ALPHABET[((bits >> bit_count) & 0x1f) as usize] as char);
+        }
+    }
+    if bit_count > 0 {
+        out.push(BASE32_ALPHABET[((bits << (5 - bit_count)) & 0x1f) as usize] as char);
+    }
+    out
+}
+
+pub(crate) fn percent_decode(input: &str) -> String {
+    let bytes = input.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'%' if i + 2 < bytes.len() => {
+                let hex = std::str::from_utf8(&bytes[i + 1..i + 3]).ok();
+                match hex.and_then(|hex| u8::from_str_radix(hex, 16).ok()) {
+                    Some(byte) => {
+                        out.push(byte);
+                        i += 3;
+                        continue;
+                    }
+                    None => out.push(b'%'),
+                }
+            }
+            b'+' => out.push(b' '),
+            byte => out.push(byte),
+        }
+        i += 1;
+    }
+    String::from_utf8_lossy(&out).into_owned()
+}
+
+fn percent_encode(input: &str) -> String {
+    input
+        .bytes()
+        .map(|b| match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' | b'@' => (b as char).to_string(),
+            _ => format!("%{:02X}", b),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rfc_secret(algorithm: Algorithm, len: usize) -> OtpSecret {
+        let seed: Vec<u8> = b"1234567890".iter().cycle().take(len).copied().collect();
+        OtpSecret { algorithm, digits: 8, ..OtpSecret::totp(seed) }
+    }
+
+    #[test]
+    fn test_rfc4226_hotp_vectors() {
+        let secret = OtpSecret { digits: 6, ..rfc_secret(Algorithm::Sha1, 20) };
+        let expected = ["755224", "287082", "359152", "969429", "338314", "254676", "287922", "162583", "399871", "520489"];
+        for (counter, code) in expected.iter().enumerate() {
+            assert_eq!(secret.hotp(counter as u64), *code);
+        }
+    }
+
+    #[test]
+    fn test_rfc6238_totp_vectors() {
+        let vectors: [(u64, &str, &str, &str); 6] = [
+            (59, "94287082", "46119246", "90693936"),
+            (1111111109, "07081804", "68084774", "25091201"),
+            (1111111111, "14050471", "67062674", "99943326"),
+            (1234567890, "89005924", "91819424", "93441116"),
+            (2000000000, "69279037", "90698825", "38618901"),
+            (20000000000, "65353130", "77737706", "47863826"),
+        ];
+        let sha1 = rfc_secret(Algorithm::Sha1, 20);
+        let sha256 = rfc_secret(Algorithm::Sha256, 32);
+        let sha512 = rfc_secret(Algorithm::Sha512, 64);
+        for (time, code1, code256, code512) in vectors {
+            assert_eq!(sha1.totp_at(time).unwrap().0, code1);
+            assert_eq!(sha256.totp_at(time).unwrap().0, code256);
+            assert_eq!(sha512.totp_at(time).unwrap().0, code512);
+        }
+        assert_eq!(sha1.totp_at(59).unwrap().1, 1);
+    }
+
+    #[test]
+    fn test_uri_roundtrip() {
+        let uri = "otpauth://totp/ACME%20Co:john.doe@email.com?secret=HXDMVJECJJWSRB3HWIZR4IFUGFTMXBOZ&issuer=ACME%20Co&algorithm=SHA256&digits=8&period=60";
+        let otp = OtpSecret::parse(uri).unwrap();
+        assert_eq!(otp.issuer.as_deref(), Some("ACME Co"));
+        assert_eq!(otp.account.as_deref(), Some("john.doe@email.com"));
+        assert_eq!(otp.algorithm, Algorithm::Sha256);
+        assert_eq!(otp.digits, 8);
+        assert_eq!(otp.kind, OtpKind::Totp { period: 60 });
+        assert_eq!(base32_encode(&otp.secret), "HXDMVJECJJWSRB3HWIZR4IFUGFTMXBOZ");
+        assert_eq!(OtpSecret::parse(&otp.to_uri()).unwrap(), otp);
+    }
+
+    #[test]
+    fn test_bare_base32_secret() {
+        let otp = OtpSecret::parse("jbsw y3dp ehpk 3pxp").unwrap();
+        assert_eq!(otp.secret, b"Hello!\xde\xad\xbe\xef");
+        assert_eq!(otp.kind, OtpKind::Totp { period: 30 });
+        assert!(OtpSecret::parse("not base32!").is_err());
+        assert!(OtpSecret::parse("otpauth://hotp/x?secret=JBSWY3DPEHPK3PXP").is_err());
+    }
+}
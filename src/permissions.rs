@@ -0,0 +1,187 @@
+//! Checks that kofl files are private to the user, repaired by `kofl doctor --fix-permissions`.
+//!
+//! Access for others through write permission or a foreign owner is a
+//! refusal to start, anyone who can change the files can change the vault.
+//! Read access for the group or others is only warned about.
+
+use crate::backup::Backup;
+use crate::layout;
+use crate::setting::registry::SettingsRegistry;
+use crate::vault;
+use std::fs;
+use std::os::unix::fs::{MetadataExt, PermissionsExt};
+use std::path::{Path, PathBuf};
+
+const PRIVATE_FILE: u32 = 0o600;
+const PRIVATE_DIR: u32 = 0o700;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Problem {
+    Owner { uid: u32 },
+    Writable { mode: u32 },
+    Readable { mode: u32 },
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Finding {
+    pub path: PathBuf,
+    pub problem: Problem,
+}
+
+impl Finding {
+    pub fn is_fatal(&self) -> bool {
+        !matches!(self.problem, Problem::Readable { .. })
+    }
+
+    pub fn describe(&self) -> String {
+        match self.problem {
+            Problem::Owner { uid } => format!("{} is owned by user {}, not by you", self.path.display(), uid),
+            Problem::Writable { mode } => {
+                format!("{} can be changed by other users (mode {:o})", self.path.display(), mode)
+            }
+            Problem::Readable { mode } => {
+                format!("{} can be read by other users (mode {:o})", self.path.display(), mode)
+            }
+        }
+    }
+
+    /// Makes the path private again, a foreign owner needs `chown` by hand.
+    pub fn fix(&self) -> Result<(), String> {
+        if let Problem::Owner { .. } = self.problem {
+            return Err(format!("{}, fix it with chown", self.describe()));
+        }
+        let mode = if self.path.is_dir() { PRIVATE_DIR } else { PRIVATE_FILE };
+        fs::set_permissions(&self.path, fs::Permissions::from_mode(mode))
+            .map_err(|e| format!("Cannot change the mode of {}: {}", self.path.display(), e))
+    }
+}
+
+// the effective uid, /proc/self belongs to it
+fn current_uid() -> Option<u32> {
+    fs::metadata("/proc/self").ok().map(|metadata| metadata.uid())
+}
+
+/// What is wrong with one path, nothing when it does not exist.
+pub fn check(path: &Path, uid: Option<u32>) -> Option<Finding> {
+    let metadata = fs::metadata(path).ok()?;
+    let mode = metadata.permissions().mode() & 0o777;
+    let problem = match uid {
+        Some(uid) if metadata.uid() != uid => Problem::Owner { uid: metadata.uid() },
+        _ if mode & 0o022 != 0 => Problem::Writable { mode },
+        _ if mode & 0o077 != 0 => Problem::Readable { mode },
+        _ => return None,
+    };
+    Some(Finding { path: path.to_path_buf(), problem })
+}
+
+/// Where the archives live, the backup_location setting may point outside
+/// the data directory.
+pub fn backup_dir(settings: &SettingsRegistry) -> PathBuf {
+    Backup::from_settings(settings)
+        .map(|bc| bc.get_backup_dir().clone())
+        .unwrap_or_else(|_| vault::active().backups)
+}
+
+/// The kofl directories and the files of the selected vault, with the
+/// archives in `backup_dir`.
+pub fn kofl_paths(backup_dir: &Path) -> Vec<PathBuf> {
+    let paths = vault::active();
+    let mut candidates = vec![layout::config_dir(), layout::data_dir(), layout::runtime_dir()];
+    for file in [&paths.config, &paths.storage, &paths.session] {
+        candidates.extend(file.parent().map(Path::to_path_buf));
+        candidates.push(file.clone());
+    }
+    candidates.push(paths.config.with_extension("checksum"));
+    candidates.push(vault::vaults_dir());
+    candidates.push(vault::current_file());
+    candidates.push(backup_dir.to_path_buf());
+    if let Ok(entries) = fs::read_dir(backup_dir) {
+        candidates.extend(entries.filter_map(|entry| entry.ok()).map(|entry| entry.path()).filter(|path| path.is_file()));
+    }
+
+    let mut unique: Vec<PathBuf> = Vec::new();
+    for candidate in candidates {
+        if !unique.contains(&candidate) {
+            unique.push(candidate);
+        }
+    }
+    unique
+}
+
+pub fn inspect(paths: &[PathBuf]) -> Vec<Finding> {
+    let uid = current_uid();
+    paths.iter().filter_map(|path| check(path, uid)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::setting::setting_key::SettingKey;
+    use crate::setting::setting_value::SettingValue;
+    use tempfile::TempDir;
+
+    fn set_mode(path: &Path, mode: u32) {
+        fs::set_permissions(path, fs::Permissions::from_mode(mode)).unwrap();
+    }
+
+    #[test]
+    fn test_check_modes() {
+        let temp_dir = TempDir::new().unwrap();
+        let file = temp_dir.path().join("config.toml");
+        fs::write(&file, "secret").unwrap();
+        let uid = current_uid();
+
+        set_mode(&file, 0o600);
+        assert_eq!(check(&file, uid), None);
+
+        set_mode(&file, 0o644);
+        let finding = check(&file, uid).unwrap();
+        assert_eq!(finding.problem, Problem::Readable { mode: 0o644 });
+        assert!(!finding.is_fatal());
+
+        set_mode(&file, 0o620);
+        let finding = check(&file, uid).unwrap();
+        assert!(finding.is_fatal());
+        finding.fix().unwrap();
+        assert_eq!(check(&file, uid), None);
+
+        set_mode(temp_dir.path(), 0o755);
+        check(temp_dir.path(), uid).unwrap().fix().unwrap();
+        assert_eq!(fs::metadata(temp_dir.path()).unwrap().permissions().mode() & 0o777, 0o700);
+
+        assert_eq!(check(&temp_dir.path().join("missing"), uid), None);
+    }
+
+    #[test]
+    fn test_foreign_owner_is_not_fixed() {
+        let temp_dir = TempDir::new().unwrap();
+        let uid = current_uid().unwrap();
+        let finding = check(temp_dir.path(), Some(uid + 1)).unwrap();
+        assert_eq!(finding.problem, Problem::Owner { uid });
+        assert!(finding.is_fatal());
+        assert!(finding.fix().is_err());
+    }
+
+    #[test]
+    fn test_custom_backup_location_is_inspected() {
+        let temp_dir = TempDir::new().unwrap();
+        let location = temp_dir.path().join("archives");
+        let mut settings = SettingsRegistry::new();
+        settings
+            .update(SettingKey::BackupLocation, SettingValue::String(location.display().to_string()))
+            .unwrap();
+
+        let dir = backup_dir(&settings);
+        assert_eq!(dir, location);
+        let archive = dir.join("backup_20240101_000000.tar.gz");
+        fs::write(&archive, "archive").unwrap();
+        set_mode(&archive, 0o644);
+        set_mode(&dir, 0o750);
+
+        let paths = kofl_paths(&dir);
+        assert!(paths.contains(&dir) && paths.contains(&archive));
+        let findings: Vec<Finding> = inspect(&paths).into_iter().filter(|finding| finding.path.starts_with(&dir)).collect();
+        assert_eq!(findings.len(), 2);
+        assert!(findings.iter().all(|finding| matches!(finding.problem, Problem::Readable { .. })));
+    }
+}
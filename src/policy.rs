@@ -0,0 +1,354 @@
+use crate::config::Config::KoflGlobalConfig;
+use crate::constants::CONS;
+use crate::setting::registry::SettingsRegistry;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// Field names accepted by `kofl policy set|unset`.
+pub const POLICY_FIELDS: &[&str] = &[
+    "min_length",
+    "max_length",
+    "require_uppercase",
+    "require_lowercase",
+    "require_digit",
+    "require_symbol",
+    "symbols",
+    "max_repeated_run",
+    "banned_substrings",
+    "max_age_days",
+];
+
+/// Rules a password has to satisfy.
+///
+/// The base policy comes from the settings, folders (the `folder/` prefix of an
+/// entry name) and single entries can then override any of its fields.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PasswordPolicy {
+    pub min_length: usize,
+    pub max_length: usize,
+    pub require_uppercase: bool,
+    pub require_lowercase: bool,
+    pub require_digit: bool,
+    pub require_symbol: bool,
+    pub symbols: String,
+    // 0 allows runs of any length
+    pub max_repeated_run: usize,
+    pub banned_substrings: Vec<String>,
+    // days before the password is due for rotation, 0 never expires
+    pub max_age_days: usize,
+}
+
+/// Partial policy stored in the config for a folder or an entry.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct PolicyOverride {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub min_length: Option<usize>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_length: Option<usize>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub require_uppercase: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub require_lowercase: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub require_digit: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub require_symbol: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub symbols: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_repeated_run: Option<usize>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub banned_substrings: Option<Vec<String>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_age_days: Option<usize>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum PolicyViolation {
+    TooShort { min: usize, actual: usize },
+    TooLong { max: usize, actual: usize },
+    MissingUppercase,
+    MissingLowercase,
+    MissingDigit,
+    MissingSymbol(String),
+    RepeatedRun(usize),
+    BannedSubstring(String),
+}
+
+impl fmt::Display for PolicyViolation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PolicyViolation::TooShort { min, actual } => {
+                write!(f, "minimum length is {} but the password is {} characters long", min, actual)
+            }
+            PolicyViolation::TooLong { max, actual } => {
+                write!(f, "maximum length is {} but the password is {} characters long", max, actual)
+            }
+            PolicyViolation::MissingUppercase => write!(f, "at least one uppercase letter (A-Z) is required"),
+            PolicyViolation::MissingLowercase => write!(f, "at least one lowercase letter (a-z) is required"),
+            PolicyViolation::MissingDigit => write!(f, "at least one digit (0-9) is required"),
+            PolicyViolation::MissingSymbol(symbols) => {
+                write!(f, "at least one special character ({}) is required", symbols)
+            }
+            PolicyViolation::RepeatedRun(max) => {
+                write!(f, "no character may be repeated more than {} times in a row", max)
+            }
+            PolicyViolation::BannedSubstring(word) => write!(f, "the password must not contain '{}'", word),
+        }
+    }
+}
+
+impl PasswordPolicy {
+    pub fn from_settings(settings: &SettingsRegistry) -> Self {
+        PasswordPolicy {
+            min_length: settings.password_min_length().unwrap_or(CONS::MIN_PASSWORD_LENGTH as u32) as usize,
+            max_length: settings.password_max_length().unwrap_or(128) as usize,
+            require_uppercase: settings.require_uppercase().unwrap_or(true),
+            require_lowercase: settings.require_lowercase().unwrap_or(true),
+            require_digit: settings.require_digits().unwrap_or(true),
+            require_symbol: settings.require_special_chars().unwrap_or(true),
+            symbols: settings.password_symbols().unwrap_or_else(|| "!@#$%^&*".to_string()),
+            max_repeated_run: settings.max_repeated_chars().unwrap_or(3) as usize,
+            banned_substrings: split_list(&settings.banned_substrings().unwrap_or_default()),
+            max_age_days: settings.password_max_age_days().unwrap_or(0) as usize,
+        }
+    }
+
+    /// Policy for `target`, an entry name or a folder ending with `/`.
+    /// Overrides are applied from the outermost folder to the entry itself.
+    pub fn for_entry(kgc: &KoflGlobalConfig, target: &str) -> Self {
+        let mut policy = PasswordPolicy::from_settings(&kgc.get_settings());
+        let overrides = kgc.get_policy_overrides();
+
+        let mut scopes = parent_folders(target);
+        if !target.ends_with('/') {
+            scopes.push(target.to_string());
+        }
+        for scope in scopes {
+            if let Some(policy_override) = overrides.get(&scope) {
+                policy.apply(policy_override);
+            }
+        }
+        policy
+    }
+
+    pub fn apply(&mut self, policy_override: &PolicyOverride) {
+        if let Some(v) = policy_override.min_length { self.min_length = v; }
+        if let Some(v) = policy_override.max_length { self.max_length = v; }
+        if let Some(v) = policy_override.require_uppercase { self.require_uppercase = v; }
+        if let Some(v) = policy_override.require_lowercase { self.require_lowercase = v; }
+        if let Some(v) = policy_override.require_digit { self.require_digit = v; }
+        if let Some(v) = policy_override.require_symbol { self.require_symbol = v; }
+        if let Some(v) = &policy_override.symbols { self.symbols = v.clone(); }
+        if let Some(v) = policy_override.max_repeated_run { self.max_repeated_run = v; }
+        if let Some(v) = &policy_override.banned_substrings { self.banned_substrings = v.clone(); }
+        if let Some(v) = policy_override.max_age_days { self.max_age_days = v; }
+    }
+
+    /// Every rule `password` breaks, empty if it is acceptable.
+    pub fn check(&self, password: &str) -> Vec<PolicyViolation> {
+        let mut violations = Vec::new();
+        let length = password.chars().count();
+
+        if length < self.min_length {
+            violations.push(PolicyViolation::TooShort { min: self.min_length, actual: length });
+        }
+        if length > self.max_length {
+            violations.push(PolicyViolation::TooLong { max: self.max_length, actual: length });
+        }
+        if self.require_uppercase && !password.chars().any(|c| c.is_ascii_uppercase()) {
+            violations.push(PolicyViolation::MissingUppercase);
+        }
+        if self.require_lowercase && !password.chars().any(|c| c.is_ascii_lowercase()) {
+            violations.push(PolicyViolation::MissingLowercase);
+        }
+        if self.require_digit && !password.chars().any(|c| c.is_ascii_digit()) {
+            violations.push(PolicyViolation::MissingDigit);
+        }
+        if self.require_symbol && !password.chars().any(|c| self.symbols.contains(c)) {
+            violations.push(PolicyViolation::MissingSymbol(self.symbols.clone()));
+        }
+        if self.max_repeated_run > 0 && longest_run(password) > self.max_repeated_run {
+            violations.push(PolicyViolation::RepeatedRun(self.max_repeated_run));
+        }
+
+        let lowered = password.to_lowercase();
+        for word in &self.banned_substrings {
+            if !word.is_empty() && lowered.contains(&word.to_lowercase()) {
+                violations.push(PolicyViolation::BannedSubstring(word.clone()));
+            }
+        }
+        violations
+    }
+}
+
+impl fmt::Display for PasswordPolicy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "min_length = {}", self.min_length)?;
+        writeln!(f, "max_length = {}", self.max_length)?;
+        writeln!(f, "require_uppercase = {}", self.require_uppercase)?;
+        writeln!(f, "require_lowercase = {}", self.require_lowercase)?;
+        writeln!(f, "require_digit = {}", self.require_digit)?;
+        writeln!(f, "require_symbol = {}", self.require_symbol)?;
+        writeln!(f, "symbols = {}", self.symbols)?;
+        writeln!(f, "max_repeated_run = {}", self.max_repeated_run)?;
+        writeln!(f, "banned_substrings = {}", self.banned_substrings.join(","))?;
+        write!(f, "max_age_days = {}", self.max_age_days)
+    }
+}
+
+impl PolicyOverride {
+    pub fn set(&mut self, field: &str, raw: &str) -> Result<(), String> {
+        let raw = raw.trim();
+        match field {
+            "min_length" => self.min_length = Some(parse_number(raw)?),
+            "max_length" => self.max_length = Some(parse_number(raw)?),
+            "require_uppercase" => self.require_uppercase = Some(parse_bool(raw)?),
+            "require_lowercase" => self.require_lowercase = Some(parse_bool(raw)?),
+            "require_digit" => self.require_digit = Some(parse_bool(raw)?),
+            "require_symbol" => self.require_symbol = Some(parse_bool(raw)?),
+            "symbols" => {
+                if raw.is_empty() || raw.chars().any(|c| c.is_alphanumeric() || c.is_whitespace()) {
+                    return Err("The symbol set can only contain punctuation characters".to_string());
+                }
+                self.symbols = Some(raw.to_string())
+            }
+            "max_repeated_run" => self.max_repeated_run = Some(parse_number(raw)?),
+            "banned_substrings" => self.banned_substrings = Some(split_list(raw)),
+            "max_age_days" => self.max_age_days = Some(parse_number(raw)?),
+            _ => return Err(unknown_field(field)),
+        }
+        Ok(())
+    }
+
+    pub fn unset(&mut self, field: &str) -> Result<(), String> {
+        match field {
+            "min_length" => self.min_length = None,
+            "max_length" => self.max_length = None,
+            "require_uppercase" => self.require_uppercase = None,
+            "require_lowercase" => self.require_lowercase = None,
+            "require_digit" => self.require_digit = None,
+            "require_symbol" => self.require_symbol = None,
+            "symbols" => self.symbols = None,
+            "max_repeated_run" => self.max_repeated_run = None,
+            "banned_substrings" => self.banned_substrings = None,
+            "max_age_days" => self.max_age_days = None,
+            _ => return Err(unknown_field(field)),
+        }
+        Ok(())
+    }
+
+    pub fn is_empty(&self) -> bool {
+        *self == PolicyOverride::default()
+    }
+}
+
+/// Folder scopes containing `target`, outermost first: `a/b/c` gives `a/` and `a/b/`.
+pub fn parent_folders(target: &str) -> Vec<String> {
+    target
+        .char_indices()
+        .filter(|(_, c)| *c == '/')
+        .map(|(i, _)| target[..=i].to_string())
+        .collect()
+}
+
+fn longest_run(password: &str) -> usize {
+    let mut longest = 0;
+    let mut current = 0;
+    let mut previous = None;
+    for c in password.chars() {
+        current = if Some(c) == previous { current + 1 } else { 1 };
+        longest = longest.max(current);
+        previous = Some(c);
+    }
+    longest
+}
+
+fn split_list(raw: &str) -> Vec<String> {
+    raw.split(',')
+        .map(|word| word.trim().to_string())
+        .filter(|word| !word.is_empty())
+        .collect()
+}
+
+fn parse_number(raw: &str) -> Result<usize, String> {
+    raw.parse::<usize>().map_err(|_| format!("'{}' is not a positive integer", raw))
+}
+
+fn parse_bool(raw: &str) -> Result<bool, String> {
+    match raw.to_lowercase().as_str() {
+        "true" | "yes" | "on" | "1" => Ok(true),
+        "false" | "no" | "off" | "0" => Ok(false),
+        _ => Err(format!("'{}' is not a boolean (true/false)", raw)),
+    }
+}
+
+fn unknown_field(field: &str) -> String {
+    format!("Unknown policy field '{}', known fields are: {}", field, POLICY_FIELDS.join(", "))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn default_policy() -> PasswordPolicy {
+        PasswordPolicy::from_settings(&SettingsRegistry::new())
+    }
+
+    #[test]
+    fn test_all_violations_are_reported() {
+        let violations = default_policy().check("aaaa");
+
+        assert!(violations.contains(&PolicyViolation::TooShort { min: 8, actual: 4 }));
+        assert!(violations.contains(&PolicyViolation::MissingUppercase));
+        assert!(violations.contains(&PolicyViolation::MissingDigit));
+        assert!(violations.contains(&PolicyViolation::MissingSymbol("!@#$%^&*".to_string())));
+        assert!(violations.contains(&PolicyViolation::RepeatedRun(3)));
+        assert!(!violations.contains(&PolicyViolation::MissingLowercase));
+    }
+
+    #[test]
+    fn test_valid_password_passes() {
+        assert!(default_policy().check("Tr0ub4dor&3x").is_empty());
+    }
+
+    #[test]
+    fn test_banned_substrings_are_case_insensitive() {
+        let mut policy = default_policy();
+        policy.banned_substrings = vec!["kofl".to_string()];
+
+        assert_eq!(
+            policy.check("MyKOFL#pass1"),
+            vec![PolicyViolation::BannedSubstring("kofl".to_string())]
+        );
+    }
+
+    #[test]
+    fn test_overrides_apply_from_folder_to_entry() {
+        let mut kgc = KoflGlobalConfig::new();
+        let mut folder = PolicyOverride::default();
+        folder.set("min_length", "20").unwrap();
+        folder.set("require_symbol", "false").unwrap();
+        kgc.set_policy_override("bank/", Some(folder));
+
+        let mut entry = PolicyOverride::default();
+        entry.set("min_length", "6").unwrap();
+        kgc.set_policy_override("bank/pin", Some(entry));
+
+        let folder_policy = PasswordPolicy::for_entry(&kgc, "bank/checking");
+        assert_eq!(folder_policy.min_length, 20);
+        assert!(!folder_policy.require_symbol);
+
+        let entry_policy = PasswordPolicy::for_entry(&kgc, "bank/pin");
+        assert_eq!(entry_policy.min_length, 6);
+        assert!(!entry_policy.require_symbol);
+
+        assert_eq!(PasswordPolicy::for_entry(&kgc, "mail").min_length, 8);
+    }
+
+    #[test]
+    fn test_parent_folders() {
+        assert_eq!(parent_folders("a/b/c"), vec!["a/".to_string(), "a/b/".to_string()]);
+        assert!(parent_folders("entry").is_empty());
+    }
+}
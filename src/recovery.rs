@@ -0,0 +1,161 @@
+//! Recovery kit for a forgotten master password.
+//!
+//! A random recovery key wraps the vault key, and is itself split into Shamir
+//! shares to hand out on paper. The recovery key is also kept wrapped with
+//! the vault key, so the kit follows master password changes and the shares
+//! stay valid until a new kit is made.
+//!
+//! A share reads `KOFL-<kit>-<threshold>-<number>-<base32 data>-<check>`, all
+//! upper case so a QR code can use its compact alphanumeric mode. The check is
+//! the start of a SHA-256 of the rest and catches typos.
+
+use crate::cipher;
+use crate::otp::{base32_decode, base32_encode};
+use crate::shamir;
+use chrono::{DateTime, Utc};
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::fmt;
+
+const PREFIX: &str = "KOFL";
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct RecoveryKit {
+    pub kit_id: String,
+    pub shares: u8,
+    pub threshold: u8,
+    pub created_at: DateTime<Utc>,
+    // vault key sealed with the recovery key
+    wrapped_vault_key: String,
+    // recovery key sealed with the vault key
+    wrapped_recovery_key: String,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Share {
+    pub kit_id: String,
+    pub threshold: u8,
+    pub number: u8,
+    pub data: Vec<u8>,
+}
+
+impl RecoveryKit {
+    pub fn create(vault_key_hex: &str, shares: u8, threshold: u8) -> Result<(RecoveryKit, Vec<Share>), String> {
+        let mut recovery_key = [0u8; 32];
+        rand::thread_rng().fill_bytes(&mut recovery_key);
+        let mut id = [0u8; 4];
+        rand::thread_rng().fill_bytes(&mut id);
+        let kit_id = hex::encode_upper(id);
+
+        let pieces = shamir::split(&recovery_key, shares, threshold)?;
+        let recovery_key_hex = hex::encode(recovery_key);
+        let kit = RecoveryKit {
+            kit_id: kit_id.clone(),
+            shares,
+            threshold,
+            created_at: Utc::now(),
+            wrapped_vault_key: cipher::seal(&recovery_key_hex, vault_key_hex)?,
+            wrapped_recovery_key: cipher::seal(vault_key_hex, &recovery_key_hex)?,
+        };
+        let shares = pieces
+            .into_iter()
+            .map(|(number, data)| Share { kit_id: kit_id.clone(), threshold, number, data })
+            .collect();
+        Ok((kit, shares))
+    }
+
+    /// The vault key, from at least `threshold` shares of this kit.
+    pub fn recover(&self, shares: &[Share]) -> Result<String, String> {
+        if let Some(other) = shares.iter().find(|share| share.kit_id != self.kit_id) {
+            return Err(format!("Share {} belongs to kit {}, the current kit is {}", other.number, other.kit_id, self.kit_id));
+        }
+        if shares.len() < self.threshold as usize {
+            return Err(format!("{} shares are needed, {} given", self.threshold, shares.len()));
+        }
+        let pieces: Vec<(u8, Vec<u8>)> = shares.iter().map(|share| (share.number, share.data.clone())).collect();
+        let recovery_key = shamir::combine(&pieces)?;
+        cipher::open(&hex::encode(recovery_key), &self.wrapped_vault_key)
+            .map_err(|_| "The shares do not open the recovery kit".to_string())
+    }
+
+    /// Follows a change of the vault key.
+    pub fn rewrap(&mut self, old_vault_key_hex: &str, new_vault_key_hex: &str) -> Result<(), String> {
+        let recovery_key_hex = cipher::open(old_vault_key_hex, &self.wrapped_recovery_key)?;
+        self.wrapped_vault_key = cipher::seal(&recovery_key_hex, new_vault_key_hex)?;
+        self.wrapped_recovery_key = cipher::seal(new_vault_key_hex, &recovery_key_hex)?;
+        Ok(())
+    }
+}
+
+fn check(body: &str) -> String {
+    hex::encode_upper(&Sha256::digest(body.as_bytes())[..2])
+}
+
+impl fmt::Display for Share {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let body = format!("{}-{}-{}-{}-{}", PREFIX, self.kit_id, self.threshold, self.number, base32_encode(&self.data));
+        write!(f, "{}-{}", body, check(&body))
+    }
+}
+
+impl std::str::FromStr for Share {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s: String = s.chars().filter(|c| !c.is_whitespace()).collect::<String>().to_uppercase();
+        let (body, checksum) = s.rsplit_once('-').ok_or("Not a kofl recovery share")?;
+        let parts: Vec<&str> = body.split('-').collect();
+        if parts.len() != 5 || parts[0] != PREFIX {
+            return Err("Not a kofl recovery share".to_string());
+        }
+        if check(body) != checksum {
+            return Err("The share has a typo, its check does not match".to_string());
+        }
+        Ok(Share {
+            kit_id: parts[1].to_string(),
+            threshold: parts[2].parse().map_err(|_| "Invalid threshold in the share")?,
+            number: parts[3].parse().map_err(|_| "Invalid share number")?,
+            data: base32_decode(parts[4])?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const VAULT_KEY: &str = "000102030405060708090a0b0c0d0e0f101112131415161718191a1b1c1d1e1f";
+
+    #[test]
+    fn test_kit_recovers_the_vault_key() {
+        let (kit, shares) = RecoveryKit::create(VAULT_KEY, 5, 3).unwrap();
+        assert_eq!(kit.recover(&shares[2..]).unwrap(), VAULT_KEY);
+        assert!(kit.recover(&shares[..2]).is_err());
+
+        let (other_kit, _) = RecoveryKit::create(VAULT_KEY, 3, 2).unwrap();
+        assert!(other_kit.recover(&shares[..3]).is_err());
+    }
+
+    #[test]
+    fn test_shares_survive_a_vault_key_change() {
+        let new_key = "ff".repeat(32);
+        let (mut kit, shares) = RecoveryKit::create(VAULT_KEY, 3, 2).unwrap();
+        kit.rewrap(VAULT_KEY, &new_key).unwrap();
+        assert_eq!(kit.recover(&shares[1..]).unwrap(), new_key);
+    }
+
+    #[test]
+    fn test_share_text_format() {
+        let (_, shares) = RecoveryKit::create(VAULT_KEY, 3, 2).unwrap();
+        let text = shares[1].to_string();
+        assert!(text.starts_with("KOFL-"));
+        assert_eq!(text.to_lowercase().parse::<Share>().unwrap(), shares[1]);
+
+        // one changed data character is caught by the check
+        let mut typo: Vec<char> = text.chars().collect();
+        let at = text.len() - 8;
+        typo[at] = if typo[at] == 'A' { 'B' } else { 'A' };
+        assert!(typo.iter().collect::<String>().parse::<Share>().is_err());
+    }
+}
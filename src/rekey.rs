@@ -0,0 +1,87 @@
+//! Replacing the vault key, after a master password or key file change.
+//!
+//! Everything encrypted with the old key is converted before the config
+//! points at the new one: entries and their history in one database
+//! transaction, then the 2FA seed, backup passphrase key and recovery kit
+//! kept in the config. The new config is written beside the old one before
+//! the transaction commits and replaces it after, so a failed write leaves
+//! the vault on the old key.
+
+use crate::backup::{ArchiveKey, Backup};
+use crate::cipher;
+use crate::context::Context;
+use rand::distributions::Alphanumeric;
+use rand::{thread_rng, Rng};
+
+/// Archives the vault as it is, used before and after the key changes.
+pub fn backup(context: &Context) -> Result<(), String> {
+    let kgc = context.kgc.borrow();
+    let bc = Backup::from_settings(&kgc.get_settings()).map_err(|e| e.to_string())?;
+    let archive_key = ArchiveKey::from_config(&kgc, &context.vault_key()?).map_err(|e| e.to_string())?;
+    bc.create_new_backup(
+        kgc.get_config_path(),
+        &context.db.connection,
+        &kgc.get_config_path().with_extension("checksum"),
+        &archive_key,
+    )
+    .map(|_| ())
+    .map_err(|e| e.to_string())
+}
+
+/// A salt for the new key, so archives of the old key are told apart.
+pub fn new_salt() -> String {
+    thread_rng().sample_iter(&Alphanumeric).take(16).map(char::from).collect()
+}
+
+/// Switches the vault to `new_key`, derived with `salt` and bound to a key file when `keyfile` is set.
+pub fn rekey(context: &Context, new_key: &str, salt: String, keyfile: bool) -> Result<(), String> {
+    let old_key = context.vault_key()?;
+
+    let mut two_factor = context.kgc.borrow().get_two_factor().cloned();
+    if let Some(two_factor) = two_factor.as_mut() {
+        two_factor
+            .reseal(&old_key, new_key)
+            .map_err(|e| format!("Could not re-encrypt the 2FA seed: {}", e))?;
+    }
+
+    let backup_key = match context.kgc.borrow().get_backup_key() {
+        Some(backup_key) => {
+            let old = hex::decode(&old_key).map_err(|e| e.to_string())?;
+            let new = hex::decode(new_key).map_err(|e| e.to_string())?;
+            let rewrapped = backup_key
+                .rewrap(&old, &new)
+                .map_err(|e| format!("Could not re-encrypt the backup passphrase key: {}", e))?;
+            Some(rewrapped)
+        }
+        None => None,
+    };
+
+    let mut recovery_kit = context.kgc.borrow().get_recovery_kit().cloned();
+    if let Some(kit) = recovery_kit.as_mut() {
+        kit.rewrap(&old_key, new_key)
+            .map_err(|e| format!("Could not re-encrypt the recovery kit: {}", e))?;
+    }
+
+    let mut kgc = context.kgc.borrow().clone();
+    kgc.set_salt(salt);
+    kgc.set_vault_key(new_key);
+    kgc.set_keyfile(keyfile);
+    kgc.set_two_factor(two_factor);
+    kgc.set_backup_key(backup_key);
+    kgc.set_recovery_kit(recovery_kit);
+
+    if let Err(e) = context.db.reencrypt(
+        |password_hash| cipher::encrypt_password(new_key, &cipher::decrypt_password(&old_key, password_hash)?),
+        |sealed| cipher::seal(new_key, &cipher::open(&old_key, sealed)?),
+        || kgc.stage(),
+    ) {
+        kgc.discard_staged();
+        return Err(format!("Could not re-encrypt the vault, nothing was changed: {}", e));
+    }
+    kgc.install_staged()
+        .map_err(|e| format!("The entries use the new key, but the config was not replaced: {}", e))?;
+    *context.kgc.borrow_mut() = kgc;
+    context.unlock(new_key.to_string());
+    context.ss.replace_vault_key(new_key);
+    Ok(())
+}
@@ -0,0 +1,104 @@
+//! Password expiration.
+//!
+//! An entry expires `max_age_days` after its last change, where the max age
+//! comes from its policy so it can be set globally, per folder or per entry.
+
+use crate::config::Config::KoflGlobalConfig;
+use crate::db::Db::Entry;
+use crate::policy::PasswordPolicy;
+use chrono::{DateTime, Duration, Utc};
+
+pub struct Expiry {
+    pub name: String,
+    pub max_age_days: usize,
+    // None when the stored timestamp cannot be parsed, such entries count as overdue
+    pub changed_at: Option<DateTime<Utc>>,
+}
+
+impl Expiry {
+    pub fn expires_at(&self) -> Option<DateTime<Utc>> {
+        self.changed_at.map(|changed| changed + Duration::days(self.max_age_days as i64))
+    }
+
+    pub fn is_overdue(&self, now: DateTime<Utc>) -> bool {
+        self.expires_at().map_or(true, |expires| expires <= now)
+    }
+
+    /// Whole days until expiry, negative once overdue.
+    pub fn days_left(&self, now: DateTime<Utc>) -> Option<i64> {
+        self.expires_at().map(|expires| (expires - now).num_days())
+    }
+}
+
+/// Expiry of every entry whose policy has a max age.
+pub fn expiries(kgc: &KoflGlobalConfig, entries: &[Entry]) -> Vec<Expiry> {
+    entries
+        .iter()
+        .filter_map(|entry| {
+            let max_age_days = PasswordPolicy::for_entry(kgc, &entry.ent_name).max_age_days;
+            if max_age_days == 0 {
+                return None;
+            }
+            Some(Expiry {
+                name: entry.ent_name.clone(),
+                max_age_days,
+                changed_at: DateTime::parse_from_rfc3339(&entry.timestamp).ok().map(|t| t.with_timezone(&Utc)),
+            })
+        })
+        .collect()
+}
+
+pub fn overdue(kgc: &KoflGlobalConfig, entries: &[Entry], now: DateTime<Utc>) -> Vec<Expiry> {
+    expiries(kgc, entries).into_iter().filter(|expiry| expiry.is_overdue(now)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::policy::PolicyOverride;
+
+    fn entry(name: &str, age_days: i64, now: DateTime<Utc>) -> Entry {
+        Entry {
+            id: 0,
+            ent_name: name.to_string(),
+            password_hash: String::new(),
+            timestamp: (now - Duration::days(age_days)).to_rfc3339(),
+            username: None,
+            url: None,
+        }
+    }
+
+    #[test]
+    fn test_max_age_from_folder_and_entry_overrides() {
+        let now = Utc::now();
+        let mut kgc = KoflGlobalConfig::new();
+        let mut folder = PolicyOverride::default();
+        folder.set("max_age_days", "90").unwrap();
+        kgc.set_policy_override("work/", Some(folder));
+        let mut vpn = PolicyOverride::default();
+        vpn.set("max_age_days", "30").unwrap();
+        kgc.set_policy_override("work/vpn", Some(vpn));
+
+        let entries = vec![entry("work/mail", 100, now), entry("work/vpn", 20, now), entry("personal", 1000, now)];
+        let expiries = expiries(&kgc, &entries);
+
+        // no max age by default, so only the work entries expire
+        assert_eq!(expiries.len(), 2);
+        assert!(expiries[0].is_overdue(now));
+        assert_eq!(expiries[1].days_left(now), Some(10));
+        assert_eq!(overdue(&kgc, &entries, now).len(), 1);
+    }
+
+    #[test]
+    fn test_unknown_timestamp_is_overdue() {
+        let now = Utc::now();
+        let mut kgc = KoflGlobalConfig::new();
+        let mut all = PolicyOverride::default();
+        all.set("max_age_days", "30").unwrap();
+        kgc.set_policy_override("legacy", Some(all));
+
+        let mut legacy = entry("legacy", 0, now);
+        legacy.timestamp = "12-12-12".to_string();
+        assert!(overdue(&kgc, &[legacy], now)[0].days_left(now).is_none());
+    }
+}
@@ -0,0 +1,114 @@
+//! What a session is bound to, see the `session_scope` setting.
+//!
+//! A binding names the scope and what identifies it, so a session keeps the
+//! scope it was started with even if the setting changes:
+//!
+//! - `tty:<tty_nr>:<sid>`, the controlling terminal and the session of the
+//!   shell in it, so a new shell reusing the same pseudo terminal does not match
+//! - `ppid:<pid>:<start time>`, the parent process, usually the shell
+//! - `logind:<id>`, the systemd-logind session
+
+use std::fs;
+
+pub const SCOPES: [&str; 4] = ["user", "tty", "ppid", "logind"];
+
+/// Fields of `/proc/<pid>/stat` that identify a process and its terminal.
+#[derive(Debug, PartialEq)]
+struct ProcStat {
+    ppid: u32,
+    session: u32,
+    tty_nr: u32,
+    start_time: u64,
+}
+
+// the command name in brackets may hold spaces and brackets, fields start after the last ')'
+fn parse_stat(stat: &str) -> Option<ProcStat> {
+    let fields: Vec<&str> = stat.get(stat.rfind(')')? + 1..)?.split_whitespace().collect();
+    Some(ProcStat {
+        ppid: fields.get(1)?.parse().ok()?,
+        session: fields.get(3)?.parse().ok()?,
+        tty_nr: fields.get(4)?.parse().ok()?,
+        start_time: fields.get(19)?.parse().ok()?,
+    })
+}
+
+fn read_stat(pid: &str) -> Result<ProcStat, String> {
+    let stat = fs::read_to_string(format!("/proc/{}/stat", pid)).map_err(|e| format!("Cannot read /proc/{}/stat: {}", pid, e))?;
+    parse_stat(&stat).ok_or_else(|| format!("Cannot parse /proc/{}/stat", pid))
+}
+
+// systemd puts the processes of a login session in a `session-<id>.scope` cgroup
+fn logind_session(cgroup: &str) -> Option<String> {
+    cgroup
+        .lines()
+        .flat_map(|line| line.split('/'))
+        .find_map(|part| part.strip_prefix("session-").and_then(|rest| rest.strip_suffix(".scope")))
+        .map(|id| id.to_string())
+}
+
+/// The binding of this process under `scope`, None for the `user` scope which binds nothing.
+pub fn current(scope: &str) -> Result<Option<String>, String> {
+    match scope {
+        "user" => Ok(None),
+        "tty" => {
+            let stat = read_stat("self")?;
+            if stat.tty_nr == 0 {
+                return Err("There is no controlling terminal to bind the session to".to_string());
+            }
+            Ok(Some(format!("tty:{}:{}", stat.tty_nr, stat.session)))
+        }
+        "ppid" => {
+            let ppid = read_stat("self")?.ppid;
+            let parent = read_stat(&ppid.to_string())?;
+            Ok(Some(format!("ppid:{}:{}", ppid, parent.start_time)))
+        }
+        "logind" => {
+            let id = fs::read_to_string("/proc/self/cgroup")
+                .ok()
+                .and_then(|cgroup| logind_session(&cgroup))
+                .or_else(|| std::env::var("XDG_SESSION_ID").ok().filter(|id| !id.is_empty()))
+                .ok_or("There is no systemd-logind session to bind the session to")?;
+            Ok(Some(format!("logind:{}", id)))
+        }
+        other => Err(format!("Unknown session scope '{}', use one of: {}", other, SCOPES.join(", "))),
+    }
+}
+
+/// Human name of what a binding is bound to.
+pub fn describe(binding: &str) -> &'static str {
+    match binding.split(':').next() {
+        Some("tty") => "terminal",
+        Some("ppid") => "parent process",
+        Some("logind") => "login session",
+        _ => "scope",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_stat() {
+        let stat = "4242 (my (odd) cmd) S 4100 4242 4100 34817 4242 4194560 103 0 0 0 0 0 0 0 20 0 1 0 987654 8196096 209";
+        assert_eq!(
+            parse_stat(stat),
+            Some(ProcStat { ppid: 4100, session: 4100, tty_nr: 34817, start_time: 987654 })
+        );
+        assert_eq!(parse_stat("4242 (cmd) S 1"), None);
+    }
+
+    #[test]
+    fn test_logind_session() {
+        let cgroup = "0::/user.slice/user-1000.slice/session-7.scope\n";
+        assert_eq!(logind_session(cgroup), Some("7".to_string()));
+        assert_eq!(logind_session("0::/system.slice/cron.service\n"), None);
+    }
+
+    #[test]
+    fn test_current() {
+        assert_eq!(current("user"), Ok(None));
+        assert!(current("ppid").unwrap().unwrap().starts_with("ppid:"));
+        assert!(current("sometimes").is_err());
+    }
+}
@@ -1,6 +1,7 @@
+pub mod binding;
+
 use std::env::home_dir;
 use std::fmt::Debug;
-use crate::utils::Utils::{check_existing_session_config, get_home_dir};
 use serde::{Deserialize, Serialize};
 use std::env;
 use std::fs;
@@ -10,6 +11,7 @@ use chrono::{DateTime, TimeZone, Utc};
 use crate::cli::Command;
 use crate::errors::{ErrorExecution, ErrorValidation};
 use crate::context::Context;
+use crate::layout;
 use rand::{thread_rng, Rng};
 use rand::distributions::Alphanumeric;
 use sha2::{Sha256, Digest};
@@ -35,7 +37,7 @@ pub enum SessionError {
     AuthenticationRequired
 }
 
-#[derive(Serialize, Deserialize)]
+#[derive(Serialize, Deserialize, Clone)]
 pub struct Session {
     session_path: PathBuf,
     session_id: String,
@@ -43,15 +45,27 @@ pub struct Session {
     created_at: DateTime<Utc>,
     expires_at: DateTime<Utc>,
     last_activity: DateTime<Utc>,
-    is_active: bool
+    is_active: bool,
+    // what the session is bound to, see `binding`, None when valid for the whole user
+    #[serde(default)]
+    binding: Option<String>,
+    // last master password re-entry, see `ReauthValidator`
+    #[serde(default)]
+    reauthenticated_at: Option<DateTime<Utc>>,
+    // hex vault key from the login, the config only keeps a verifier of it
+    #[serde(default)]
+    vault_key: Option<String>,
+    // from the `auto_lock_on_idle` and `idle_timeout` settings, None when idle sessions never lock
+    #[serde(skip)]
+    idle_timeout: Option<chrono::Duration>,
 }
 
 impl Session {
-    pub fn new(user_login: String, status: bool) -> Self {
+    /// `duration` is the session lifetime in minutes, see the `session_duration` setting.
+    pub fn new(user_login: String, status: bool, duration: u32) -> Self {
         let now = Utc::now();
-        let home_dir = get_home_dir().expect("Home directory not found");
         Session {
-            session_path: home_dir.join(".kofl_session"),
+            session_path: crate::vault::active().session,
             session_id: thread_rng()
                 .sample_iter(&Alphanumeric)
                 .take(32)
@@ -59,27 +73,76 @@ impl Session {
                 .collect(),
             user_login,
             created_at: now,
-            expires_at: now + chrono::Duration::seconds(300),
+            expires_at: now + chrono::Duration::minutes(duration as i64),
             last_activity: now,
-            is_active: status
+            is_active: status,
+            binding: None,
+            reauthenticated_at: None,
+            vault_key: None,
+            idle_timeout: None,
+        }
+    }
+
+    /// An active session for a successful login, bound under the `session_scope` setting.
+    pub fn start(user_login: String, duration: u32, scope: &str) -> Result<Self, String> {
+        let mut session = Session::new(user_login, true, duration);
+        session.binding = binding::current(scope)?;
+        Ok(session)
+    }
+
+    pub fn set_vault_key(&mut self, key: String) {
+        self.vault_key = Some(key);
+    }
+
+    /// The vault key while the session is active, None once it ended.
+    pub fn vault_key(&self) -> Option<String> {
+        if self.check_if_expired() || self.check_binding().is_err() {
+            return None;
+        }
+        self.vault_key.clone()
+    }
+
+    /// Whether this process is within what the session is bound to.
+    pub fn check_binding(&self) -> Result<(), String> {
+        let bound = match &self.binding {
+            Some(bound) => bound,
+            None => return Ok(()),
+        };
+        let scope = bound.split(':').next().unwrap_or_default();
+        match binding::current(scope) {
+            Ok(Some(current)) if current == *bound => Ok(()),
+            _ => Err(format!("The session belongs to another {}, run `kofl login` here ⛔", binding::describe(bound))),
         }
     }
 
+    /// Locks the session after `minutes` without a successful command, never when None.
+    pub fn set_idle_timeout(&mut self, minutes: Option<u32>) {
+        self.idle_timeout = minutes.map(|minutes| chrono::Duration::minutes(minutes as i64));
+    }
+
 
     pub fn get_session_path(&self) -> &PathBuf {
        &self.session_path
     }
 
     pub fn load(&mut self) -> Result<(), SessionError> {
-        if check_existing_session_config() {
+        if self.session_path.exists() {
             match self.read_config_from_toml_file() {
                 Ok(config) => {
+                    let idle_timeout = self.idle_timeout;
                     *self = config; // mutating the self with Session  serialized
+                    self.idle_timeout = idle_timeout;
+                    // sessions started before the vault key moved out of the config have none
+                    if self.vault_key.is_none() {
+                        self.is_active = false;
+                    }
                     if self.check_if_expired() {
+                        // an idle or outlived session does not keep the key around
+                        if self.vault_key.take().is_some() {
+                            self.lock();
+                        }
                         return Err(SessionError::ExpiredSession);
                     }
-                    self.last_activity = Utc::now();
-                    self.update();
                     Ok(())
                 }
                 Err(e) => {
@@ -103,7 +166,7 @@ impl Session {
         let toml_str = self.serialize_to_toml();
         // println!("toml str =\n{}", toml_str);
         let config_pth = &self.session_path;
-        fs::write(config_pth, toml_str).expect("could not create toml file for session config");
+        layout::write_private(config_pth, toml_str).expect("could not create toml file for session config");
     }
 
     pub fn read_config_from_toml_file(&self) -> Result<Session, Box<dyn std::error::Error>> {
@@ -113,8 +176,79 @@ impl Session {
         Ok(config)
     }
 
+    /// A session ends when it was locked, its lifetime is over or it sat idle too long.
     pub fn check_if_expired(&self) -> bool {
-        self.expires_at < Utc::now()
+        self.is_expired_at(Utc::now())
+    }
+
+    fn is_expired_at(&self, now: DateTime<Utc>) -> bool {
+        if !self.is_active || self.expires_at < now {
+            return true;
+        }
+        match self.idle_timeout {
+            Some(idle_timeout) => self.last_activity + idle_timeout < now,
+            None => false,
+        }
+    }
+
+    /// Slides the idle timeout forward, called after each successful command.
+    /// The file is read back first, the command may have locked, replaced or removed the session.
+    pub fn record_activity(&self) {
+        let mut session = match self.read_config_from_toml_file() {
+            Ok(session) => session,
+            Err(_) => return,
+        };
+        session.idle_timeout = self.idle_timeout;
+        if session.session_id != self.session_id || session.check_if_expired() || session.check_binding().is_err() {
+            return;
+        }
+        session.last_activity = Utc::now();
+        session.update();
+    }
+
+    /// Whether the master password was entered again in the last `seconds`.
+    pub fn reauthenticated_within(&self, seconds: u32) -> bool {
+        match self.reauthenticated_at {
+            Some(at) => Utc::now() - at <= chrono::Duration::seconds(seconds as i64),
+            None => false,
+        }
+    }
+
+    /// Replaces the vault key of the current session after the vault was re-keyed, if it is still active.
+    pub fn replace_vault_key(&self, key: &str) {
+        let mut session = match self.read_config_from_toml_file() {
+            Ok(session) => session,
+            Err(_) => return,
+        };
+        session.idle_timeout = self.idle_timeout;
+        if session.session_id != self.session_id || session.check_if_expired() {
+            return;
+        }
+        session.vault_key = Some(key.to_string());
+        session.update();
+    }
+
+    /// Records a master password re-entry in the current session, if it is still active.
+    pub fn record_reauth(&self) {
+        let mut session = match self.read_config_from_toml_file() {
+            Ok(session) => session,
+            Err(_) => return,
+        };
+        session.idle_timeout = self.idle_timeout;
+        if session.session_id != self.session_id || session.check_if_expired() {
+            return;
+        }
+        session.reauthenticated_at = Some(Utc::now());
+        session.update();
+    }
+
+    /// Ends the session now and forgets the vault key, the next command needs `kofl login`.
+    pub fn lock(&self) {
+        let mut session = self.clone();
+        session.is_active = false;
+        session.expires_at = Utc::now();
+        session.vault_key = None;
+        session.update();
     }
 }
 
@@ -129,6 +263,8 @@ impl Debug for Session {
              ├─ Created: {}\n\
              ├─ Expires: {}\n\
              ├─ Last Activity: {}\n\
+             ├─ Idle Timeout: {}\n\
+             ├─ Bound To: {}\n\
              └─ Active: {}\n",
             self.session_path.display(),
             self.session_id,
@@ -136,7 +272,60 @@ impl Debug for Session {
             self.created_at.format("%Y-%m-%d %H:%M:%S"),
             self.expires_at.format("%Y-%m-%d %H:%M:%S"),
             self.last_activity.format("%Y-%m-%d %H:%M:%S"),
+            match self.idle_timeout {
+                Some(idle_timeout) => format!("{} min", idle_timeout.num_minutes()),
+                None => "off".to_string(),
+            },
+            self.binding.as_deref().unwrap_or("user"),
             if self.is_active { "Yes" } else { "No" }
         )
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_idle_timeout_slides_with_activity() {
+        let mut session = Session::new("alice".to_string(), true, 30);
+        let start = session.last_activity;
+        assert!(!session.is_expired_at(start + chrono::Duration::minutes(10)));
+
+        session.set_idle_timeout(Some(5));
+        assert!(!session.is_expired_at(start + chrono::Duration::minutes(4)));
+        assert!(session.is_expired_at(start + chrono::Duration::minutes(6)));
+
+        session.last_activity = start + chrono::Duration::minutes(4);
+        assert!(!session.is_expired_at(start + chrono::Duration::minutes(8)));
+        // the lifetime is absolute, activity does not extend it
+        session.last_activity = start + chrono::Duration::minutes(29);
+        assert!(session.is_expired_at(start + chrono::Duration::minutes(31)));
+    }
+
+    #[test]
+    fn test_reauthenticated_within() {
+        let mut session = Session::new("alice".to_string(), true, 30);
+        assert!(!session.reauthenticated_within(300));
+
+        session.reauthenticated_at = Some(Utc::now() - chrono::Duration::seconds(60));
+        assert!(session.reauthenticated_within(300));
+        assert!(!session.reauthenticated_within(30));
+    }
+
+    #[test]
+    fn test_inactive_session_is_expired() {
+        let session = Session::new("alice".to_string(), false, 30);
+        assert!(session.is_expired_at(session.created_at));
+    }
+
+    #[test]
+    fn test_vault_key_only_while_active() {
+        let mut session = Session::new("alice".to_string(), true, 30);
+        session.set_vault_key("ab12".to_string());
+        assert_eq!(session.vault_key().as_deref(), Some("ab12"));
+
+        session.is_active = false;
+        assert_eq!(session.vault_key(), None);
+    }
+}
@@ -0,0 +1,51 @@
+use crate::setting::core::Setting;
+use crate::setting::setting_value::SettingValue;
+use crate::setting::setting_key::SettingKey;
+use std::path::Path;
+
+pub struct BackupLocation {
+    value: String,  // empty means the backup directory of the vault
+}
+
+impl BackupLocation {
+    pub fn new() -> Self {
+        Self { value: String::new() } // Default value
+    }
+
+    pub fn get(&self) -> &str {
+        &self.value
+    }
+}
+
+impl Setting for BackupLocation {
+    fn key(&self) -> SettingKey {
+        SettingKey::BackupLocation
+    }
+
+    fn default(&self) -> SettingValue {
+        SettingValue::String(String::new())
+    }
+
+    fn validate(&self, value: &SettingValue) -> Result<(), String> {
+        match value.as_string() {
+            Ok(path) => {
+                if !path.is_empty() && !Path::new(&path).is_absolute() {
+                    Err("Backup location must be an absolute path".to_string())
+                } else {
+                    Ok(())
+                }
+            },
+            Err(e) => Err(e),
+        }
+    }
+
+    fn update(&mut self, value: SettingValue) -> Result<(), String> {
+        self.validate(&value)?;
+        self.value = value.as_string()?;
+        Ok(())
+    }
+
+    fn get_value(&self) -> SettingValue {
+        SettingValue::String(self.value.clone())
+    }
+}
@@ -0,0 +1,50 @@
+use crate::setting::core::Setting;
+use crate::setting::setting_value::SettingValue;
+use crate::setting::setting_key::SettingKey;
+
+pub struct BackupRetentionCount {
+    value: u32,  // number of most recent backups always kept
+}
+
+impl BackupRetentionCount {
+    pub fn new() -> Self {
+        Self { value: 10 } // Default value
+    }
+
+    pub fn get(&self) -> u32 {
+        self.value
+    }
+}
+
+impl Setting for BackupRetentionCount {
+    fn key(&self) -> SettingKey {
+        SettingKey::BackupRetentionCount
+    }
+
+    fn default(&self) -> SettingValue {
+        SettingValue::UnsignedInteger(10)
+    }
+
+    fn validate(&self, value: &SettingValue) -> Result<(), String> {
+        match value.as_u32() {
+            Ok(count) => {
+                if count < 1 || count > 1000 {
+                    Err("Backup retention count must be between 1 and 1000".to_string())
+                } else {
+                    Ok(())
+                }
+            },
+            Err(e) => Err(e),
+        }
+    }
+
+    fn update(&mut self, value: SettingValue) -> Result<(), String> {
+        self.validate(&value)?;
+        self.value = value.as_u32()?;
+        Ok(())
+    }
+
+    fn get_value(&self) -> SettingValue {
+        SettingValue::UnsignedInteger(self.value as u64)
+    }
+}
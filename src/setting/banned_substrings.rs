@@ -0,0 +1,41 @@
+use crate::setting::core::Setting;
+use crate::setting::setting_value::SettingValue;
+use crate::setting::setting_key::SettingKey;
+
+pub struct BannedSubstrings {
+    value: String,  // comma separated, matched case-insensitively
+}
+
+impl BannedSubstrings {
+    pub fn new() -> Self {
+        Self { value: String::new() } // Default value
+    }
+
+    pub fn get(&self) -> &str {
+        &self.value
+    }
+}
+
+impl Setting for BannedSubstrings {
+    fn key(&self) -> SettingKey {
+        SettingKey::BannedSubstrings
+    }
+
+    fn default(&self) -> SettingValue {
+        SettingValue::String(String::new())
+    }
+
+    fn validate(&self, value: &SettingValue) -> Result<(), String> {
+        value.as_string().map(|_| ())
+    }
+
+    fn update(&mut self, value: SettingValue) -> Result<(), String> {
+        self.validate(&value)?;
+        self.value = value.as_string()?;
+        Ok(())
+    }
+
+    fn get_value(&self) -> SettingValue {
+        SettingValue::String(self.value.clone())
+    }
+}
@@ -0,0 +1,50 @@
+use crate::setting::core::Setting;
+use crate::setting::setting_value::SettingValue;
+use crate::setting::setting_key::SettingKey;
+
+pub struct ClipboardMaxPastes {
+    value: u32,  // 0 leaves only clipboard_timeout in charge
+}
+
+impl ClipboardMaxPastes {
+    pub fn new() -> Self {
+        Self { value: 0 } // Default value
+    }
+
+    pub fn get(&self) -> u32 {
+        self.value
+    }
+}
+
+impl Setting for ClipboardMaxPastes {
+    fn key(&self) -> SettingKey {
+        SettingKey::ClipboardMaxPastes
+    }
+
+    fn default(&self) -> SettingValue {
+        SettingValue::UnsignedInteger(0)
+    }
+
+    fn validate(&self, value: &SettingValue) -> Result<(), String> {
+        match value.as_u32() {
+            Ok(pastes) => {
+                if pastes > 100 {
+                    Err("Clipboard max pastes must be between 0 and 100".to_string())
+                } else {
+                    Ok(())
+                }
+            },
+            Err(e) => Err(e),
+        }
+    }
+
+    fn update(&mut self, value: SettingValue) -> Result<(), String> {
+        self.validate(&value)?;
+        self.value = value.as_u32()?;
+        Ok(())
+    }
+
+    fn get_value(&self) -> SettingValue {
+        SettingValue::UnsignedInteger(self.value as u64)
+    }
+}
@@ -0,0 +1,50 @@
+use crate::setting::core::Setting;
+use crate::setting::setting_value::SettingValue;
+use crate::setting::setting_key::SettingKey;
+
+pub struct ClipboardTimeout {
+    value: u32,  // seconds, 0 leaves the clipboard untouched
+}
+
+impl ClipboardTimeout {
+    pub fn new() -> Self {
+        Self { value: 45 } // Default value
+    }
+
+    pub fn get(&self) -> u32 {
+        self.value
+    }
+}
+
+impl Setting for ClipboardTimeout {
+    fn key(&self) -> SettingKey {
+        SettingKey::ClipboardTimeout
+    }
+
+    fn default(&self) -> SettingValue {
+        SettingValue::UnsignedInteger(45)
+    }
+
+    fn validate(&self, value: &SettingValue) -> Result<(), String> {
+        match value.as_u32() {
+            Ok(seconds) => {
+                if seconds > 600 {
+                    Err("Clipboard timeout must be between 0 and 600 seconds".to_string())
+                } else {
+                    Ok(())
+                }
+            },
+            Err(e) => Err(e),
+        }
+    }
+
+    fn update(&mut self, value: SettingValue) -> Result<(), String> {
+        self.validate(&value)?;
+        self.value = value.as_u32()?;
+        Ok(())
+    }
+
+    fn get_value(&self) -> SettingValue {
+        SettingValue::UnsignedInteger(self.value as u64)
+    }
+}
@@ -7,6 +7,11 @@ pub trait Setting {
     fn validate(&self, value: &SettingValue) -> Result<(), String>;
     fn update(&mut self, value: SettingValue) -> Result<(), String>;
     fn get_value(&self) -> SettingValue;
+
+    /// Parses a user supplied string into a value of this setting's type.
+    fn parse(&self, raw: &str) -> Result<SettingValue, String> {
+        self.default().parse_as(raw)
+    }
     
     // These can be derived from the enum
     fn name(&self) -> String {
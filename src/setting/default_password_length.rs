@@ -0,0 +1,50 @@
+use crate::setting::core::Setting;
+use crate::setting::setting_value::SettingValue;
+use crate::setting::setting_key::SettingKey;
+
+pub struct DefaultPasswordLength {
+    value: u32,
+}
+
+impl DefaultPasswordLength {
+    pub fn new() -> Self {
+        Self { value: 24 } // Default value
+    }
+
+    pub fn get(&self) -> u32 {
+        self.value
+    }
+}
+
+impl Setting for DefaultPasswordLength {
+    fn key(&self) -> SettingKey {
+        SettingKey::DefaultPasswordLength
+    }
+
+    fn default(&self) -> SettingValue {
+        SettingValue::UnsignedInteger(24)
+    }
+
+    fn validate(&self, value: &SettingValue) -> Result<(), String> {
+        match value.as_u32() {
+            Ok(v) => {
+                if v < 4 || v > 256 {
+                    Err("Default password length must be between 4 and 256".to_string())
+                } else {
+                    Ok(())
+                }
+            },
+            Err(e) => Err(e),
+        }
+    }
+
+    fn update(&mut self, value: SettingValue) -> Result<(), String> {
+        self.validate(&value)?;
+        self.value = value.as_u32()?;
+        Ok(())
+    }
+
+    fn get_value(&self) -> SettingValue {
+        SettingValue::UnsignedInteger(self.value as u64)
+    }
+}
@@ -0,0 +1,41 @@
+use crate::setting::core::Setting;
+use crate::setting::setting_value::SettingValue;
+use crate::setting::setting_key::SettingKey;
+
+pub struct ExcludeAmbiguousChars {
+    value: bool,
+}
+
+impl ExcludeAmbiguousChars {
+    pub fn new() -> Self {
+        Self { value: false } // Default value
+    }
+
+    pub fn get(&self) -> bool {
+        self.value
+    }
+}
+
+impl Setting for ExcludeAmbiguousChars {
+    fn key(&self) -> SettingKey {
+        SettingKey::ExcludeAmbiguousChars
+    }
+
+    fn default(&self) -> SettingValue {
+        SettingValue::Boolean(false)
+    }
+
+    fn validate(&self, value: &SettingValue) -> Result<(), String> {
+        value.as_bool().map(|_| ())
+    }
+
+    fn update(&mut self, value: SettingValue) -> Result<(), String> {
+        self.validate(&value)?;
+        self.value = value.as_bool()?;
+        Ok(())
+    }
+
+    fn get_value(&self) -> SettingValue {
+        SettingValue::Boolean(self.value)
+    }
+}
@@ -0,0 +1,51 @@
+use crate::setting::core::Setting;
+use crate::setting::setting_value::SettingValue;
+use crate::setting::setting_key::SettingKey;
+use std::path::Path;
+
+pub struct HibpFile {
+    value: String,  // empty means the breach check is skipped on add and update
+}
+
+impl HibpFile {
+    pub fn new() -> Self {
+        Self { value: String::new() } // Default value
+    }
+
+    pub fn get(&self) -> &str {
+        &self.value
+    }
+}
+
+impl Setting for HibpFile {
+    fn key(&self) -> SettingKey {
+        SettingKey::HibpFile
+    }
+
+    fn default(&self) -> SettingValue {
+        SettingValue::String(String::new())
+    }
+
+    fn validate(&self, value: &SettingValue) -> Result<(), String> {
+        match value.as_string() {
+            Ok(path) => {
+                if !path.is_empty() && !Path::new(&path).is_absolute() {
+                    Err("HIBP file must be an absolute path".to_string())
+                } else {
+                    Ok(())
+                }
+            },
+            Err(e) => Err(e),
+        }
+    }
+
+    fn update(&mut self, value: SettingValue) -> Result<(), String> {
+        self.validate(&value)?;
+        self.value = value.as_string()?;
+        Ok(())
+    }
+
+    fn get_value(&self) -> SettingValue {
+        SettingValue::String(self.value.clone())
+    }
+}
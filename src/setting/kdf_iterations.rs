@@ -0,0 +1,51 @@
+use crate::constants::CONS;
+use crate::setting::core::Setting;
+use crate::setting::setting_value::SettingValue;
+use crate::setting::setting_key::SettingKey;
+
+pub struct KdfIterations {
+    value: u32,
+}
+
+impl KdfIterations {
+    pub fn new() -> Self {
+        Self { value: CONS::MASTER_KDF_ITERATIONS } // Default value
+    }
+
+    pub fn get(&self) -> u32 {
+        self.value
+    }
+}
+
+impl Setting for KdfIterations {
+    fn key(&self) -> SettingKey {
+        SettingKey::KdfIterations
+    }
+
+    fn default(&self) -> SettingValue {
+        SettingValue::UnsignedInteger(CONS::MASTER_KDF_ITERATIONS as u64)
+    }
+
+    fn validate(&self, value: &SettingValue) -> Result<(), String> {
+        match value.as_u32() {
+            Ok(iterations) => {
+                if iterations < 100_000 || iterations > 10_000_000 {
+                    Err("KDF iterations must be between 100000 and 10000000".to_string())
+                } else {
+                    Ok(())
+                }
+            },
+            Err(e) => Err(e),
+        }
+    }
+
+    fn update(&mut self, value: SettingValue) -> Result<(), String> {
+        self.validate(&value)?;
+        self.value = value.as_u32()?;
+        Ok(())
+    }
+
+    fn get_value(&self) -> SettingValue {
+        SettingValue::UnsignedInteger(self.value as u64)
+    }
+}
@@ -0,0 +1,50 @@
+use crate::setting::core::Setting;
+use crate::setting::setting_value::SettingValue;
+use crate::setting::setting_key::SettingKey;
+
+pub struct MaxLoginAttempts {
+    value: u32,
+}
+
+impl MaxLoginAttempts {
+    pub fn new() -> Self {
+        Self { value: 5 } // Default value
+    }
+
+    pub fn get(&self) -> u32 {
+        self.value
+    }
+}
+
+impl Setting for MaxLoginAttempts {
+    fn key(&self) -> SettingKey {
+        SettingKey::MaxLoginAttempts
+    }
+
+    fn default(&self) -> SettingValue {
+        SettingValue::UnsignedInteger(5)
+    }
+
+    fn validate(&self, value: &SettingValue) -> Result<(), String> {
+        match value.as_u32() {
+            Ok(attempts) => {
+                if attempts < 1 || attempts > 100 {
+                    Err("Max login attempts must be between 1 and 100".to_string())
+                } else {
+                    Ok(())
+                }
+            },
+            Err(e) => Err(e),
+        }
+    }
+
+    fn update(&mut self, value: SettingValue) -> Result<(), String> {
+        self.validate(&value)?;
+        self.value = value.as_u32()?;
+        Ok(())
+    }
+
+    fn get_value(&self) -> SettingValue {
+        SettingValue::UnsignedInteger(self.value as u64)
+    }
+}
@@ -0,0 +1,50 @@
+use crate::setting::core::Setting;
+use crate::setting::setting_value::SettingValue;
+use crate::setting::setting_key::SettingKey;
+
+pub struct MaxRepeatedChars {
+    value: u32,  // 0 allows any run
+}
+
+impl MaxRepeatedChars {
+    pub fn new() -> Self {
+        Self { value: 3 } // Default value
+    }
+
+    pub fn get(&self) -> u32 {
+        self.value
+    }
+}
+
+impl Setting for MaxRepeatedChars {
+    fn key(&self) -> SettingKey {
+        SettingKey::MaxRepeatedChars
+    }
+
+    fn default(&self) -> SettingValue {
+        SettingValue::UnsignedInteger(3)
+    }
+
+    fn validate(&self, value: &SettingValue) -> Result<(), String> {
+        match value.as_u32() {
+            Ok(v) => {
+                if v > 64 {
+                    Err("Maximum run of repeated characters must be between 0 and 64".to_string())
+                } else {
+                    Ok(())
+                }
+            },
+            Err(e) => Err(e),
+        }
+    }
+
+    fn update(&mut self, value: SettingValue) -> Result<(), String> {
+        self.validate(&value)?;
+        self.value = value.as_u32()?;
+        Ok(())
+    }
+
+    fn get_value(&self) -> SettingValue {
+        SettingValue::UnsignedInteger(self.value as u64)
+    }
+}
@@ -0,0 +1,50 @@
+use crate::setting::core::Setting;
+use crate::setting::setting_value::SettingValue;
+use crate::setting::setting_key::SettingKey;
+
+pub struct MinPasswordScore {
+    value: u32,  // 0 (too guessable) to 4 (very unguessable)
+}
+
+impl MinPasswordScore {
+    pub fn new() -> Self {
+        Self { value: 2 } // Default value
+    }
+
+    pub fn get(&self) -> u32 {
+        self.value
+    }
+}
+
+impl Setting for MinPasswordScore {
+    fn key(&self) -> SettingKey {
+        SettingKey::MinPasswordScore
+    }
+
+    fn default(&self) -> SettingValue {
+        SettingValue::UnsignedInteger(2)
+    }
+
+    fn validate(&self, value: &SettingValue) -> Result<(), String> {
+        match value.as_u32() {
+            Ok(v) => {
+                if v > 4 {
+                    Err("Minimum password score must be between 0 and 4".to_string())
+                } else {
+                    Ok(())
+                }
+            },
+            Err(e) => Err(e),
+        }
+    }
+
+    fn update(&mut self, value: SettingValue) -> Result<(), String> {
+        self.validate(&value)?;
+        self.value = value.as_u32()?;
+        Ok(())
+    }
+
+    fn get_value(&self) -> SettingValue {
+        SettingValue::UnsignedInteger(self.value as u64)
+    }
+}
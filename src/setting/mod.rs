@@ -1,6 +1,35 @@
 pub mod core;
-mod registry;
+pub mod registry;
 mod session_duration;
 mod failed_login_lockout_duration;
-mod setting_value;
-mod setting_key;
+mod backup_retention_count;
+mod backup_location;
+mod max_login_attempts;
+mod password_min_length;
+mod require_special_chars;
+mod clipboard_timeout;
+mod kdf_iterations;
+mod password_max_length;
+mod require_uppercase;
+mod require_lowercase;
+mod require_digits;
+mod password_symbols;
+mod max_repeated_chars;
+mod banned_substrings;
+mod min_password_score;
+mod blocklist_file;
+mod default_password_length;
+mod exclude_ambiguous_chars;
+mod password_generation_strategy;
+mod passphrase_words;
+mod passphrase_separator;
+mod hibp_file;
+mod password_max_age_days;
+mod auto_lock_on_idle;
+mod idle_timeout;
+mod require_password_for_sensitive_actions;
+mod sudo_timeout;
+mod session_scope;
+mod clipboard_max_pastes;
+pub mod setting_value;
+pub mod setting_key;
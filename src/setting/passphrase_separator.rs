@@ -0,0 +1,52 @@
+use crate::setting::core::Setting;
+use crate::setting::setting_value::SettingValue;
+use crate::setting::setting_key::SettingKey;
+
+pub struct PassphraseSeparator {
+    value: String,
+}
+
+impl PassphraseSeparator {
+    pub fn new() -> Self {
+        Self { value: "-".to_string() } // Default value
+    }
+
+    pub fn get(&self) -> &str {
+        &self.value
+    }
+}
+
+impl Setting for PassphraseSeparator {
+    fn key(&self) -> SettingKey {
+        SettingKey::PassphraseSeparator
+    }
+
+    fn default(&self) -> SettingValue {
+        SettingValue::String("-".to_string())
+    }
+
+    fn validate(&self, value: &SettingValue) -> Result<(), String> {
+        match value.as_string() {
+            Ok(separator) => {
+                if separator.chars().count() > 3 {
+                    Err("The passphrase separator can be at most 3 characters".to_string())
+                } else if separator.chars().any(|c| c.is_alphabetic()) {
+                    Err("The passphrase separator cannot contain letters".to_string())
+                } else {
+                    Ok(())
+                }
+            },
+            Err(e) => Err(e),
+        }
+    }
+
+    fn update(&mut self, value: SettingValue) -> Result<(), String> {
+        self.validate(&value)?;
+        self.value = value.as_string()?;
+        Ok(())
+    }
+
+    fn get_value(&self) -> SettingValue {
+        SettingValue::String(self.value.clone())
+    }
+}
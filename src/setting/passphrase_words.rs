@@ -0,0 +1,50 @@
+use crate::setting::core::Setting;
+use crate::setting::setting_value::SettingValue;
+use crate::setting::setting_key::SettingKey;
+
+pub struct PassphraseWords {
+    value: u32,
+}
+
+impl PassphraseWords {
+    pub fn new() -> Self {
+        Self { value: 6 } // Default value
+    }
+
+    pub fn get(&self) -> u32 {
+        self.value
+    }
+}
+
+impl Setting for PassphraseWords {
+    fn key(&self) -> SettingKey {
+        SettingKey::PassphraseWords
+    }
+
+    fn default(&self) -> SettingValue {
+        SettingValue::UnsignedInteger(6)
+    }
+
+    fn validate(&self, value: &SettingValue) -> Result<(), String> {
+        match value.as_u32() {
+            Ok(v) => {
+                if v < 3 || v > 20 {
+                    Err("Passphrase word count must be between 3 and 20".to_string())
+                } else {
+                    Ok(())
+                }
+            },
+            Err(e) => Err(e),
+        }
+    }
+
+    fn update(&mut self, value: SettingValue) -> Result<(), String> {
+        self.validate(&value)?;
+        self.value = value.as_u32()?;
+        Ok(())
+    }
+
+    fn get_value(&self) -> SettingValue {
+        SettingValue::UnsignedInteger(self.value as u64)
+    }
+}
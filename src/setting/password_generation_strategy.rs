@@ -0,0 +1,52 @@
+use crate::setting::core::Setting;
+use crate::setting::setting_value::SettingValue;
+use crate::setting::setting_key::SettingKey;
+
+pub const STRATEGIES: [&str; 3] = ["random", "passphrase", "pronounceable"];
+
+pub struct PasswordGenerationStrategy {
+    value: String,
+}
+
+impl PasswordGenerationStrategy {
+    pub fn new() -> Self {
+        Self { value: "random".to_string() } // Default value
+    }
+
+    pub fn get(&self) -> &str {
+        &self.value
+    }
+}
+
+impl Setting for PasswordGenerationStrategy {
+    fn key(&self) -> SettingKey {
+        SettingKey::PasswordGenerationStrategy
+    }
+
+    fn default(&self) -> SettingValue {
+        SettingValue::String("random".to_string())
+    }
+
+    fn validate(&self, value: &SettingValue) -> Result<(), String> {
+        match value.as_string() {
+            Ok(strategy) => {
+                if STRATEGIES.contains(&strategy.as_str()) {
+                    Ok(())
+                } else {
+                    Err(format!("Password generation strategy must be one of: {}", STRATEGIES.join(", ")))
+                }
+            },
+            Err(e) => Err(e),
+        }
+    }
+
+    fn update(&mut self, value: SettingValue) -> Result<(), String> {
+        self.validate(&value)?;
+        self.value = value.as_string()?;
+        Ok(())
+    }
+
+    fn get_value(&self) -> SettingValue {
+        SettingValue::String(self.value.clone())
+    }
+}
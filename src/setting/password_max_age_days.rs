@@ -0,0 +1,50 @@
+use crate::setting::core::Setting;
+use crate::setting::setting_value::SettingValue;
+use crate::setting::setting_key::SettingKey;
+
+pub struct PasswordMaxAgeDays {
+    value: u32,  // 0 means passwords never expire
+}
+
+impl PasswordMaxAgeDays {
+    pub fn new() -> Self {
+        Self { value: 0 } // Default value
+    }
+
+    pub fn get(&self) -> u32 {
+        self.value
+    }
+}
+
+impl Setting for PasswordMaxAgeDays {
+    fn key(&self) -> SettingKey {
+        SettingKey::PasswordMaxAgeDays
+    }
+
+    fn default(&self) -> SettingValue {
+        SettingValue::UnsignedInteger(0)
+    }
+
+    fn validate(&self, value: &SettingValue) -> Result<(), String> {
+        match value.as_u32() {
+            Ok(v) => {
+                if v > 3650 {
+                    Err("Password max age must be at most 3650 days".to_string())
+                } else {
+                    Ok(())
+                }
+            },
+            Err(e) => Err(e),
+        }
+    }
+
+    fn update(&mut self, value: SettingValue) -> Result<(), String> {
+        self.validate(&value)?;
+        self.value = value.as_u32()?;
+        Ok(())
+    }
+
+    fn get_value(&self) -> SettingValue {
+        SettingValue::UnsignedInteger(self.value as u64)
+    }
+}
@@ -0,0 +1,50 @@
+use crate::setting::core::Setting;
+use crate::setting::setting_value::SettingValue;
+use crate::setting::setting_key::SettingKey;
+
+pub struct PasswordMaxLength {
+    value: u32,
+}
+
+impl PasswordMaxLength {
+    pub fn new() -> Self {
+        Self { value: 128 } // Default value
+    }
+
+    pub fn get(&self) -> u32 {
+        self.value
+    }
+}
+
+impl Setting for PasswordMaxLength {
+    fn key(&self) -> SettingKey {
+        SettingKey::PasswordMaxLength
+    }
+
+    fn default(&self) -> SettingValue {
+        SettingValue::UnsignedInteger(128)
+    }
+
+    fn validate(&self, value: &SettingValue) -> Result<(), String> {
+        match value.as_u32() {
+            Ok(v) => {
+                if v < 8 || v > 1024 {
+                    Err("Maximum password length must be between 8 and 1024".to_string())
+                } else {
+                    Ok(())
+                }
+            },
+            Err(e) => Err(e),
+        }
+    }
+
+    fn update(&mut self, value: SettingValue) -> Result<(), String> {
+        self.validate(&value)?;
+        self.value = value.as_u32()?;
+        Ok(())
+    }
+
+    fn get_value(&self) -> SettingValue {
+        SettingValue::UnsignedInteger(self.value as u64)
+    }
+}
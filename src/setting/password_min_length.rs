@@ -0,0 +1,51 @@
+use crate::constants::CONS;
+use crate::setting::core::Setting;
+use crate::setting::setting_value::SettingValue;
+use crate::setting::setting_key::SettingKey;
+
+pub struct PasswordMinLength {
+    value: u32,
+}
+
+impl PasswordMinLength {
+    pub fn new() -> Self {
+        Self { value: CONS::MIN_PASSWORD_LENGTH as u32 } // Default value
+    }
+
+    pub fn get(&self) -> u32 {
+        self.value
+    }
+}
+
+impl Setting for PasswordMinLength {
+    fn key(&self) -> SettingKey {
+        SettingKey::PasswordMinLength
+    }
+
+    fn default(&self) -> SettingValue {
+        SettingValue::UnsignedInteger(CONS::MIN_PASSWORD_LENGTH as u64)
+    }
+
+    fn validate(&self, value: &SettingValue) -> Result<(), String> {
+        match value.as_u32() {
+            Ok(length) => {
+                if length < 4 || length > 128 {
+                    Err("Password minimum length must be between 4 and 128 characters".to_string())
+                } else {
+                    Ok(())
+                }
+            },
+            Err(e) => Err(e),
+        }
+    }
+
+    fn update(&mut self, value: SettingValue) -> Result<(), String> {
+        self.validate(&value)?;
+        self.value = value.as_u32()?;
+        Ok(())
+    }
+
+    fn get_value(&self) -> SettingValue {
+        SettingValue::UnsignedInteger(self.value as u64)
+    }
+}
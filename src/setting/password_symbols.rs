@@ -0,0 +1,54 @@
+use crate::setting::core::Setting;
+use crate::setting::setting_value::SettingValue;
+use crate::setting::setting_key::SettingKey;
+
+pub const DEFAULT_SYMBOLS: &str = "!@#$%^&*";
+
+pub struct PasswordSymbols {
+    value: String,
+}
+
+impl PasswordSymbols {
+    pub fn new() -> Self {
+        Self { value: DEFAULT_SYMBOLS.to_string() } // Default value
+    }
+
+    pub fn get(&self) -> &str {
+        &self.value
+    }
+}
+
+impl Setting for PasswordSymbols {
+    fn key(&self) -> SettingKey {
+        SettingKey::PasswordSymbols
+    }
+
+    fn default(&self) -> SettingValue {
+        SettingValue::String(DEFAULT_SYMBOLS.to_string())
+    }
+
+    fn validate(&self, value: &SettingValue) -> Result<(), String> {
+        match value.as_string() {
+            Ok(symbols) => {
+                if symbols.is_empty() {
+                    Err("The symbol set cannot be empty".to_string())
+                } else if symbols.chars().any(|c| c.is_alphanumeric() || c.is_whitespace()) {
+                    Err("The symbol set can only contain punctuation characters".to_string())
+                } else {
+                    Ok(())
+                }
+            },
+            Err(e) => Err(e),
+        }
+    }
+
+    fn update(&mut self, value: SettingValue) -> Result<(), String> {
+        self.validate(&value)?;
+        self.value = value.as_string()?;
+        Ok(())
+    }
+
+    fn get_value(&self) -> SettingValue {
+        SettingValue::String(self.value.clone())
+    }
+}
@@ -1,8 +1,39 @@
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
+use log::warn;
 use crate::setting::core::Setting;
 use crate::setting::setting_value::SettingValue;
 use crate::setting::setting_key::SettingKey;
 use crate::setting::session_duration::SessionDuration;
+use crate::setting::failed_login_lockout_duration::FailedLoginLockoutDuration;
+use crate::setting::max_login_attempts::MaxLoginAttempts;
+use crate::setting::password_min_length::PasswordMinLength;
+use crate::setting::require_special_chars::RequireSpecialChars;
+use crate::setting::backup_retention_count::BackupRetentionCount;
+use crate::setting::backup_location::BackupLocation;
+use crate::setting::clipboard_timeout::ClipboardTimeout;
+use crate::setting::kdf_iterations::KdfIterations;
+use crate::setting::password_max_length::PasswordMaxLength;
+use crate::setting::require_uppercase::RequireUppercase;
+use crate::setting::require_lowercase::RequireLowercase;
+use crate::setting::require_digits::RequireDigits;
+use crate::setting::password_symbols::PasswordSymbols;
+use crate::setting::max_repeated_chars::MaxRepeatedChars;
+use crate::setting::banned_substrings::BannedSubstrings;
+use crate::setting::min_password_score::MinPasswordScore;
+use crate::setting::blocklist_file::BlocklistFile;
+use crate::setting::default_password_length::DefaultPasswordLength;
+use crate::setting::exclude_ambiguous_chars::ExcludeAmbiguousChars;
+use crate::setting::password_generation_strategy::PasswordGenerationStrategy;
+use crate::setting::passphrase_words::PassphraseWords;
+use crate::setting::passphrase_separator::PassphraseSeparator;
+use crate::setting::hibp_file::HibpFile;
+use crate::setting::password_max_age_days::PasswordMaxAgeDays;
+use crate::setting::auto_lock_on_idle::AutoLockOnIdle;
+use crate::setting::idle_timeout::IdleTimeout;
+use crate::setting::require_password_for_sensitive_actions::RequirePasswordForSensitiveActions;
+use crate::setting::sudo_timeout::SudoTimeout;
+use crate::setting::session_scope::SessionScope;
+use crate::setting::clipboard_max_pastes::ClipboardMaxPastes;
 
 pub struct SettingsRegistry {
     settings: HashMap<SettingKey, Box<dyn Setting>>,
@@ -16,9 +47,54 @@ impl SettingsRegistry {
         
         // Register default settings
         registry.register(Box::new(SessionDuration::new()));
+        registry.register(Box::new(FailedLoginLockoutDuration::new()));
+        registry.register(Box::new(MaxLoginAttempts::new()));
+        registry.register(Box::new(PasswordMinLength::new()));
+        registry.register(Box::new(RequireSpecialChars::new()));
+        registry.register(Box::new(BackupRetentionCount::new()));
+        registry.register(Box::new(BackupLocation::new()));
+        registry.register(Box::new(ClipboardTimeout::new()));
+        registry.register(Box::new(KdfIterations::new()));
+        registry.register(Box::new(PasswordMaxLength::new()));
+        registry.register(Box::new(RequireUppercase::new()));
+        registry.register(Box::new(RequireLowercase::new()));
+        registry.register(Box::new(RequireDigits::new()));
+        registry.register(Box::new(PasswordSymbols::new()));
+        registry.register(Box::new(MaxRepeatedChars::new()));
+        registry.register(Box::new(BannedSubstrings::new()));
+        registry.register(Box::new(MinPasswordScore::new()));
+        registry.register(Box::new(BlocklistFile::new()));
+        registry.register(Box::new(DefaultPasswordLength::new()));
+        registry.register(Box::new(ExcludeAmbiguousChars::new()));
+        registry.register(Box::new(PasswordGenerationStrategy::new()));
+        registry.register(Box::new(PassphraseWords::new()));
+        registry.register(Box::new(PassphraseSeparator::new()));
+        registry.register(Box::new(HibpFile::new()));
+        registry.register(Box::new(PasswordMaxAgeDays::new()));
+        registry.register(Box::new(AutoLockOnIdle::new()));
+        registry.register(Box::new(IdleTimeout::new()));
+        registry.register(Box::new(RequirePasswordForSensitiveActions::new()));
+        registry.register(Box::new(SudoTimeout::new()));
+        registry.register(Box::new(SessionScope::new()));
+        registry.register(Box::new(ClipboardMaxPastes::new()));
         
         registry
     }
+
+    /// Builds a registry from the raw values persisted in the config.
+    /// Unknown keys and invalid values are skipped, leaving the default in place.
+    pub fn from_values(values: &BTreeMap<String, String>) -> Self {
+        let mut registry = SettingsRegistry::new();
+        for (name, raw) in values {
+            let result = name
+                .parse::<SettingKey>()
+                .and_then(|key| registry.parse(key, raw).and_then(|value| registry.update(key, value)));
+            if let Err(e) = result {
+                warn!("Ignoring stored setting {} = {}: {}", name, raw, e);
+            }
+        }
+        registry
+    }
     
     pub fn register(&mut self, setting: Box<dyn Setting>) {
         self.settings.insert(setting.key(), setting);
@@ -37,6 +113,18 @@ impl SettingsRegistry {
             None => None,
         }
     }
+
+    /// All registered settings, in declaration order.
+    pub fn list(&self) -> Vec<&dyn Setting> {
+        SettingKey::ALL.iter().filter_map(|key| self.get(*key)).collect()
+    }
+
+    pub fn parse(&self, key: SettingKey, raw: &str) -> Result<SettingValue, String> {
+        match self.settings.get(&key) {
+            Some(setting) => setting.parse(raw),
+            None => Err(format!("Setting '{:?}' not found", key)),
+        }
+    }
     
     pub fn update(&mut self, key: SettingKey, value: SettingValue) -> Result<(), String> {
         match self.settings.get_mut(&key) {
@@ -44,10 +132,170 @@ impl SettingsRegistry {
             None => Err(format!("Setting '{:?}' not found", key)),
         }
     }
+
+    pub fn reset(&mut self, key: SettingKey) -> Result<(), String> {
+        match self.settings.get_mut(&key) {
+            Some(setting) => {
+                let default = setting.default();
+                setting.update(default)
+            }
+            None => Err(format!("Setting '{:?}' not found", key)),
+        }
+    }
     
     // Convenience getters for common settings
     pub fn session_duration(&self) -> Option<u32> {
         self.get(SettingKey::SessionDuration)
             .and_then(|setting| setting.get_value().as_u32().ok())
     }
-}
\ No newline at end of file
+
+    pub fn failed_login_lockout_duration(&self) -> Option<u32> {
+        self.get(SettingKey::FailedLoginLockoutDuration)
+            .and_then(|setting| setting.get_value().as_u32().ok())
+    }
+
+    pub fn max_login_attempts(&self) -> Option<u32> {
+        self.get(SettingKey::MaxLoginAttempts)
+            .and_then(|setting| setting.get_value().as_u32().ok())
+    }
+
+    pub fn password_min_length(&self) -> Option<u32> {
+        self.get(SettingKey::PasswordMinLength)
+            .and_then(|setting| setting.get_value().as_u32().ok())
+    }
+
+    pub fn require_special_chars(&self) -> Option<bool> {
+        self.get(SettingKey::RequireSpecialChars)
+            .and_then(|setting| setting.get_value().as_bool().ok())
+    }
+
+    pub fn backup_retention_count(&self) -> Option<u32> {
+        self.get(SettingKey::BackupRetentionCount)
+            .and_then(|setting| setting.get_value().as_u32().ok())
+    }
+
+    pub fn backup_location(&self) -> Option<String> {
+        self.get(SettingKey::BackupLocation)
+            .and_then(|setting| setting.get_value().as_string().ok())
+    }
+
+    pub fn clipboard_timeout(&self) -> Option<u32> {
+        self.get(SettingKey::ClipboardTimeout)
+            .and_then(|setting| setting.get_value().as_u32().ok())
+    }
+
+    pub fn kdf_iterations(&self) -> Option<u32> {
+        self.get(SettingKey::KdfIterations)
+            .and_then(|setting| setting.get_value().as_u32().ok())
+    }
+
+    pub fn password_max_length(&self) -> Option<u32> {
+        self.get(SettingKey::PasswordMaxLength)
+            .and_then(|setting| setting.get_value().as_u32().ok())
+    }
+
+    pub fn require_uppercase(&self) -> Option<bool> {
+        self.get(SettingKey::RequireUppercase)
+            .and_then(|setting| setting.get_value().as_bool().ok())
+    }
+
+    pub fn require_lowercase(&self) -> Option<bool> {
+        self.get(SettingKey::RequireLowercase)
+            .and_then(|setting| setting.get_value().as_bool().ok())
+    }
+
+    pub fn require_digits(&self) -> Option<bool> {
+        self.get(SettingKey::RequireDigits)
+            .and_then(|setting| setting.get_value().as_bool().ok())
+    }
+
+    pub fn password_symbols(&self) -> Option<String> {
+        self.get(SettingKey::PasswordSymbols)
+            .and_then(|setting| setting.get_value().as_string().ok())
+    }
+
+    pub fn max_repeated_chars(&self) -> Option<u32> {
+        self.get(SettingKey::MaxRepeatedChars)
+            .and_then(|setting| setting.get_value().as_u32().ok())
+    }
+
+    pub fn banned_substrings(&self) -> Option<String> {
+        self.get(SettingKey::BannedSubstrings)
+            .and_then(|setting| setting.get_value().as_string().ok())
+    }
+
+    pub fn min_password_score(&self) -> Option<u32> {
+        self.get(SettingKey::MinPasswordScore)
+            .and_then(|setting| setting.get_value().as_u32().ok())
+    }
+
+    pub fn blocklist_file(&self) -> Option<String> {
+        self.get(SettingKey::BlocklistFile)
+            .and_then(|setting| setting.get_value().as_string().ok())
+    }
+
+    pub fn default_password_length(&self) -> Option<u32> {
+        self.get(SettingKey::DefaultPasswordLength)
+            .and_then(|setting| setting.get_value().as_u32().ok())
+    }
+
+    pub fn exclude_ambiguous_chars(&self) -> Option<bool> {
+        self.get(SettingKey::ExcludeAmbiguousChars)
+            .and_then(|setting| setting.get_value().as_bool().ok())
+    }
+
+    pub fn password_generation_strategy(&self) -> Option<String> {
+        self.get(SettingKey::PasswordGenerationStrategy)
+            .and_then(|setting| setting.get_value().as_string().ok())
+    }
+
+    pub fn passphrase_words(&self) -> Option<u32> {
+        self.get(SettingKey::PassphraseWords)
+            .and_then(|setting| setting.get_value().as_u32().ok())
+    }
+
+    pub fn passphrase_separator(&self) -> Option<String> {
+        self.get(SettingKey::PassphraseSeparator)
+            .and_then(|setting| setting.get_value().as_string().ok())
+    }
+
+    pub fn hibp_file(&self) -> Option<String> {
+        self.get(SettingKey::HibpFile)
+            .and_then(|setting| setting.get_value().as_string().ok())
+    }
+
+    pub fn password_max_age_days(&self) -> Option<u32> {
+        self.get(SettingKey::PasswordMaxAgeDays)
+            .and_then(|setting| setting.get_value().as_u32().ok())
+    }
+
+    pub fn auto_lock_on_idle(&self) -> Option<bool> {
+        self.get(SettingKey::AutoLockOnIdle)
+            .and_then(|setting| setting.get_value().as_bool().ok())
+    }
+
+    pub fn idle_timeout(&self) -> Option<u32> {
+        self.get(SettingKey::IdleTimeout)
+            .and_then(|setting| setting.get_value().as_u32().ok())
+    }
+
+    pub fn require_password_for_sensitive_actions(&self) -> Option<bool> {
+        self.get(SettingKey::RequirePasswordForSensitiveActions)
+            .and_then(|setting| setting.get_value().as_bool().ok())
+    }
+
+    pub fn sudo_timeout(&self) -> Option<u32> {
+        self.get(SettingKey::SudoTimeout)
+            .and_then(|setting| setting.get_value().as_u32().ok())
+    }
+
+    pub fn session_scope(&self) -> Option<String> {
+        self.get(SettingKey::SessionScope)
+            .and_then(|setting| setting.get_value().as_string().ok())
+    }
+
+    pub fn clipboard_max_pastes(&self) -> Option<u32> {
+        self.get(SettingKey::ClipboardMaxPastes)
+            .and_then(|setting| setting.get_value().as_u32().ok())
+    }
+}
@@ -0,0 +1,41 @@
+use crate::setting::core::Setting;
+use crate::setting::setting_value::SettingValue;
+use crate::setting::setting_key::SettingKey;
+
+pub struct RequireSpecialChars {
+    value: bool,
+}
+
+impl RequireSpecialChars {
+    pub fn new() -> Self {
+        Self { value: true } // Default value
+    }
+
+    pub fn get(&self) -> bool {
+        self.value
+    }
+}
+
+impl Setting for RequireSpecialChars {
+    fn key(&self) -> SettingKey {
+        SettingKey::RequireSpecialChars
+    }
+
+    fn default(&self) -> SettingValue {
+        SettingValue::Boolean(true)
+    }
+
+    fn validate(&self, value: &SettingValue) -> Result<(), String> {
+        value.as_bool().map(|_| ())
+    }
+
+    fn update(&mut self, value: SettingValue) -> Result<(), String> {
+        self.validate(&value)?;
+        self.value = value.as_bool()?;
+        Ok(())
+    }
+
+    fn get_value(&self) -> SettingValue {
+        SettingValue::Boolean(self.value)
+    }
+}
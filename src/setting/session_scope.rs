@@ -0,0 +1,51 @@
+use crate::setting::core::Setting;
+use crate::setting::setting_value::SettingValue;
+use crate::setting::setting_key::SettingKey;
+use crate::session::binding::SCOPES;
+
+pub struct SessionScope {
+    value: String,
+}
+
+impl SessionScope {
+    pub fn new() -> Self {
+        Self { value: "user".to_string() } // Default value
+    }
+
+    pub fn get(&self) -> &str {
+        &self.value
+    }
+}
+
+impl Setting for SessionScope {
+    fn key(&self) -> SettingKey {
+        SettingKey::SessionScope
+    }
+
+    fn default(&self) -> SettingValue {
+        SettingValue::String("user".to_string())
+    }
+
+    fn validate(&self, value: &SettingValue) -> Result<(), String> {
+        match value.as_string() {
+            Ok(scope) => {
+                if SCOPES.contains(&scope.as_str()) {
+                    Ok(())
+                } else {
+                    Err(format!("Session scope must be one of: {}", SCOPES.join(", ")))
+                }
+            },
+            Err(e) => Err(e),
+        }
+    }
+
+    fn update(&mut self, value: SettingValue) -> Result<(), String> {
+        self.validate(&value)?;
+        self.value = value.as_string()?;
+        Ok(())
+    }
+
+    fn get_value(&self) -> SettingValue {
+        SettingValue::String(self.value.clone())
+    }
+}
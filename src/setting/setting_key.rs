@@ -1,5 +1,6 @@
 use std::fmt::{Display, Formatter};
 use std::hash::{Hash, Hasher};
+use std::str::FromStr;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum SettingKey {
@@ -8,7 +9,33 @@ pub enum SettingKey {
     MaxLoginAttempts,
     PasswordMinLength,
     RequireSpecialChars,
+    BackupRetentionCount,
+    BackupLocation,
+    ClipboardTimeout,
+    KdfIterations,
+    PasswordMaxLength,
+    RequireUppercase,
+    RequireLowercase,
+    RequireDigits,
+    PasswordSymbols,
+    MaxRepeatedChars,
+    BannedSubstrings,
+    MinPasswordScore,
+    BlocklistFile,
+    DefaultPasswordLength,
+    ExcludeAmbiguousChars,
+    PasswordGenerationStrategy,
+    PassphraseWords,
+    PassphraseSeparator,
+    HibpFile,
     // Add more settings as needed
+    PasswordMaxAgeDays,
+    AutoLockOnIdle,
+    IdleTimeout,
+    RequirePasswordForSensitiveActions,
+    SudoTimeout,
+    SessionScope,
+    ClipboardMaxPastes,
 }
 
 impl Hash for SettingKey {
@@ -26,18 +53,116 @@ impl Display for SettingKey {
             SettingKey::MaxLoginAttempts => write!(f, "max_login_attempts"),
             SettingKey::PasswordMinLength => write!(f, "password_min_length"),
             SettingKey::RequireSpecialChars => write!(f, "require_special_chars"),
+            SettingKey::BackupRetentionCount => write!(f, "backup_retention_count"),
+            SettingKey::BackupLocation => write!(f, "backup_location"),
+            SettingKey::ClipboardTimeout => write!(f, "clipboard_timeout"),
+            SettingKey::KdfIterations => write!(f, "kdf_iterations"),
+            SettingKey::PasswordMaxLength => write!(f, "password_max_length"),
+            SettingKey::RequireUppercase => write!(f, "require_uppercase"),
+            SettingKey::RequireLowercase => write!(f, "require_lowercase"),
+            SettingKey::RequireDigits => write!(f, "require_digits"),
+            SettingKey::PasswordSymbols => write!(f, "password_symbols"),
+            SettingKey::MaxRepeatedChars => write!(f, "max_repeated_chars"),
+            SettingKey::BannedSubstrings => write!(f, "banned_substrings"),
+            SettingKey::MinPasswordScore => write!(f, "min_password_score"),
+            SettingKey::BlocklistFile => write!(f, "blocklist_file"),
+            SettingKey::DefaultPasswordLength => write!(f, "default_password_length"),
+            SettingKey::ExcludeAmbiguousChars => write!(f, "exclude_ambiguous_chars"),
+            SettingKey::PasswordGenerationStrategy => write!(f, "password_generation_strategy"),
+            SettingKey::PassphraseWords => write!(f, "passphrase_words"),
+            SettingKey::PassphraseSeparator => write!(f, "passphrase_separator"),
+            SettingKey::HibpFile => write!(f, "hibp_file"),
+            SettingKey::PasswordMaxAgeDays => write!(f, "password_max_age_days"),
+            SettingKey::AutoLockOnIdle => write!(f, "auto_lock_on_idle"),
+            SettingKey::IdleTimeout => write!(f, "idle_timeout"),
+            SettingKey::RequirePasswordForSensitiveActions => write!(f, "require_password_for_sensitive_actions"),
+            SettingKey::SudoTimeout => write!(f, "sudo_timeout"),
+            SettingKey::SessionScope => write!(f, "session_scope"),
+            SettingKey::ClipboardMaxPastes => write!(f, "clipboard_max_pastes"),
         }
     }
 }
 
+impl FromStr for SettingKey {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        SettingKey::ALL
+            .iter()
+            .find(|key| key.to_string() == s)
+            .copied()
+            .ok_or_else(|| format!("Unknown setting '{}'", s))
+    }
+}
+
 impl SettingKey {
+    pub const ALL: &'static [SettingKey] = &[
+        SettingKey::SessionDuration,
+        SettingKey::FailedLoginLockoutDuration,
+        SettingKey::MaxLoginAttempts,
+        SettingKey::PasswordMinLength,
+        SettingKey::RequireSpecialChars,
+        SettingKey::BackupRetentionCount,
+        SettingKey::BackupLocation,
+        SettingKey::ClipboardTimeout,
+        SettingKey::KdfIterations,
+        SettingKey::PasswordMaxLength,
+        SettingKey::RequireUppercase,
+        SettingKey::RequireLowercase,
+        SettingKey::RequireDigits,
+        SettingKey::PasswordSymbols,
+        SettingKey::MaxRepeatedChars,
+        SettingKey::BannedSubstrings,
+        SettingKey::MinPasswordScore,
+        SettingKey::BlocklistFile,
+        SettingKey::DefaultPasswordLength,
+        SettingKey::ExcludeAmbiguousChars,
+        SettingKey::PasswordGenerationStrategy,
+        SettingKey::PassphraseWords,
+        SettingKey::PassphraseSeparator,
+        SettingKey::HibpFile,
+        SettingKey::PasswordMaxAgeDays,
+        SettingKey::AutoLockOnIdle,
+        SettingKey::IdleTimeout,
+        SettingKey::RequirePasswordForSensitiveActions,
+        SettingKey::SudoTimeout,
+        SettingKey::SessionScope,
+        SettingKey::ClipboardMaxPastes,
+    ];
+
     pub fn description(&self) -> &'static str {
         match self {
-            SettingKey::SessionDuration => "How long a session remains active (in minutes)",
+            SettingKey::SessionDuration => "How long a session remains active after login, even when in use (in minutes)",
             SettingKey::FailedLoginLockoutDuration => "Duration of account lockout after failed login attempts (in minutes)",
             SettingKey::MaxLoginAttempts => "Maximum number of failed login attempts before lockout",
             SettingKey::PasswordMinLength => "Minimum length required for passwords",
             SettingKey::RequireSpecialChars => "Whether passwords must contain special characters",
+            SettingKey::BackupRetentionCount => "Number of most recent backups kept when pruning",
+            SettingKey::BackupLocation => "Directory where backups are stored (empty for backups/ in the data directory of the vault)",
+            SettingKey::ClipboardTimeout => "Seconds before a copied password is cleared from the clipboard (0 to never clear)",
+            SettingKey::KdfIterations => "PBKDF2 iterations used to derive the vault key when the master password is set",
+            SettingKey::PasswordMaxLength => "Maximum length allowed for passwords",
+            SettingKey::RequireUppercase => "Whether passwords must contain an uppercase letter",
+            SettingKey::RequireLowercase => "Whether passwords must contain a lowercase letter",
+            SettingKey::RequireDigits => "Whether passwords must contain a digit",
+            SettingKey::PasswordSymbols => "Characters that count as special characters",
+            SettingKey::MaxRepeatedChars => "Longest run of the same character allowed in a password (0 for no limit)",
+            SettingKey::BannedSubstrings => "Comma separated words that passwords must not contain",
+            SettingKey::MinPasswordScore => "Lowest accepted strength score, from 0 (too guessable) to 4 (very unguessable)",
+            SettingKey::BlocklistFile => "File with extra forbidden passwords, one per line (empty for the built-in lists only)",
+            SettingKey::DefaultPasswordLength => "Length of generated passwords when none is given",
+            SettingKey::ExcludeAmbiguousChars => "Whether generated passwords avoid look-alike characters (0/O, 1/l/I)",
+            SettingKey::PasswordGenerationStrategy => "How passwords are generated: random, passphrase (diceware words) or pronounceable",
+            SettingKey::PassphraseWords => "Number of words in generated passphrases",
+            SettingKey::PassphraseSeparator => "Characters placed between the words of generated passphrases",
+            SettingKey::HibpFile => "Local copy of the HIBP Pwned Passwords SHA-1 list checked on add and update (empty to skip)",
+            SettingKey::PasswordMaxAgeDays => "Days before a password is due for rotation (0 to never expire)",
+            SettingKey::AutoLockOnIdle => "Whether the session ends after idle_timeout minutes without a successful command",
+            SettingKey::IdleTimeout => "Minutes without a successful command before the session locks, see auto_lock_on_idle",
+            SettingKey::RequirePasswordForSensitiveActions => "Whether sensitive actions, such as printing a password or changing settings, ask for the master password again",
+            SettingKey::SudoTimeout => "Seconds a master password re-entry covers further sensitive actions (0 to ask every time)",
+            SettingKey::SessionScope => "Where a login is valid: user (anywhere), tty (this terminal), ppid (this shell) or logind (this login session)",
+            SettingKey::ClipboardMaxPastes => "Pastes after which a copied password is cleared from the clipboard, X11 only (0 for no limit)",
         }
     }
 }
\ No newline at end of file
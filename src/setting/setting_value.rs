@@ -24,6 +24,50 @@ impl SettingValue {
             _ => Err(format!("Cannot convert {:?} to u32", self)),
         }
     }
+
+    pub fn as_bool(&self) -> Result<bool, String> {
+        match self {
+            SettingValue::Boolean(val) => Ok(*val),
+            _ => Err(format!("Cannot convert {:?} to bool", self)),
+        }
+    }
+
+    /// Parses `raw` into a value of the same variant as `self`.
+    pub fn parse_as(&self, raw: &str) -> Result<SettingValue, String> {
+        let raw = raw.trim();
+        match self {
+            SettingValue::Integer(_) => raw
+                .parse::<i64>()
+                .map(SettingValue::Integer)
+                .map_err(|_| format!("'{}' is not an integer", raw)),
+            SettingValue::UnsignedInteger(_) => raw
+                .parse::<u64>()
+                .map(SettingValue::UnsignedInteger)
+                .map_err(|_| format!("'{}' is not a positive integer", raw)),
+            SettingValue::Float(_) => raw
+                .parse::<f64>()
+                .map(SettingValue::Float)
+                .map_err(|_| format!("'{}' is not a number", raw)),
+            SettingValue::Boolean(_) => match raw.to_lowercase().as_str() {
+                "true" | "yes" | "on" | "1" => Ok(SettingValue::Boolean(true)),
+                "false" | "no" | "off" | "0" => Ok(SettingValue::Boolean(false)),
+                _ => Err(format!("'{}' is not a boolean (true/false)", raw)),
+            },
+            SettingValue::String(_) => Ok(SettingValue::String(raw.to_string())),
+            SettingValue::Duration(_) => raw
+                .trim_end_matches('s')
+                .parse::<u64>()
+                .map(|secs| SettingValue::Duration(std::time::Duration::from_secs(secs)))
+                .map_err(|_| format!("'{}' is not a duration in seconds", raw)),
+        }
+    }
+
+    pub fn as_string(&self) -> Result<String, String> {
+        match self {
+            SettingValue::String(val) => Ok(val.clone()),
+            _ => Err(format!("Cannot convert {:?} to string", self)),
+        }
+    }
 }
 
 impl Display for SettingValue {
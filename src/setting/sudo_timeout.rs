@@ -0,0 +1,50 @@
+use crate::setting::core::Setting;
+use crate::setting::setting_value::SettingValue;
+use crate::setting::setting_key::SettingKey;
+
+pub struct SudoTimeout {
+    value: u32,  // seconds, 0 asks every time
+}
+
+impl SudoTimeout {
+    pub fn new() -> Self {
+        Self { value: 300 } // Default value
+    }
+
+    pub fn get(&self) -> u32 {
+        self.value
+    }
+}
+
+impl Setting for SudoTimeout {
+    fn key(&self) -> SettingKey {
+        SettingKey::SudoTimeout
+    }
+
+    fn default(&self) -> SettingValue {
+        SettingValue::UnsignedInteger(300)
+    }
+
+    fn validate(&self, value: &SettingValue) -> Result<(), String> {
+        match value.as_u32() {
+            Ok(seconds) => {
+                if seconds > 3600 {
+                    Err("Sudo timeout must be between 0 and 3600 seconds".to_string())
+                } else {
+                    Ok(())
+                }
+            },
+            Err(e) => Err(e),
+        }
+    }
+
+    fn update(&mut self, value: SettingValue) -> Result<(), String> {
+        self.validate(&value)?;
+        self.value = value.as_u32()?;
+        Ok(())
+    }
+
+    fn get_value(&self) -> SettingValue {
+        SettingValue::UnsignedInteger(self.value as u64)
+    }
+}
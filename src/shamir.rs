@@ -0,0 +1,128 @@
+//! Shamir's secret sharing over GF(256), one polynomial per secret byte.
+//!
+//! Any `threshold` shares give the secret back, fewer give no information
+//! about it. The field is the one of AES (x^8 + x^4 + x^3 + x + 1).
+
+use rand::RngCore;
+
+fn mul(mut a: u8, mut b: u8) -> u8 {
+    let mut product = 0;
+    while b != 0 {
+        if b & 1 != 0 {
+            product ^= a;
+        }
+        let carry = a & 0x80;
+        a <<= 1;
+        if carry != 0 {
+            a ^= 0x1b;
+        }
+        b >>= 1;
+    }
+    product
+}
+
+// a^254 is the inverse of a in GF(256)
+fn inv(a: u8) -> u8 {
+    let mut result = 1;
+    let mut base = a;
+    let mut exp = 254;
+    while exp > 0 {
+        if exp & 1 == 1 {
+            result = mul(result, base);
+        }
+        base = mul(base, base);
+        exp >>= 1;
+    }
+    result
+}
+
+/// Splits `secret` into `shares` pieces as (x, y bytes) pairs, x going from 1 to `shares`.
+pub fn split(secret: &[u8], shares: u8, threshold: u8) -> Result<Vec<(u8, Vec<u8>)>, String> {
+    if threshold < 2 {
+        return Err("The threshold must be at least 2".to_string());
+    }
+    if shares < threshold {
+        return Err(format!("{} shares cannot reach a threshold of {}", shares, threshold));
+    }
+
+    let mut rng = rand::thread_rng();
+    let mut out: Vec<(u8, Vec<u8>)> = (1..=shares).map(|x| (x, Vec::with_capacity(secret.len()))).collect();
+    let mut coefficients = vec![0u8; threshold as usize];
+    for &byte in secret {
+        coefficients[0] = byte;
+        rng.fill_bytes(&mut coefficients[1..]);
+        for (x, y) in out.iter_mut() {
+            // Horner, highest degree first
+            let value = coefficients.iter().rev().fold(0, |acc, &c| mul(acc, *x) ^ c);
+            y.push(value);
+        }
+    }
+    Ok(out)
+}
+
+/// Rebuilds the secret from shares by Lagrange interpolation at x = 0.
+/// With fewer shares than the threshold the result is garbage, not an error.
+pub fn combine(shares: &[(u8, Vec<u8>)]) -> Result<Vec<u8>, String> {
+    let len = match shares.first() {
+        Some((_, y)) => y.len(),
+        None => return Err("No shares given".to_string()),
+    };
+    for (i, (x, y)) in shares.iter().enumerate() {
+        if *x == 0 {
+            return Err("Share number 0 is invalid".to_string());
+        }
+        if y.len() != len {
+            return Err("The shares have different lengths".to_string());
+        }
+        if shares[..i].iter().any(|(other, _)| other == x) {
+            return Err(format!("Share {} was given twice", x));
+        }
+    }
+
+    let mut secret = vec![0u8; len];
+    for (j, (xj, yj)) in shares.iter().enumerate() {
+        // basis polynomial of share j evaluated at 0, subtraction is xor here
+        let mut basis = 1;
+        for (m, (xm, _)) in shares.iter().enumerate() {
+            if m != j {
+                basis = mul(basis, mul(*xm, inv(xm ^ xj)));
+            }
+        }
+        for (byte, y) in secret.iter_mut().zip(yj) {
+            *byte ^= mul(*y, basis);
+        }
+    }
+    Ok(secret)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_field_inverse() {
+        for a in 1..=255u8 {
+            assert_eq!(mul(a, inv(a)), 1);
+        }
+    }
+
+    #[test]
+    fn test_any_threshold_subset_recovers() {
+        let secret = b"correct horse battery staple 32b".to_vec();
+        let shares = split(&secret, 5, 3).unwrap();
+
+        assert_eq!(combine(&shares).unwrap(), secret);
+        assert_eq!(combine(&[shares[4].clone(), shares[0].clone(), shares[2].clone()]).unwrap(), secret);
+        assert_eq!(combine(&shares[1..4]).unwrap(), secret);
+        assert_ne!(combine(&shares[..2]).unwrap(), secret);
+    }
+
+    #[test]
+    fn test_invalid_input() {
+        assert!(split(b"x", 2, 3).is_err());
+        assert!(split(b"x", 3, 1).is_err());
+        let shares = split(b"xy", 3, 2).unwrap();
+        assert!(combine(&[shares[0].clone(), shares[0].clone()]).is_err());
+        assert!(combine(&[]).is_err());
+    }
+}
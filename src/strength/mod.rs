@@ -0,0 +1,777 @@
+//! Password strength estimation in the spirit of zxcvbn.
+//!
+//! The password is broken into the cheapest sequence of patterns an attacker
+//! would try (dictionary words, keyboard walks, sequences, repeats, dates),
+//! falling back to brute force for whatever is left. The number of guesses of
+//! that sequence gives the score and the crack time estimates.
+
+use chrono::{Datelike, Utc};
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::OnceLock;
+
+const PASSWORDS: &str = include_str!("data/passwords.txt");
+const ENGLISH: &str = include_str!("data/english.txt");
+const NAMES: &str = include_str!("data/names.txt");
+
+const BRUTEFORCE_CARDINALITY: f64 = 10.0;
+const MIN_GUESSES_SINGLE_CHAR: f64 = 10.0;
+const MIN_GUESSES_MULTI_CHAR: f64 = 50.0;
+const MIN_YEAR_SPACE: f64 = 20.0;
+
+// guesses per second for the crack time estimates
+const ONLINE_THROTTLED: f64 = 100.0 / 3600.0;
+const OFFLINE_SLOW_HASH: f64 = 1e4;
+const OFFLINE_FAST_HASH: f64 = 1e10;
+
+// qwerty rows, each row sits half a key to the right of the one above
+const KEYBOARD_ROWS: [(&str, &str); 4] = [
+    ("`1234567890-=", "~!@#$%^&*()_+"),
+    ("qwertyuiop[]\\", "QWERTYUIOP{}|"),
+    ("asdfghjkl;'", "ASDFGHJKL:\""),
+    ("zxcvbnm,./", "ZXCVBNM<>?"),
+];
+const KEYBOARD_STARTING_POSITIONS: f64 = 94.0;
+const KEYBOARD_AVERAGE_DEGREE: f64 = 4.6;
+
+const L33T_TABLE: &[(char, &[char])] = &[
+    ('4', &['a']),
+    ('@', &['a']),
+    ('8', &['b']),
+    ('(', &['c']),
+    ('3', &['e']),
+    ('6', &['g']),
+    ('9', &['g']),
+    ('1', &['i', 'l']),
+    ('!', &['i']),
+    ('|', &['i', 'l']),
+    ('0', &['o']),
+    ('$', &['s']),
+    ('5', &['s']),
+    ('7', &['t']),
+    ('+', &['t']),
+    ('2', &['z']),
+];
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Pattern {
+    Dictionary { word: String, rank: usize, dictionary: &'static str, reversed: bool, l33t: bool },
+    Spatial { turns: usize },
+    Sequence { ascending: bool },
+    Repeat { base: String, count: usize },
+    Date { year: i32 },
+    Bruteforce,
+}
+
+/// One piece of the password, covering the characters `i..=j`.
+#[derive(Debug, Clone)]
+pub struct Match {
+    pub i: usize,
+    pub j: usize,
+    pub token: String,
+    pub pattern: Pattern,
+    pub guesses: f64,
+}
+
+/// Result of the estimation, `score` goes from 0 (too guessable) to 4 (very unguessable).
+#[derive(Debug, Clone)]
+pub struct Strength {
+    pub guesses: f64,
+    pub score: u8,
+    pub sequence: Vec<Match>,
+    pub warning: Option<String>,
+    pub suggestions: Vec<String>,
+}
+
+impl Strength {
+    pub fn crack_time_online(&self) -> String {
+        display_time(self.guesses / ONLINE_THROTTLED)
+    }
+
+    pub fn crack_time_offline_slow(&self) -> String {
+        display_time(self.guesses / OFFLINE_SLOW_HASH)
+    }
+
+    pub fn crack_time_offline_fast(&self) -> String {
+        display_time(self.guesses / OFFLINE_FAST_HASH)
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self.score {
+            0 => "very weak",
+            1 => "weak",
+            2 => "fair",
+            3 => "strong",
+            _ => "very strong",
+        }
+    }
+
+    /// Bar such as `[###--] 3/4 strong`.
+    pub fn meter(&self) -> String {
+        let filled = self.score as usize + 1;
+        format!("[{}{}] {}/4 {}", "#".repeat(filled), "-".repeat(5 - filled), self.score, self.label())
+    }
+}
+
+impl fmt::Display for Strength {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}, cracked in {} online (throttled), {} offline (slow hash)",
+            self.meter(),
+            self.crack_time_online(),
+            self.crack_time_offline_slow()
+        )?;
+        if let Some(warning) = &self.warning {
+            write!(f, "\n  {}", warning)?;
+        }
+        for suggestion in &self.suggestions {
+            write!(f, "\n  - {}", suggestion)?;
+        }
+        Ok(())
+    }
+}
+
+/// Estimates the strength of `password`. `user_inputs` are words an attacker
+/// targeting this password would try first, such as the username or entry name.
+pub fn estimate(password: &str, user_inputs: &[&str]) -> Strength {
+    let chars: Vec<char> = password.chars().collect();
+    if chars.is_empty() {
+        return Strength {
+            guesses: 1.0,
+            score: 0,
+            sequence: Vec::new(),
+            warning: Some("The password is empty".to_string()),
+            suggestions: Vec::new(),
+        };
+    }
+
+    let user_dictionary = user_dictionary(user_inputs);
+    let mut matches = Vec::new();
+    matches.extend(dictionary_matches(&chars, &user_dictionary));
+    matches.extend(spatial_matches(&chars));
+    matches.extend(sequence_matches(&chars));
+    matches.extend(repeat_matches(&chars));
+    matches.extend(date_matches(&chars));
+
+    let (guesses, sequence) = most_guessable_sequence(&chars, &matches);
+    let score = score(guesses);
+    let (warning, suggestions) = feedback(score, &sequence);
+
+    Strength { guesses, score, sequence, warning, suggestions }
+}
+
+fn score(guesses: f64) -> u8 {
+    // offsets the zxcvbn thresholds so a barely passing estimate does not round up
+    let delta = 5.0;
+    if guesses < 1e3 + delta {
+        0
+    } else if guesses < 1e6 + delta {
+        1
+    } else if guesses < 1e8 + delta {
+        2
+    } else if guesses < 1e10 + delta {
+        3
+    } else {
+        4
+    }
+}
+
+fn ranked(data: &'static str) -> HashMap<&'static str, usize> {
+    let mut ranks = HashMap::new();
+    for (index, word) in data.lines().map(str::trim).filter(|w| !w.is_empty()).enumerate() {
+        ranks.entry(word).or_insert(index + 1);
+    }
+    ranks
+}
+
+fn dictionaries() -> &'static [(&'static str, HashMap<&'static str, usize>)] {
+    static DICTIONARIES: OnceLock<Vec<(&'static str, HashMap<&'static str, usize>)>> = OnceLock::new();
+    DICTIONARIES.get_or_init(|| {
+        vec![
+            ("common password", ranked(PASSWORDS)),
+            ("english word", ranked(ENGLISH)),
+            ("name", ranked(NAMES)),
+        ]
+    })
+}
+
+fn user_dictionary(user_inputs: &[&str]) -> HashMap<String, usize> {
+    let mut ranks = HashMap::new();
+    let tokens = user_inputs
+        .iter()
+        .flat_map(|input| {
+            let lowered = input.to_lowercase();
+            let mut tokens: Vec<String> = lowered
+                .split(|c: char| !c.is_alphanumeric())
+                .map(str::to_string)
+                .collect();
+            tokens.push(lowered);
+            tokens
+        })
+        .filter(|token| token.chars().count() >= 3);
+    for (index, token) in tokens.enumerate() {
+        ranks.entry(token).or_insert(index + 1);
+    }
+    ranks
+}
+
+fn dictionary_matches(chars: &[char], user_dictionary: &HashMap<String, usize>) -> Vec<Match> {
+    let mut matches = Vec::new();
+    let n = chars.len();
+
+    for i in 0..n {
+        for j in (i + 2)..n {
+            let token: String = chars[i..=j].iter().collect();
+            let lowered = token.to_lowercase();
+            let reversed: String = lowered.chars().rev().collect();
+
+            let mut candidates = vec![(lowered.clone(), false, false), (reversed, true, false)];
+            for unleeted in unleet(&lowered) {
+                candidates.push((unleeted, false, true));
+            }
+
+            for (word, is_reversed, is_l33t) in candidates {
+                let mut found: Vec<(&'static str, usize)> = dictionaries()
+                    .iter()
+                    .filter_map(|(name, ranks)| ranks.get(word.as_str()).map(|rank| (*name, *rank)))
+                    .collect();
+                if let Some(rank) = user_dictionary.get(&word) {
+                    found.push(("your own details", *rank));
+                }
+
+                for (dictionary, rank) in found {
+                    let mut guesses = rank as f64 * uppercase_variations(&token);
+                    if is_reversed {
+                        guesses *= 2.0;
+                    }
+                    if is_l33t {
+                        guesses *= l33t_variations(&token);
+                    }
+                    matches.push(Match {
+                        i,
+                        j,
+                        token: token.clone(),
+                        pattern: Pattern::Dictionary {
+                            word: word.clone(),
+                            rank,
+                            dictionary,
+                            reversed: is_reversed,
+                            l33t: is_l33t,
+                        },
+                        guesses,
+                    });
+                }
+            }
+        }
+    }
+    matches
+}
+
+// variants of `token` with l33t characters translated back, limited to a few per token
+fn unleet(token: &str) -> Vec<String> {
+    if !token.chars().any(|c| L33T_TABLE.iter().any(|(l, _)| *l == c)) {
+        return Vec::new();
+    }
+
+    let mut variants = vec![String::new()];
+    for c in token.chars() {
+        let replacements: Vec<char> = match L33T_TABLE.iter().find(|(l, _)| *l == c) {
+            Some((_, letters)) => letters.to_vec(),
+            None => vec![c],
+        };
+        let mut next = Vec::new();
+        for variant in &variants {
+            for r in &replacements {
+                let mut v = variant.clone();
+                v.push(*r);
+                next.push(v);
+            }
+        }
+        next.truncate(8);
+        variants = next;
+    }
+    variants.retain(|v| v != token);
+    variants
+}
+
+fn n_choose_k(n: usize, k: usize) -> f64 {
+    if k > n {
+        return 0.0;
+    }
+    let mut result = 1.0;
+    for d in 1..=k {
+        result = result * (n - k + d) as f64 / d as f64;
+    }
+    result
+}
+
+fn uppercase_variations(token: &str) -> f64 {
+    let upper = token.chars().filter(|c| c.is_uppercase()).count();
+    let lower = token.chars().filter(|c| c.is_lowercase()).count();
+    if upper == 0 {
+        return 1.0;
+    }
+    let first_upper = token.chars().next().map_or(false, |c| c.is_uppercase());
+    let last_upper = token.chars().last().map_or(false, |c| c.is_uppercase());
+    if lower == 0 || (upper == 1 && (first_upper || last_upper)) {
+        return 2.0;
+    }
+    (1..=upper.min(lower)).map(|k| n_choose_k(upper + lower, k)).sum()
+}
+
+fn l33t_variations(token: &str) -> f64 {
+    let substituted = token
+        .chars()
+        .filter(|c| L33T_TABLE.iter().any(|(l, _)| l == c))
+        .count();
+    (2.0_f64).powi(substituted as i32).max(2.0)
+}
+
+fn keyboard_position(c: char) -> Option<(i32, i32, bool)> {
+    for (y, (plain, shifted)) in KEYBOARD_ROWS.iter().enumerate() {
+        if let Some(x) = plain.chars().position(|k| k == c) {
+            return Some((x as i32, y as i32, false));
+        }
+        if let Some(x) = shifted.chars().position(|k| k == c) {
+            return Some((x as i32, y as i32, true));
+        }
+    }
+    None
+}
+
+// direction from `a` to the adjacent key `b`, None if they are not neighbours
+fn keyboard_direction(a: char, b: char) -> Option<usize> {
+    let (ax, ay, _) = keyboard_position(a)?;
+    let (bx, by, _) = keyboard_position(b)?;
+    let neighbours = [(-1, 0), (1, 0), (0, -1), (1, -1), (-1, 1), (0, 1)];
+    neighbours
+        .iter()
+        .position(|(dx, dy)| ax + dx == bx && ay + dy == by)
+}
+
+fn spatial_matches(chars: &[char]) -> Vec<Match> {
+    let mut matches = Vec::new();
+    let mut i = 0;
+    while i < chars.len() {
+        let mut j = i;
+        let mut turns = 0;
+        let mut last_direction = None;
+        while j + 1 < chars.len() {
+            match keyboard_direction(chars[j], chars[j + 1]) {
+                Some(direction) => {
+                    if last_direction != Some(direction) {
+                        turns += 1;
+                        last_direction = Some(direction);
+                    }
+                    j += 1;
+                }
+                None => break,
+            }
+        }
+
+        let length = j - i + 1;
+        if length >= 3 {
+            let shifted = chars[i..=j]
+                .iter()
+                .filter(|c| keyboard_position(**c).map_or(false, |(_, _, s)| s))
+                .count();
+            let mut guesses = 0.0;
+            for l in 2..=length {
+                for t in 1..=turns.min(l - 1) {
+                    guesses += n_choose_k(l - 1, t - 1)
+                        * KEYBOARD_STARTING_POSITIONS
+                        * KEYBOARD_AVERAGE_DEGREE.powi(t as i32);
+                }
+            }
+            if shifted > 0 {
+                let unshifted = length - shifted;
+                guesses *= if unshifted == 0 {
+                    2.0
+                } else {
+                    (1..=shifted.min(unshifted)).map(|k| n_choose_k(length, k)).sum()
+                };
+            }
+            matches.push(Match {
+                i,
+                j,
+                token: chars[i..=j].iter().collect(),
+                pattern: Pattern::Spatial { turns },
+                guesses,
+            });
+        }
+        i = j.max(i) + 1;
+    }
+    matches
+}
+
+fn sequence_matches(chars: &[char]) -> Vec<Match> {
+    let same_class = |a: char, b: char| {
+        (a.is_ascii_lowercase() && b.is_ascii_lowercase())
+            || (a.is_ascii_uppercase() && b.is_ascii_uppercase())
+            || (a.is_ascii_digit() && b.is_ascii_digit())
+    };
+
+    let mut matches = Vec::new();
+    let mut i = 0;
+    while i + 1 < chars.len() {
+        let delta = chars[i + 1] as i32 - chars[i] as i32;
+        let mut j = i + 1;
+        if (delta == 1 || delta == -1) && same_class(chars[i], chars[i + 1]) {
+            while j + 1 < chars.len()
+                && chars[j + 1] as i32 - chars[j] as i32 == delta
+                && same_class(chars[j], chars[j + 1])
+            {
+                j += 1;
+            }
+        }
+
+        let length = j - i + 1;
+        if length >= 3 {
+            let first = chars[i];
+            let base = if "aAzZ019".contains(first) {
+                4.0
+            } else if first.is_ascii_digit() {
+                10.0
+            } else {
+                26.0
+            };
+            let ascending = delta > 0;
+            matches.push(Match {
+                i,
+                j,
+                token: chars[i..=j].iter().collect(),
+                pattern: Pattern::Sequence { ascending },
+                guesses: base * length as f64 * if ascending { 1.0 } else { 2.0 },
+            });
+            i = j;
+        } else {
+            i += 1;
+        }
+    }
+    matches
+}
+
+fn repeat_matches(chars: &[char]) -> Vec<Match> {
+    let mut matches = Vec::new();
+    let n = chars.len();
+
+    for i in 0..n {
+        for base_len in 1..=4 {
+            let mut count = 1;
+            while i + (count + 1) * base_len <= n
+                && chars[i + count * base_len..i + (count + 1) * base_len] == chars[i..i + base_len]
+            {
+                count += 1;
+            }
+            let length = count * base_len;
+            if count >= 2 && length >= 3 {
+                let base: String = chars[i..i + base_len].iter().collect();
+                let base_guesses = (BRUTEFORCE_CARDINALITY.powi(base_len as i32)).max(MIN_GUESSES_SINGLE_CHAR);
+                matches.push(Match {
+                    i,
+                    j: i + length - 1,
+                    token: chars[i..i + length].iter().collect(),
+                    pattern: Pattern::Repeat { base, count },
+                    guesses: base_guesses * count as f64,
+                });
+            }
+        }
+    }
+    matches
+}
+
+fn date_matches(chars: &[char]) -> Vec<Match> {
+    let reference_year = Utc::now().year();
+    let year_guesses = |year: i32| ((year - reference_year).abs() as f64).max(MIN_YEAR_SPACE);
+
+    let mut matches = Vec::new();
+    let n = chars.len();
+    for i in 0..n {
+        for j in (i + 3)..n.min(i + 10) {
+            let token: String = chars[i..=j].iter().collect();
+            let digits: String = token.chars().filter(|c| c.is_ascii_digit()).collect();
+            let separators = token.len() - digits.len();
+
+            if separators == 0 && digits.len() == 4 {
+                if let Ok(year) = digits.parse::<i32>() {
+                    if (1900..=2099).contains(&year) {
+                        matches.push(Match {
+                            i,
+                            j,
+                            token: token.clone(),
+                            pattern: Pattern::Date { year },
+                            guesses: year_guesses(year),
+                        });
+                    }
+                }
+            }
+
+            if let Some(year) = parse_date(&token) {
+                let mut guesses = year_guesses(year) * 365.0;
+                if separators > 0 {
+                    guesses *= 4.0;
+                }
+                matches.push(Match { i, j, token, pattern: Pattern::Date { year }, guesses });
+            }
+        }
+    }
+    matches
+}
+
+// recognises day/month/year in any common order, returns the year
+fn parse_date(token: &str) -> Option<i32> {
+    let parts: Vec<&str> = token.split(|c| c == '/' || c == '-' || c == '.' || c == ' ').collect();
+    let groups: Vec<Vec<u32>> = if parts.len() == 3 {
+        if parts.iter().any(|p| p.is_empty() || !p.chars().all(|c| c.is_ascii_digit())) {
+            return None;
+        }
+        vec![parts.iter().map(|p| p.parse().unwrap_or(0)).collect()]
+    } else if parts.len() == 1 && token.chars().all(|c| c.is_ascii_digit()) && (4..=8).contains(&token.len()) {
+        // without separators try every split into three numbers
+        let mut groups = Vec::new();
+        for a in 1..token.len() - 1 {
+            for b in (a + 1)..token.len() {
+                let (x, y, z) = (&token[..a], &token[a..b], &token[b..]);
+                if [x, y, z].iter().all(|p| p.len() <= 4) {
+                    groups.push(vec![x.parse().ok()?, y.parse().ok()?, z.parse().ok()?]);
+                }
+            }
+        }
+        groups
+    } else {
+        return None;
+    };
+
+    let valid_day_month = |d: u32, m: u32| (1..=31).contains(&d) && (1..=12).contains(&m);
+    let full_year = |y: u32| -> Option<i32> {
+        match y {
+            1900..=2099 => Some(y as i32),
+            0..=99 => Some(if y > 50 { 1900 + y as i32 } else { 2000 + y as i32 }),
+            _ => None,
+        }
+    };
+
+    for g in groups {
+        let (a, b, c) = (g[0], g[1], g[2]);
+        // year last: dd mm yy or mm dd yy
+        if valid_day_month(a, b) || valid_day_month(b, a) {
+            if let Some(year) = full_year(c) {
+                return Some(year);
+            }
+        }
+        // year first: yyyy mm dd
+        if a >= 1900 && (valid_day_month(c, b) || valid_day_month(b, c)) {
+            if let Some(year) = full_year(a) {
+                return Some(year);
+            }
+        }
+    }
+    None
+}
+
+fn bruteforce_guesses(length: usize) -> f64 {
+    let min = if length == 1 { MIN_GUESSES_SINGLE_CHAR + 1.0 } else { MIN_GUESSES_MULTI_CHAR + 1.0 };
+    BRUTEFORCE_CARDINALITY.powi(length as i32).max(min)
+}
+
+fn factorial(n: usize) -> f64 {
+    (1..=n).map(|k| k as f64).product()
+}
+
+/// Finds the sequence of non-overlapping matches covering the password that
+/// minimises `count! * product(guesses)`, filling the gaps with brute force.
+fn most_guessable_sequence(chars: &[char], matches: &[Match]) -> (f64, Vec<Match>) {
+    let n = chars.len();
+    let min_guesses = |m: &Match| {
+        let floor = if m.j == m.i { MIN_GUESSES_SINGLE_CHAR } else { MIN_GUESSES_MULTI_CHAR };
+        m.guesses.max(floor)
+    };
+
+    let mut by_end: Vec<Vec<Match>> = vec![Vec::new(); n];
+    for m in matches {
+        by_end[m.j].push(m.clone());
+    }
+    for j in 0..n {
+        for i in 0..=j {
+            by_end[j].push(Match {
+                i,
+                j,
+                token: chars[i..=j].iter().collect(),
+                pattern: Pattern::Bruteforce,
+                guesses: bruteforce_guesses(j - i + 1),
+            });
+        }
+    }
+
+    // best[k][l]: lowest log10 product of guesses for the first k characters in l matches
+    let mut best: Vec<HashMap<usize, (f64, Option<Match>)>> = vec![HashMap::new(); n + 1];
+    best[0].insert(0, (0.0, None));
+    for k in 1..=n {
+        for m in &by_end[k - 1] {
+            let previous: Vec<(usize, f64)> = best[m.i].iter().map(|(l, (cost, _))| (*l, *cost)).collect();
+            for (l, cost) in previous {
+                let candidate = cost + min_guesses(m).log10();
+                let entry = best[k].entry(l + 1).or_insert((f64::INFINITY, None));
+                if candidate < entry.0 {
+                    *entry = (candidate, Some(m.clone()));
+                }
+            }
+        }
+    }
+
+    let (mut length, mut log_guesses) = (0, f64::INFINITY);
+    for (l, (cost, _)) in &best[n] {
+        let total = cost + factorial(*l).log10();
+        if total < log_guesses {
+            length = *l;
+            log_guesses = total;
+        }
+    }
+
+    let mut sequence = Vec::new();
+    let mut k = n;
+    let mut l = length;
+    while k > 0 {
+        let m = best[k].get(&l).and_then(|(_, m)| m.clone()).expect("optimal sequence is complete");
+        k = m.i;
+        l -= 1;
+        sequence.push(m);
+    }
+    sequence.reverse();
+
+    (10f64.powf(log_guesses), sequence)
+}
+
+fn feedback(score: u8, sequence: &[Match]) -> (Option<String>, Vec<String>) {
+    if score > 2 {
+        return (None, Vec::new());
+    }
+
+    let mut suggestions = vec!["Add another word or two, uncommon words are better".to_string()];
+    let longest = sequence
+        .iter()
+        .filter(|m| m.pattern != Pattern::Bruteforce)
+        .max_by_key(|m| m.token.chars().count());
+
+    let warning = match longest.map(|m| (&m.pattern, m)) {
+        Some((Pattern::Dictionary { dictionary, rank, reversed, l33t, .. }, m)) => {
+            if m.token.chars().next().map_or(false, |c| c.is_uppercase()) {
+                suggestions.push("Capitalizing the first letter does not help much".to_string());
+            }
+            if *reversed {
+                suggestions.push("Reversed words are not much harder to guess".to_string());
+            }
+            if *l33t {
+                suggestions.push("Predictable substitutions like '@' instead of 'a' do not help much".to_string());
+            }
+            Some(match *dictionary {
+                "common password" if *rank <= 100 => "This is a top-100 common password".to_string(),
+                "common password" => "This is similar to a commonly used password".to_string(),
+                "your own details" => "Avoid your username or the entry name in the password".to_string(),
+                "name" => "Names and surnames are easy to guess".to_string(),
+                _ => "A word by itself is easy to guess".to_string(),
+            })
+        }
+        Some((Pattern::Spatial { turns }, _)) => {
+            suggestions.push("Use a longer keyboard pattern with more turns".to_string());
+            Some(if *turns == 1 {
+                "Straight rows of keys are easy to guess".to_string()
+            } else {
+                "Short keyboard patterns are easy to guess".to_string()
+            })
+        }
+        Some((Pattern::Repeat { base, .. }, _)) => {
+            suggestions.push("Avoid repeated words and characters".to_string());
+            Some(if base.chars().count() == 1 {
+                "Repeats like \"aaa\" are easy to guess".to_string()
+            } else {
+                "Repeats like \"abcabcabc\" are only slightly harder to guess than \"abc\"".to_string()
+            })
+        }
+        Some((Pattern::Sequence { .. }, _)) => {
+            suggestions.push("Avoid sequences".to_string());
+            Some("Sequences like abc or 6543 are easy to guess".to_string())
+        }
+        Some((Pattern::Date { .. }, _)) => {
+            suggestions.push("Avoid dates and years that are associated with you".to_string());
+            Some("Dates are often easy to guess".to_string())
+        }
+        _ => None,
+    };
+    (warning, suggestions)
+}
+
+fn display_time(seconds: f64) -> String {
+    let minute = 60.0;
+    let hour = minute * 60.0;
+    let day = hour * 24.0;
+    let month = day * 31.0;
+    let year = month * 12.0;
+    let century = year * 100.0;
+
+    let (value, unit) = if seconds < 1.0 {
+        return "less than a second".to_string();
+    } else if seconds < minute {
+        (seconds, "second")
+    } else if seconds < hour {
+        (seconds / minute, "minute")
+    } else if seconds < day {
+        (seconds / hour, "hour")
+    } else if seconds < month {
+        (seconds / day, "day")
+    } else if seconds < year {
+        (seconds / month, "month")
+    } else if seconds < century {
+        (seconds / year, "year")
+    } else {
+        return "centuries".to_string();
+    };
+    let value = value.round() as u64;
+    format!("{} {}{}", value, unit, if value == 1 { "" } else { "s" })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_common_password_with_decorations_is_weak() {
+        let strength = estimate("Password1!", &[]);
+        assert!(strength.score <= 1, "score was {}", strength.score);
+        assert!(strength.warning.is_some());
+    }
+
+    #[test]
+    fn test_l33t_and_keyboard_patterns_are_detected() {
+        assert!(estimate("p@ssw0rd", &[]).score == 0);
+        assert!(estimate("qwertyuiop", &[]).score <= 1);
+        assert!(estimate("1qaz2wsx", &[]).score <= 1);
+    }
+
+    #[test]
+    fn test_dates_sequences_and_repeats_are_cheap() {
+        assert!(estimate("12/05/1987", &[]).score <= 1);
+        assert!(estimate("abcdefgh", &[]).score == 0);
+        assert!(estimate("zzzzzzzzzz", &[]).score == 0);
+    }
+
+    #[test]
+    fn test_user_inputs_lower_the_estimate() {
+        let without = estimate("lkkheir2024", &[]);
+        let with = estimate("lkkheir2024", &["lkkheir"]);
+        assert!(with.guesses < without.guesses);
+    }
+
+    #[test]
+    fn test_random_password_is_strong() {
+        assert_eq!(estimate("xK#9vQ!m2Rp$L7wZ", &[]).score, 4);
+    }
+
+    #[test]
+    fn test_crack_time_display() {
+        assert_eq!(display_time(0.5), "less than a second");
+        assert_eq!(display_time(90.0), "2 minutes");
+        assert_eq!(display_time(1e12), "centuries");
+    }
+}
@@ -0,0 +1,203 @@
+//! Second factor for unlocking the vault.
+//!
+//! The TOTP seed is stored in the config sealed with the vault key, next to
+//! salted hashes of single use recovery codes for when the authenticator is
+//! lost. A code is accepted one step either side of the current one to allow
+//! for clock drift, and never twice.
+//!
+//! The code gates `kofl login` only, it is not part of the vault key. Someone
+//! with the master password, the key file if any and a copy of the files can
+//! derive the key and decrypt the vault without it. It keeps a leaked master
+//! password from opening a session on this machine, not an offline attack.
+
+use crate::cipher;
+use crate::otp::{OtpKind, OtpSecret};
+use qrcode::render::unicode;
+use qrcode::QrCode;
+use rand::distributions::Alphanumeric;
+use rand::{thread_rng, Rng, RngCore};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+const ISSUER: &str = "kofl";
+const SEED_BYTES: usize = 20;
+const RECOVERY_CODES: usize = 10;
+// accepted steps before and after the current one
+const DRIFT_STEPS: u64 = 1;
+const RECOVERY_ALPHABET: &[u8] = b"abcdefghjkmnpqrstuvwxyz23456789";
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct TwoFactor {
+    // otpauth URI of the seed, see `cipher::seal`
+    sealed_seed: String,
+    recovery_salt: String,
+    // sha256(salt || code) of the recovery codes not used yet
+    recovery_hashes: Vec<String>,
+    // last TOTP step accepted
+    #[serde(default)]
+    last_step: u64,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum Factor {
+    Totp,
+    RecoveryCode { remaining: usize },
+}
+
+impl TwoFactor {
+    /// Generates a new seed and recovery codes, the codes are only returned here.
+    pub fn enroll(master_key_hex: &str, account: &str) -> Result<(TwoFactor, OtpSecret, Vec<String>), String> {
+        let mut seed = vec![0u8; SEED_BYTES];
+        thread_rng().fill_bytes(&mut seed);
+        let otp = OtpSecret {
+            issuer: Some(ISSUER.to_string()),
+            account: Some(account.to_string()),
+            ..OtpSecret::totp(seed)
+        };
+
+        let recovery_salt: String = thread_rng().sample_iter(&Alphanumeric).take(16).map(char::from).collect();
+        let codes: Vec<String> = (0..RECOVERY_CODES).map(|_| recovery_code()).collect();
+        let two_factor = TwoFactor {
+            sealed_seed: cipher::seal(master_key_hex, &otp.to_uri())?,
+            recovery_hashes: codes.iter().map(|code| hash_code(&recovery_salt, code)).collect(),
+            recovery_salt,
+            last_step: 0,
+        };
+        Ok((two_factor, otp, codes))
+    }
+
+    pub fn seed(&self, master_key_hex: &str) -> Result<OtpSecret, String> {
+        OtpSecret::parse(&cipher::open(master_key_hex, &self.sealed_seed)?)
+    }
+
+    /// Seals the seed again under a new vault key.
+    pub fn reseal(&mut self, old_master_key_hex: &str, new_master_key_hex: &str) -> Result<(), String> {
+        let uri = cipher::open(old_master_key_hex, &self.sealed_seed)?;
+        self.sealed_seed = cipher::seal(new_master_key_hex, &uri)?;
+        Ok(())
+    }
+
+    pub fn recovery_codes_left(&self) -> usize {
+        self.recovery_hashes.len()
+    }
+
+    /// Replaces the recovery codes, the old ones stop working. The new codes are only returned here.
+    pub fn new_recovery_codes(&mut self) -> Vec<String> {
+        let codes: Vec<String> = (0..RECOVERY_CODES).map(|_| recovery_code()).collect();
+        self.recovery_hashes = codes.iter().map(|code| hash_code(&self.recovery_salt, code)).collect();
+        codes
+    }
+
+    /// Whether `code` is a recovery code not used yet, without consuming it.
+    pub fn has_recovery_code(&self, code: &str) -> bool {
+        self.recovery_hashes.contains(&hash_code(&self.recovery_salt, code))
+    }
+
+    /// Checks a TOTP or recovery code, consuming it on success.
+    pub fn verify(&mut self, master_key_hex: &str, input: &str, unix_time: u64) -> Result<Factor, String> {
+        let input = input.trim();
+        if input.len() <= 8 && input.chars().all(|c| c.is_ascii_digit()) {
+            let seed = self.seed(master_key_hex)?;
+            let period = match seed.kind {
+                OtpKind::Totp { period } => period,
+                OtpKind::Hotp { .. } => return Err("The 2FA seed is not a TOTP seed".to_string()),
+            };
+            let current = unix_time / period;
+            let first = current.saturating_sub(DRIFT_STEPS).max(self.last_step + 1);
+            return match (first..=current + DRIFT_STEPS).find(|&step| seed.hotp(step) == input) {
+                Some(step) => {
+                    self.last_step = step;
+                    Ok(Factor::Totp)
+                }
+                None => Err("Invalid or already used 2FA code".to_string()),
+            };
+        }
+
+        let hash = hash_code(&self.recovery_salt, input);
+        match self.recovery_hashes.iter().position(|stored| *stored == hash) {
+            Some(index) => {
+                self.recovery_hashes.remove(index);
+                Ok(Factor::RecoveryCode { remaining: self.recovery_hashes.len() })
+            }
+            None => Err("Invalid 2FA or recovery code".to_string()),
+        }
+    }
+}
+
+// two groups of five, about 50 bits
+fn recovery_code() -> String {
+    let mut rng = thread_rng();
+    let mut pick = || RECOVERY_ALPHABET[rng.gen_range(0..RECOVERY_ALPHABET.len())] as char;
+    let first: String = (0..5).map(|_| pick()).collect();
+    let second: String = (0..5).map(|_| pick()).collect();
+    format!("{}-{}", first, second)
+}
+
+// case, spaces and the dash do not matter when typing a code back
+fn hash_code(salt: &str, code: &str) -> String {
+    let normalized: String = code.chars().filter(|c| c.is_ascii_alphanumeric()).collect::<String>().to_lowercase();
+    let mut hasher = Sha256::new();
+    hasher.update(salt.as_bytes());
+    hasher.update(normalized.as_bytes());
+    hex::encode(hasher.finalize())
+}
+
+/// Renders `data` as a QR code made of half block characters.
+pub fn qr_code(data: &str) -> Result<String, String> {
+    let code = QrCode::new(data.as_bytes()).map_err(|e| e.to_string())?;
+    // light modules drawn dark and the other way around, which reads well on dark terminals
+    Ok(code
+        .render::<unicode::Dense1x2>()
+        .dark_color(unicode::Dense1x2::Light)
+        .light_color(unicode::Dense1x2::Dark)
+        .build())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const KEY: &str = "000102030405060708090a0b0c0d0e0f101112131415161718191a1b1c1d1e1f";
+
+    #[test]
+    fn test_totp_codes_are_single_use() {
+        let (mut two_factor, seed, _) = TwoFactor::enroll(KEY, "alice").unwrap();
+        let now = 1_700_000_000;
+        let (code, _) = seed.totp_at(now).unwrap();
+
+        assert_eq!(two_factor.verify(KEY, &code, now), Ok(Factor::Totp));
+        assert!(two_factor.verify(KEY, &code, now).is_err());
+
+        // the next step is still accepted, one step of drift
+        let (next, _) = seed.totp_at(now + 30).unwrap();
+        assert_eq!(two_factor.verify(KEY, &next, now), Ok(Factor::Totp));
+        let (late, _) = seed.totp_at(now + 120).unwrap();
+        assert!(two_factor.verify(KEY, &late, now).is_err());
+    }
+
+    #[test]
+    fn test_recovery_codes() {
+        let (mut two_factor, _, codes) = TwoFactor::enroll(KEY, "alice").unwrap();
+        assert_eq!(codes.len(), RECOVERY_CODES);
+        assert_eq!(two_factor.recovery_codes_left(), RECOVERY_CODES);
+
+        let typed = codes[3].to_uppercase().replace('-', " ");
+        assert_eq!(two_factor.verify(KEY, &typed, 0), Ok(Factor::RecoveryCode { remaining: RECOVERY_CODES - 1 }));
+        assert!(two_factor.verify(KEY, &codes[3], 0).is_err());
+        assert!(two_factor.verify(KEY, "aaaaa-aaaaa", 0).is_err());
+
+        let fresh = two_factor.new_recovery_codes();
+        assert_eq!(two_factor.recovery_codes_left(), RECOVERY_CODES);
+        assert!(two_factor.has_recovery_code(&fresh[0]));
+        assert!(!two_factor.has_recovery_code(&codes[0]));
+        assert_eq!(two_factor.recovery_codes_left(), RECOVERY_CODES);
+    }
+
+    #[test]
+    fn test_seed_needs_the_vault_key() {
+        let (two_factor, seed, _) = TwoFactor::enroll(KEY, "alice").unwrap();
+        assert_eq!(two_factor.seed(KEY).unwrap(), seed);
+        assert!(two_factor.seed(&"11".repeat(32)).is_err());
+        assert!(qr_code(&seed.to_uri()).unwrap().lines().count() > 10);
+    }
+}
@@ -0,0 +1,41 @@
+use crate::cli::commands::{AddCmd, UpdateCmd};
+use crate::context::Context;
+use crate::hibp::PwnedPasswords;
+use crate::validator::core::{Validator, ValidationResult};
+
+/// Rejects passwords found in the local HIBP list, only when `hibp_file` is set.
+pub struct BreachValidator {}
+
+impl BreachValidator {
+    fn check(context: &Context, password: &str) -> ValidationResult {
+        let hibp_file = context.kgc.borrow().get_settings().hibp_file();
+        let mut hibp = match PwnedPasswords::from_setting(hibp_file) {
+            Ok(Some(hibp)) => hibp,
+            Ok(None) => return ValidationResult::Success,
+            Err(e) => return ValidationResult::Warning(format!("Breach check skipped, could not open the HIBP file: {}", e)),
+        };
+
+        match hibp.count(password) {
+            Ok(0) => ValidationResult::Success,
+            Ok(count) => ValidationResult::Failure(format!(
+                "This password appeared {} times in known data breaches, choose another one",
+                count
+            )),
+            Err(e) => ValidationResult::Warning(format!("Breach check skipped, could not read the HIBP file: {}", e)),
+        }
+    }
+}
+
+impl Validator<AddCmd> for BreachValidator {
+    fn validate(&self, context: &Context, cmd: &AddCmd) -> ValidationResult {
+        log::debug!("Running BreachValidator");
+        BreachValidator::check(context, &cmd.new_password())
+    }
+}
+
+impl Validator<UpdateCmd> for BreachValidator {
+    fn validate(&self, context: &Context, cmd: &UpdateCmd) -> ValidationResult {
+        log::debug!("Running BreachValidator for UpdateCmd");
+        BreachValidator::check(context, &cmd.new_password())
+    }
+}
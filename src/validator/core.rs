@@ -1,4 +1,5 @@
 use crate::context::Context;
+use std::path::Path;
 
 #[derive(Hash, Eq, PartialEq, Clone, Debug)]
 pub enum ValidationType {
@@ -8,6 +9,9 @@ pub enum ValidationType {
     EntryExistsCheck,
     DuplicateEntryCheck,
     PasswordRequirementCheck,
+    PasswordStrengthCheck,
+    BreachCheck,
+    ReauthCheck,
 }
 
 pub enum CommandType {
@@ -18,6 +22,23 @@ pub enum CommandType {
     DESTROY_CMD,
 }
 
+/// Whether a command exposes or can destroy enough to ask for the master password again.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Sensitivity {
+    Normal,
+    Sensitive,
+}
+
+/// Declared by the commands checked by `ReauthValidator`.
+pub trait Sensitive {
+    fn sensitivity(&self) -> Sensitivity;
+
+    /// The key file passed to the command, asked for when the vault needs one and this is None.
+    fn keyfile(&self) -> Option<&Path> {
+        None
+    }
+}
+
 #[derive(Debug)]
 pub enum ValidationResult {
     Success,
@@ -1,7 +1,7 @@
 
 use crate::validator::core::{Validator, ValidationResult};
 use crate::context::Context;
-use crate::cli::commands::{AddCmd, GetCmd, UpdateCmd};
+use crate::cli::commands::{AddCmd, GetCmd, OtpCmd, RotateCmd, UpdateCmd};
 
 pub struct EntryExistsValidator {}
 
@@ -55,3 +55,31 @@ impl Validator<UpdateCmd> for EntryExistsValidator {
         }
     }
 }
+
+// entry has to exist
+impl Validator<RotateCmd> for EntryExistsValidator {
+    fn validate(&self, context: &Context, cmd: &RotateCmd) -> ValidationResult {
+        log::debug!("Running EntryExistsValidator for RotateCmd");
+        match context.db.entry_exist(cmd.name.clone()) {
+            Ok(true) => ValidationResult::Success,
+            Ok(false) => ValidationResult::Failure("No entry found with similar name ⛔".to_string()),
+            Err(_) => ValidationResult::Failure("Error during DB check ⛔".to_string()),
+        }
+    }
+}
+
+// every action but import works on one existing entry
+impl Validator<OtpCmd> for EntryExistsValidator {
+    fn validate(&self, context: &Context, cmd: &OtpCmd) -> ValidationResult {
+        log::debug!("Running EntryExistsValidator for OtpCmd");
+        let name = match cmd.entry_name() {
+            Some(name) => name,
+            None => return ValidationResult::Success,
+        };
+        match context.db.entry_exist(name.to_string()) {
+            Ok(true) => ValidationResult::Success,
+            Ok(false) => ValidationResult::Failure("No entry found with similar name ⛔".to_string()),
+            Err(_) => ValidationResult::Failure("Error during DB check ⛔".to_string()),
+        }
+    }
+}
@@ -1,4 +1,4 @@
-use crate::cli::commands::{AddCmd, DestroyCmd, GetCmd, InitCmd, LogInCmd, UpdateCmd};
+use crate::cli::commands::{AddCmd, AuditCmd, BackupCmd, ChangeMasterCmd, ConfigCmd, DestroyCmd, EmergencyKitCmd, GetCmd, InitCmd, KeyfileCmd, ListCmd, LogInCmd, LogoutCmd, OtpCmd, PolicyCmd, RecoveryCmd, RotateCmd, TwoFactorCmd, UpdateCmd, VaultCmd};
 use crate::validator::core::{Validator, ValidationResult};
 use crate::context::Context;
 
@@ -68,4 +68,157 @@ impl Validator<UpdateCmd> for MasterKeyValidator {
             ValidationResult::Failure("Master key not provided ⛔".to_string())
         }
     }
-}
\ No newline at end of file
+}
+
+impl Validator<BackupCmd> for MasterKeyValidator {
+    fn validate(&self, context: &Context, _cmd: &BackupCmd) -> ValidationResult {
+        log::debug!("Running MasterKeyValidator for BackupCmd");
+        if context.kgc.borrow().is_master_key_provided() {
+            ValidationResult::Success
+        } else {
+            ValidationResult::Failure("Master key not provided ⛔".to_string())
+        }
+    }
+}
+impl Validator<ConfigCmd> for MasterKeyValidator {
+    fn validate(&self, context: &Context, _cmd: &ConfigCmd) -> ValidationResult {
+        log::debug!("Running MasterKeyValidator for ConfigCmd");
+        if context.kgc.borrow().is_master_key_provided() {
+            ValidationResult::Success
+        } else {
+            ValidationResult::Failure("Master key not provided ⛔".to_string())
+        }
+    }
+}
+
+impl Validator<PolicyCmd> for MasterKeyValidator {
+    fn validate(&self, context: &Context, _cmd: &PolicyCmd) -> ValidationResult {
+        log::debug!("Running MasterKeyValidator for PolicyCmd");
+        if context.kgc.borrow().is_master_key_provided() {
+            ValidationResult::Success
+        } else {
+            ValidationResult::Failure("Master key not provided ⛔".to_string())
+        }
+    }
+}
+
+impl Validator<AuditCmd> for MasterKeyValidator {
+    fn validate(&self, context: &Context, _cmd: &AuditCmd) -> ValidationResult {
+        log::debug!("Running MasterKeyValidator for AuditCmd");
+        if context.kgc.borrow().is_master_key_provided() {
+            ValidationResult::Success
+        } else {
+            ValidationResult::Failure("Master key not provided ⛔".to_string())
+        }
+    }
+}
+
+impl Validator<ListCmd> for MasterKeyValidator {
+    fn validate(&self, context: &Context, _cmd: &ListCmd) -> ValidationResult {
+        log::debug!("Running MasterKeyValidator for ListCmd");
+        if context.kgc.borrow().is_master_key_provided() {
+            ValidationResult::Success
+        } else {
+            ValidationResult::Failure("Master key not provided ⛔".to_string())
+        }
+    }
+}
+
+impl Validator<RotateCmd> for MasterKeyValidator {
+    fn validate(&self, context: &Context, _cmd: &RotateCmd) -> ValidationResult {
+        log::debug!("Running MasterKeyValidator for RotateCmd");
+        if context.kgc.borrow().is_master_key_provided() {
+            ValidationResult::Success
+        } else {
+            ValidationResult::Failure("Master key not provided ⛔".to_string())
+        }
+    }
+}
+
+impl Validator<OtpCmd> for MasterKeyValidator {
+    fn validate(&self, context: &Context, _cmd: &OtpCmd) -> ValidationResult {
+        log::debug!("Running MasterKeyValidator for OtpCmd");
+        if context.kgc.borrow().is_master_key_provided() {
+            ValidationResult::Success
+        } else {
+            ValidationResult::Failure("Master key not provided ⛔".to_string())
+        }
+    }
+}
+
+impl Validator<TwoFactorCmd> for MasterKeyValidator {
+    fn validate(&self, context: &Context, _cmd: &TwoFactorCmd) -> ValidationResult {
+        log::debug!("Running MasterKeyValidator for TwoFactorCmd");
+        if context.kgc.borrow().is_master_key_provided() {
+            ValidationResult::Success
+        } else {
+            ValidationResult::Failure("Master key not provided ⛔".to_string())
+        }
+    }
+}
+
+impl Validator<KeyfileCmd> for MasterKeyValidator {
+    fn validate(&self, context: &Context, _cmd: &KeyfileCmd) -> ValidationResult {
+        log::debug!("Running MasterKeyValidator for KeyfileCmd");
+        if context.kgc.borrow().is_master_key_provided() {
+            ValidationResult::Success
+        } else {
+            ValidationResult::Failure("Master key not provided ⛔".to_string())
+        }
+    }
+}
+
+impl Validator<ChangeMasterCmd> for MasterKeyValidator {
+    fn validate(&self, context: &Context, _cmd: &ChangeMasterCmd) -> ValidationResult {
+        log::debug!("Running MasterKeyValidator for ChangeMasterCmd");
+        if context.kgc.borrow().is_master_key_provided() {
+            ValidationResult::Success
+        } else {
+            ValidationResult::Failure("Master key not provided ⛔".to_string())
+        }
+    }
+}
+
+impl Validator<RecoveryCmd> for MasterKeyValidator {
+    fn validate(&self, context: &Context, _cmd: &RecoveryCmd) -> ValidationResult {
+        log::debug!("Running MasterKeyValidator for RecoveryCmd");
+        if context.kgc.borrow().is_master_key_provided() {
+            ValidationResult::Success
+        } else {
+            ValidationResult::Failure("Master key not provided ⛔".to_string())
+        }
+    }
+}
+
+impl Validator<EmergencyKitCmd> for MasterKeyValidator {
+    fn validate(&self, context: &Context, _cmd: &EmergencyKitCmd) -> ValidationResult {
+        log::debug!("Running MasterKeyValidator for EmergencyKitCmd");
+        if context.kgc.borrow().is_master_key_provided() {
+            ValidationResult::Success
+        } else {
+            ValidationResult::Failure("Master key not provided ⛔".to_string())
+        }
+    }
+}
+
+impl Validator<LogoutCmd> for MasterKeyValidator {
+    fn validate(&self, context: &Context, _cmd: &LogoutCmd) -> ValidationResult {
+        log::debug!("Running MasterKeyValidator for LogoutCmd");
+        if context.kgc.borrow().is_master_key_provided() {
+            ValidationResult::Success
+        } else {
+            ValidationResult::Failure("Master key not provided ⛔".to_string())
+        }
+    }
+}
+
+impl Validator<VaultCmd> for MasterKeyValidator {
+    fn validate(&self, context: &Context, _cmd: &VaultCmd) -> ValidationResult {
+        log::debug!("Running MasterKeyValidator for VaultCmd");
+        if context.kgc.borrow().is_master_key_provided() {
+            ValidationResult::Success
+        } else {
+            ValidationResult::Failure("Master key not provided ⛔".to_string())
+        }
+    }
+}
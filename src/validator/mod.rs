@@ -3,7 +3,10 @@ pub mod core;
 pub mod master_key;
 pub mod session;
 pub mod rate_limit;
+pub mod reauth;
 pub mod entry_exists;
 pub mod duplicate;
 pub mod pwd_req;
+pub mod strength;
+pub mod breach;
 pub mod registry;
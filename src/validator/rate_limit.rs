@@ -2,12 +2,22 @@
 
 use crate::validator::core::{Validator, ValidationResult};
 use crate::context::Context;
+use chrono::Utc;
 
 pub struct RateLimitValidator {}
 
 impl<T> Validator<T> for RateLimitValidator {
-    fn validate(&self, _context: &Context, _cmd: &T) -> ValidationResult {
+    fn validate(&self, context: &Context, _cmd: &T) -> ValidationResult {
         log::debug!("Running RateLimitValidator");
-        ValidationResult::Success
+        match context.kgc.borrow().get_locked_until() {
+            Some(until) => {
+                let minutes = (until - Utc::now()).num_minutes() + 1;
+                ValidationResult::Failure(format!(
+                    "Too many failed master password attempts, try again in {} minute(s) ⛔",
+                    minutes
+                ))
+            }
+            None => ValidationResult::Success,
+        }
     }
 }
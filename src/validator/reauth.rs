@@ -0,0 +1,61 @@
+// src/validator/reauth.rs
+
+use crate::cli::commands::LogInCmd;
+use crate::validator::core::{Sensitive, Sensitivity, Validator, ValidationResult};
+use crate::context::Context;
+use log::info;
+use std::io::Write;
+use std::path::PathBuf;
+
+/// "sudo mode": a sensitive command needs the master password entered again,
+/// unless that happened less than `sudo_timeout` seconds ago in this session.
+pub struct ReauthValidator {}
+
+impl<T: Sensitive> Validator<T> for ReauthValidator {
+    fn validate(&self, context: &Context, cmd: &T) -> ValidationResult {
+        log::debug!("Running ReauthValidator");
+        if cmd.sensitivity() == Sensitivity::Normal {
+            return ValidationResult::Success;
+        }
+
+        let (required, timeout, has_keyfile) = {
+            let kgc = context.kgc.borrow();
+            let settings = kgc.get_settings();
+            (
+                settings.require_password_for_sensitive_actions().unwrap_or(true),
+                settings.sudo_timeout().unwrap_or(300),
+                kgc.has_keyfile(),
+            )
+        };
+        if !required || context.ss.reauthenticated_within(timeout) {
+            return ValidationResult::Success;
+        }
+
+        if context.kgc.borrow().get_locked_until().is_some() {
+            return ValidationResult::Failure("Too many failed master password attempts, sensitive actions are locked ⛔".to_string());
+        }
+        info!("This is a sensitive action, confirm it with the master password");
+        let keyfile = match (has_keyfile, cmd.keyfile()) {
+            (true, None) => match read_keyfile_path() {
+                Some(path) => Some(path),
+                None => return ValidationResult::Failure("The key file is needed to confirm this action ⛔".to_string()),
+            },
+            (_, keyfile) => keyfile.map(PathBuf::from),
+        };
+        match LogInCmd::verify_master_password(context, keyfile.as_deref()) {
+            Some(_) => ValidationResult::Success,
+            None => ValidationResult::Failure("Sensitive action not confirmed ⛔".to_string()),
+        }
+    }
+}
+
+// None when stdin is closed, unreadable or gives an empty line
+fn read_keyfile_path() -> Option<PathBuf> {
+    print!("Path of the key file ===> ");
+    let _ = std::io::stdout().flush();
+    let mut path = String::new();
+    match std::io::stdin().read_line(&mut path) {
+        Ok(read) if read > 0 && !path.trim().is_empty() => Some(PathBuf::from(path.trim())),
+        _ => None,
+    }
+}
@@ -2,15 +2,18 @@
 
 use std::collections::HashMap;
 use crate::validator::core::{ValidationType, Validator};
-use crate::cli::commands::{AddCmd, DestroyCmd, GetCmd, InitCmd, LogInCmd, UpdateCmd};
+use crate::cli::commands::{AddCmd, AuditCmd, BackupCmd, ChangeMasterCmd, ConfigCmd, DestroyCmd, EmergencyKitCmd, GetCmd, InitCmd, KeyfileCmd, ListCmd, LogInCmd, LogoutCmd, OtpCmd, PolicyCmd, RecoveryCmd, RotateCmd, TwoFactorCmd, UpdateCmd, VaultCmd};
 
 use crate::validator::master_key::MasterKeyValidator;
 use crate::validator::session::SessionValidator;
 use crate::validator::rate_limit::RateLimitValidator;
+use crate::validator::reauth::ReauthValidator;
 use crate::validator::entry_exists::EntryExistsValidator;
 use crate::validator::duplicate::DuplicateEntryValidator;
 
 use super::pwd_req::PasswordRequirementValidator;
+use super::strength::PasswordStrengthValidator;
+use super::breach::BreachValidator;
 
 pub struct ValidationRegistry<T> {
     pub validators: HashMap<ValidationType, Box<dyn Validator<T>>>,
@@ -29,6 +32,7 @@ impl ValidationRegistry<LogInCmd> {
         let mut validators: HashMap<ValidationType, Box<dyn Validator<LogInCmd>>> = HashMap::new();
         validators.insert(ValidationType::MasterKeyCheck, Box::new(MasterKeyValidator {}));
         validators.insert(ValidationType::SessionCheck, Box::new(SessionValidator {}));
+        validators.insert(ValidationType::RateLimitCheck, Box::new(RateLimitValidator {}));
         Self { validators }
     }
 }
@@ -38,6 +42,7 @@ impl ValidationRegistry<DestroyCmd> {
         let mut validators: HashMap<ValidationType, Box<dyn Validator<DestroyCmd>>> = HashMap::new();
         validators.insert(ValidationType::MasterKeyCheck, Box::new(MasterKeyValidator {}));
         validators.insert(ValidationType::SessionCheck, Box::new(SessionValidator {}));
+        validators.insert(ValidationType::RateLimitCheck, Box::new(RateLimitValidator {}));
         Self { validators }
     }
 }
@@ -50,6 +55,7 @@ impl ValidationRegistry<GetCmd> {
         validators.insert(ValidationType::RateLimitCheck, Box::new(RateLimitValidator {}));
         validators.insert(ValidationType::EntryExistsCheck, Box::new(EntryExistsValidator {}));
         validators.insert(ValidationType::DuplicateEntryCheck, Box::new(DuplicateEntryValidator {}));
+        validators.insert(ValidationType::ReauthCheck, Box::new(ReauthValidator {}));
         Self { validators }
     }
 }
@@ -63,6 +69,9 @@ impl ValidationRegistry<AddCmd> {
         validators.insert(ValidationType::EntryExistsCheck, Box::new(EntryExistsValidator {}));
         validators.insert(ValidationType::DuplicateEntryCheck, Box::new(DuplicateEntryValidator {}));
         validators.insert(ValidationType::PasswordRequirementCheck, Box::new(PasswordRequirementValidator {}));
+        validators.insert(ValidationType::PasswordStrengthCheck, Box::new(PasswordStrengthValidator {}));
+        validators.insert(ValidationType::BreachCheck, Box::new(BreachValidator {}));
+        validators.insert(ValidationType::ReauthCheck, Box::new(ReauthValidator {}));
         Self { validators }
     }
 }
@@ -76,7 +85,149 @@ impl ValidationRegistry<UpdateCmd> {
         validators.insert(ValidationType::MasterKeyCheck, Box::new(MasterKeyValidator {}));
         validators.insert(ValidationType::EntryExistsCheck, Box::new(EntryExistsValidator {}));
         validators.insert(ValidationType::PasswordRequirementCheck, Box::new(PasswordRequirementValidator {}));
+        validators.insert(ValidationType::PasswordStrengthCheck, Box::new(PasswordStrengthValidator {}));
+        validators.insert(ValidationType::BreachCheck, Box::new(BreachValidator {}));
+        validators.insert(ValidationType::ReauthCheck, Box::new(ReauthValidator {}));
+        Self { validators }
+    }
+}
+
+impl ValidationRegistry<BackupCmd> {
+    pub fn new() -> Self {
+        let mut validators: HashMap<ValidationType, Box<dyn Validator<BackupCmd>>> = HashMap::new();
+        validators.insert(ValidationType::MasterKeyCheck, Box::new(MasterKeyValidator {}));
+        validators.insert(ValidationType::SessionCheck, Box::new(SessionValidator {}));
+        validators.insert(ValidationType::ReauthCheck, Box::new(ReauthValidator {}));
+        Self { validators }
+    }
+}
+
+impl ValidationRegistry<ConfigCmd> {
+    pub fn new() -> Self {
+        let mut validators: HashMap<ValidationType, Box<dyn Validator<ConfigCmd>>> = HashMap::new();
+        validators.insert(ValidationType::MasterKeyCheck, Box::new(MasterKeyValidator {}));
+        validators.insert(ValidationType::SessionCheck, Box::new(SessionValidator {}));
+        validators.insert(ValidationType::ReauthCheck, Box::new(ReauthValidator {}));
+        Self { validators }
+    }
+}
+
+impl ValidationRegistry<PolicyCmd> {
+    pub fn new() -> Self {
+        let mut validators: HashMap<ValidationType, Box<dyn Validator<PolicyCmd>>> = HashMap::new();
+        validators.insert(ValidationType::MasterKeyCheck, Box::new(MasterKeyValidator {}));
+        validators.insert(ValidationType::SessionCheck, Box::new(SessionValidator {}));
+        validators.insert(ValidationType::ReauthCheck, Box::new(ReauthValidator {}));
+        Self { validators }
+    }
+}
+
+impl ValidationRegistry<AuditCmd> {
+    pub fn new() -> Self {
+        let mut validators: HashMap<ValidationType, Box<dyn Validator<AuditCmd>>> = HashMap::new();
+        validators.insert(ValidationType::MasterKeyCheck, Box::new(MasterKeyValidator {}));
+        validators.insert(ValidationType::SessionCheck, Box::new(SessionValidator {}));
+        Self { validators }
+    }
+}
+
+impl ValidationRegistry<ListCmd> {
+    pub fn new() -> Self {
+        let mut validators: HashMap<ValidationType, Box<dyn Validator<ListCmd>>> = HashMap::new();
+        validators.insert(ValidationType::MasterKeyCheck, Box::new(MasterKeyValidator {}));
+        validators.insert(ValidationType::SessionCheck, Box::new(SessionValidator {}));
+        Self { validators }
+    }
+}
+
+impl ValidationRegistry<RotateCmd> {
+    pub fn new() -> Self {
+        let mut validators: HashMap<ValidationType, Box<dyn Validator<RotateCmd>>> = HashMap::new();
+        validators.insert(ValidationType::MasterKeyCheck, Box::new(MasterKeyValidator {}));
+        validators.insert(ValidationType::SessionCheck, Box::new(SessionValidator {}));
+        validators.insert(ValidationType::EntryExistsCheck, Box::new(EntryExistsValidator {}));
+        validators.insert(ValidationType::ReauthCheck, Box::new(ReauthValidator {}));
+        Self { validators }
+    }
+}
+
+impl ValidationRegistry<OtpCmd> {
+    pub fn new() -> Self {
+        let mut validators: HashMap<ValidationType, Box<dyn Validator<OtpCmd>>> = HashMap::new();
+        validators.insert(ValidationType::MasterKeyCheck, Box::new(MasterKeyValidator {}));
+        validators.insert(ValidationType::SessionCheck, Box::new(SessionValidator {}));
+        validators.insert(ValidationType::EntryExistsCheck, Box::new(EntryExistsValidator {}));
+        validators.insert(ValidationType::ReauthCheck, Box::new(ReauthValidator {}));
+        Self { validators }
+    }
+}
+
+impl ValidationRegistry<TwoFactorCmd> {
+    pub fn new() -> Self {
+        let mut validators: HashMap<ValidationType, Box<dyn Validator<TwoFactorCmd>>> = HashMap::new();
+        validators.insert(ValidationType::MasterKeyCheck, Box::new(MasterKeyValidator {}));
+        validators.insert(ValidationType::SessionCheck, Box::new(SessionValidator {}));
+        validators.insert(ValidationType::RateLimitCheck, Box::new(RateLimitValidator {}));
+        Self { validators }
+    }
+}
+
+impl ValidationRegistry<KeyfileCmd> {
+    pub fn new() -> Self {
+        let mut validators: HashMap<ValidationType, Box<dyn Validator<KeyfileCmd>>> = HashMap::new();
+        validators.insert(ValidationType::MasterKeyCheck, Box::new(MasterKeyValidator {}));
+        validators.insert(ValidationType::RateLimitCheck, Box::new(RateLimitValidator {}));
+        validators.insert(ValidationType::ReauthCheck, Box::new(ReauthValidator {}));
+        Self { validators }
+    }
+}
+
+impl ValidationRegistry<ChangeMasterCmd> {
+    pub fn new() -> Self {
+        let mut validators: HashMap<ValidationType, Box<dyn Validator<ChangeMasterCmd>>> = HashMap::new();
+        validators.insert(ValidationType::MasterKeyCheck, Box::new(MasterKeyValidator {}));
+        validators.insert(ValidationType::RateLimitCheck, Box::new(RateLimitValidator {}));
+        Self { validators }
+    }
+}
+
+impl ValidationRegistry<RecoveryCmd> {
+    pub fn new() -> Self {
+        let mut validators: HashMap<ValidationType, Box<dyn Validator<RecoveryCmd>>> = HashMap::new();
+        validators.insert(ValidationType::MasterKeyCheck, Box::new(MasterKeyValidator {}));
+        validators.insert(ValidationType::SessionCheck, Box::new(SessionValidator {}));
+        validators.insert(ValidationType::RateLimitCheck, Box::new(RateLimitValidator {}));
+        validators.insert(ValidationType::ReauthCheck, Box::new(ReauthValidator {}));
+        Self { validators }
+    }
+}
+
+impl ValidationRegistry<EmergencyKitCmd> {
+    pub fn new() -> Self {
+        let mut validators: HashMap<ValidationType, Box<dyn Validator<EmergencyKitCmd>>> = HashMap::new();
+        validators.insert(ValidationType::MasterKeyCheck, Box::new(MasterKeyValidator {}));
+        validators.insert(ValidationType::SessionCheck, Box::new(SessionValidator {}));
+        validators.insert(ValidationType::RateLimitCheck, Box::new(RateLimitValidator {}));
+        validators.insert(ValidationType::ReauthCheck, Box::new(ReauthValidator {}));
         Self { validators }
     }
 }
 
+impl ValidationRegistry<LogoutCmd> {
+    pub fn new() -> Self {
+        let mut validators: HashMap<ValidationType, Box<dyn Validator<LogoutCmd>>> = HashMap::new();
+        validators.insert(ValidationType::MasterKeyCheck, Box::new(MasterKeyValidator {}));
+        Self { validators }
+    }
+}
+
+impl ValidationRegistry<VaultCmd> {
+    pub fn new() -> Self {
+        let mut validators: HashMap<ValidationType, Box<dyn Validator<VaultCmd>>> = HashMap::new();
+        validators.insert(ValidationType::MasterKeyCheck, Box::new(MasterKeyValidator {}));
+        validators.insert(ValidationType::SessionCheck, Box::new(SessionValidator {}));
+        validators.insert(ValidationType::RateLimitCheck, Box::new(RateLimitValidator {}));
+        validators.insert(ValidationType::ReauthCheck, Box::new(ReauthValidator {}));
+        Self { validators }
+    }
+}
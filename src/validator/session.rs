@@ -2,40 +2,39 @@
 
 use crate::validator::core::{Validator, ValidationResult};
 use crate::context::Context;
-use crate::cli::commands::{AddCmd, DestroyCmd, GetCmd, LogInCmd, UpdateCmd};
+use crate::cli::commands::{AddCmd, AuditCmd, BackupCmd, ConfigCmd, DestroyCmd, EmergencyKitCmd, GetCmd, ListCmd, LogInCmd, OtpCmd, PolicyCmd, RecoveryCmd, RotateCmd, TwoFactorCmd, UpdateCmd, VaultCmd};
 
 pub struct SessionValidator {}
 
+// expired or locked, or bound to another terminal, shell or login session
+fn check_session(context: &Context) -> ValidationResult {
+    if context.ss.check_if_expired() {
+        return ValidationResult::Failure("Session expired ⛔".to_string());
+    }
+    match context.ss.check_binding() {
+        Ok(()) => ValidationResult::Success,
+        Err(msg) => ValidationResult::Failure(msg),
+    }
+}
+
 impl Validator<GetCmd> for SessionValidator {
     fn validate(&self, context: &Context, _cmd: &GetCmd) -> ValidationResult {
         log::debug!("Running SessionValidator");
-        if !context.ss.check_if_expired() {
-            ValidationResult::Success
-        } else {
-            ValidationResult::Failure("Session expired ⛔".to_string())
-        }
+        check_session(context)
     }
 }
 
 impl Validator<AddCmd> for SessionValidator {
     fn validate(&self, context: &Context, _cmd: &AddCmd) -> ValidationResult {
         log::debug!("Running SessionValidator");
-        if !context.ss.check_if_expired() {
-            ValidationResult::Success
-        } else {
-            ValidationResult::Failure("Session expired ⛔".to_string())
-        }
+        check_session(context)
     }
 }
 
 impl Validator<UpdateCmd> for SessionValidator {
     fn validate(&self, context: &Context, _cmd: &UpdateCmd) -> ValidationResult {
         log::debug!("Running SessionValidator");
-        if !context.ss.check_if_expired() {
-            ValidationResult::Success
-        } else {
-            ValidationResult::Failure("Session expired ⛔".to_string())
-        }
+        check_session(context)
     }
 }
 
@@ -44,7 +43,8 @@ impl Validator<UpdateCmd> for SessionValidator {
 impl Validator<LogInCmd> for SessionValidator {
     fn validate(&self, context: &Context, _cmd: &LogInCmd) -> ValidationResult {
         log::debug!("Running SessionValidator");
-        if context.ss.check_if_expired() {
+        // a session bound elsewhere is replaced by logging in here
+        if matches!(check_session(context), ValidationResult::Failure(_)) {
             ValidationResult::Success // means that session expired and it makes sense to allow login command
         } else {
             ValidationResult::Failure("Non expired session , already loggedIn ✅".to_string())
@@ -56,10 +56,82 @@ impl Validator<LogInCmd> for SessionValidator {
 impl Validator<DestroyCmd> for SessionValidator {
     fn validate(&self, context: &Context, _cmd: &DestroyCmd) -> ValidationResult {
         log::debug!("Running SessionValidator");
-        if !context.ss.check_if_expired() {
-            ValidationResult::Success // means that session expired and it makes sense to allow login command
-        } else {
-            ValidationResult::Failure("Session expired ⛔".to_string())
-        }
+        check_session(context)
+    }
+}
+
+impl Validator<BackupCmd> for SessionValidator {
+    fn validate(&self, context: &Context, _cmd: &BackupCmd) -> ValidationResult {
+        log::debug!("Running SessionValidator");
+        check_session(context)
+    }
+}
+impl Validator<ConfigCmd> for SessionValidator {
+    fn validate(&self, context: &Context, _cmd: &ConfigCmd) -> ValidationResult {
+        log::debug!("Running SessionValidator");
+        check_session(context)
+    }
+}
+
+impl Validator<PolicyCmd> for SessionValidator {
+    fn validate(&self, context: &Context, _cmd: &PolicyCmd) -> ValidationResult {
+        log::debug!("Running SessionValidator");
+        check_session(context)
+    }
+}
+
+impl Validator<AuditCmd> for SessionValidator {
+    fn validate(&self, context: &Context, _cmd: &AuditCmd) -> ValidationResult {
+        log::debug!("Running SessionValidator");
+        check_session(context)
+    }
+}
+
+impl Validator<ListCmd> for SessionValidator {
+    fn validate(&self, context: &Context, _cmd: &ListCmd) -> ValidationResult {
+        log::debug!("Running SessionValidator");
+        check_session(context)
+    }
+}
+
+impl Validator<RotateCmd> for SessionValidator {
+    fn validate(&self, context: &Context, _cmd: &RotateCmd) -> ValidationResult {
+        log::debug!("Running SessionValidator");
+        check_session(context)
+    }
+}
+
+impl Validator<OtpCmd> for SessionValidator {
+    fn validate(&self, context: &Context, _cmd: &OtpCmd) -> ValidationResult {
+        log::debug!("Running SessionValidator");
+        check_session(context)
+    }
+}
+
+impl Validator<TwoFactorCmd> for SessionValidator {
+    fn validate(&self, context: &Context, _cmd: &TwoFactorCmd) -> ValidationResult {
+        log::debug!("Running SessionValidator");
+        check_session(context)
+    }
+}
+
+impl Validator<RecoveryCmd> for SessionValidator {
+    fn validate(&self, context: &Context, _cmd: &RecoveryCmd) -> ValidationResult {
+        log::debug!("Running SessionValidator");
+        check_session(context)
+    }
+}
+
+impl Validator<EmergencyKitCmd> for SessionValidator {
+    fn validate(&self, context: &Context, _cmd: &EmergencyKitCmd) -> ValidationResult {
+        log::debug!("Running SessionValidator");
+        check_session(context)
+    }
+}
+
+impl Validator<VaultCmd> for SessionValidator {
+    fn validate(&self, context: &Context, _cmd: &VaultCmd) -> ValidationResult {
+        log::debug!("Running SessionValidator");
+        check_session(context)
     }
 }
@@ -0,0 +1,41 @@
+use crate::cli::commands::{AddCmd, UpdateCmd};
+use crate::context::Context;
+use crate::strength;
+use crate::validator::core::{Validator, ValidationResult};
+
+pub struct PasswordStrengthValidator {}
+
+impl PasswordStrengthValidator {
+    fn check(context: &Context, name: &str, password: &str) -> ValidationResult {
+        let (username, min_score) = {
+            let kgc = context.kgc.borrow();
+            (kgc.get_user_login(), kgc.get_settings().min_password_score().unwrap_or(2))
+        };
+
+        let estimate = strength::estimate(password, &[&username, name]);
+        log::info!("Password strength: {}", estimate);
+
+        if (estimate.score as u32) < min_score {
+            return ValidationResult::Failure(format!(
+                "Password is too weak: score {} is below the minimum of {} (see `kofl config set min_password_score`)",
+                estimate.score, min_score
+            ));
+        }
+        ValidationResult::Success
+    }
+}
+
+impl Validator<AddCmd> for PasswordStrengthValidator {
+    fn validate(&self, context: &Context, cmd: &AddCmd) -> ValidationResult {
+        log::debug!("Running PasswordStrengthValidator");
+        // generated passwords are estimated too, --length 6 or a two word passphrase is weak
+        PasswordStrengthValidator::check(context, &cmd.name, &cmd.new_password())
+    }
+}
+
+impl Validator<UpdateCmd> for PasswordStrengthValidator {
+    fn validate(&self, context: &Context, cmd: &UpdateCmd) -> ValidationResult {
+        log::debug!("Running PasswordStrengthValidator for UpdateCmd");
+        PasswordStrengthValidator::check(context, &cmd.name, &cmd.new_password())
+    }
+}
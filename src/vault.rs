@@ -0,0 +1,361 @@
+//! Independent vaults, each with its own config, database, session and master password.
+//!
+//! `default` is the vault kofl always had, kept directly in the directories of
+//! `crate::layout`. Named vaults live in a `vaults/<name>` directory under each
+//! of them and back up to `backups/<name>` in the data directory. The vault is
+//! picked with `--vault`, then `KOFL_VAULT`, then the one chosen with `kofl vault use`.
+
+use crate::layout;
+use crate::utils::Utils::get_home_dir;
+use log::{info, warn};
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+pub const DEFAULT_VAULT: &str = "default";
+pub const VAULT_ENV: &str = "KOFL_VAULT";
+const VAULTS_DIR: &str = "vaults";
+const CURRENT_FILE: &str = "current";
+const MAX_NAME_LEN: usize = 32;
+
+static SELECTED: OnceLock<String> = OnceLock::new();
+
+/// Where the files of one vault are kept.
+#[derive(Debug, Clone, PartialEq)]
+pub struct VaultPaths {
+    pub config: PathBuf,
+    pub storage: PathBuf,
+    pub session: PathBuf,
+    pub backups: PathBuf,
+}
+
+impl VaultPaths {
+    /// Creates the directories the files go in, the runtime one may be gone after a reboot.
+    pub fn create_dirs(&self) -> Result<(), String> {
+        for file in [&self.config, &self.storage, &self.session] {
+            if let Some(dir) = file.parent() {
+                layout::create_private_dir(dir)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+pub fn validate_name(name: &str) -> Result<(), String> {
+    if name.is_empty() || name.len() > MAX_NAME_LEN {
+        return Err(format!("A vault name has 1 to {} characters", MAX_NAME_LEN));
+    }
+    if !name.chars().all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-' || c == '_') {
+        return Err(format!("Invalid vault name {}, use lowercase letters, digits, '-' and '_'", name));
+    }
+    if name.starts_with('-') || name == CURRENT_FILE {
+        return Err(format!("{} cannot be used as a vault name", name));
+    }
+    Ok(())
+}
+
+// named vaults, and the file of `kofl vault use`
+pub fn vaults_dir() -> PathBuf {
+    layout::config_dir().join(VAULTS_DIR)
+}
+
+/// Records the vault chosen with `kofl vault use`.
+pub fn current_file() -> PathBuf {
+    vaults_dir().join(CURRENT_FILE)
+}
+
+pub fn paths(name: &str) -> VaultPaths {
+    let (config_dir, data_dir, runtime_dir) = (layout::config_dir(), layout::data_dir(), layout::runtime_dir());
+    let backups = data_dir.join("backups");
+    if name == DEFAULT_VAULT {
+        return VaultPaths {
+            config: config_dir.join("config.toml"),
+            storage: data_dir.join("kofl.sqlite"),
+            session: runtime_dir.join("session.toml"),
+            backups,
+        };
+    }
+    VaultPaths {
+        config: config_dir.join(VAULTS_DIR).join(name).join("config.toml"),
+        storage: data_dir.join(VAULTS_DIR).join(name).join("kofl.sqlite"),
+        session: runtime_dir.join(VAULTS_DIR).join(name).join("session.toml"),
+        backups: backups.join(name),
+    }
+}
+
+/// The vault from `--vault`, `KOFL_VAULT` or `kofl vault use`, in that order.
+pub fn resolve(flag: Option<&str>) -> String {
+    if let Some(name) = flag {
+        return name.to_string();
+    }
+    match env::var(VAULT_ENV) {
+        Ok(name) if !name.is_empty() => name,
+        _ => current(),
+    }
+}
+
+/// Makes `name` the vault of this process, only the first call counts.
+pub fn select(name: &str) {
+    let _ = SELECTED.set(name.to_string());
+}
+
+pub fn selected() -> String {
+    SELECTED.get().cloned().unwrap_or_else(|| DEFAULT_VAULT.to_string())
+}
+
+/// Paths of the vault this process works on.
+pub fn active() -> VaultPaths {
+    paths(&selected())
+}
+
+pub fn exists(name: &str) -> bool {
+    name == DEFAULT_VAULT || vaults_dir().join(name).is_dir()
+}
+
+/// The default vault first, then the named ones by name.
+pub fn list() -> Vec<String> {
+    let mut names: Vec<String> = fs::read_dir(vaults_dir())
+        .map(|entries| {
+            entries
+                .filter_map(|entry| entry.ok())
+                .filter(|entry| entry.path().is_dir())
+                .filter_map(|entry| entry.file_name().into_string().ok())
+                .filter(|name| validate_name(name).is_ok() && name != DEFAULT_VAULT)
+                .collect()
+        })
+        .unwrap_or_default();
+    names.sort();
+    names.insert(0, DEFAULT_VAULT.to_string());
+    names
+}
+
+/// The vault set with `kofl vault use`, `default` if none.
+pub fn current() -> String {
+    fs::read_to_string(current_file())
+        .ok()
+        .map(|name| name.trim().to_string())
+        .filter(|name| validate_name(name).is_ok() && exists(name))
+        .unwrap_or_else(|| DEFAULT_VAULT.to_string())
+}
+
+pub fn set_current(name: &str) -> Result<(), String> {
+    create_vaults_dir()?;
+    layout::write_private(&current_file(), format!("{}\n", name)).map_err(|e| e.to_string())
+}
+
+fn create_vaults_dir() -> Result<(), String> {
+    layout::create_private_dir(&vaults_dir())
+}
+
+/// Refuses a name that is invalid or taken, before anything of the vault is made.
+pub fn check_new(name: &str) -> Result<(), String> {
+    validate_name(name)?;
+    if exists(name) {
+        return Err(format!("The vault {} already exists", name));
+    }
+    Ok(())
+}
+
+/// Makes the directories of a new named vault.
+pub fn create(name: &str) -> Result<(), String> {
+    check_new(name)?;
+    paths(name).create_dirs()
+}
+
+/// Removes what is left of a named vault, its backups are kept.
+pub fn remove(name: &str) -> Result<(), String> {
+    if name == DEFAULT_VAULT {
+        return Err("The default vault cannot be removed, `kofl destroy` wipes it".to_string());
+    }
+    let paths = paths(name);
+    // with KOFL_HOME the three are the same directory
+    for file in [&paths.session, &paths.storage, &paths.config] {
+        let dir = file.parent().expect("vault files are in a directory");
+        if dir.exists() {
+            fs::remove_dir_all(dir).map_err(|e| format!("Cannot remove {}: {}", dir.display(), e))?;
+        }
+    }
+    if current() == DEFAULT_VAULT {
+        let _ = fs::remove_file(current_file());
+    }
+    Ok(())
+}
+
+/// Moves what an older kofl kept directly in the home directory to the layout
+/// of `crate::layout`. Nothing is moved into `KOFL_HOME`, and sessions are
+/// dropped instead of moved, the next `kofl login` starts a new one.
+pub fn migrate_legacy() -> Result<(), String> {
+    if layout::kofl_home().is_some() {
+        return Ok(());
+    }
+    migrate_from(&get_home_dir().expect("Home directory not found"))
+}
+
+fn migrate_from(home_dir: &Path) -> Result<(), String> {
+    let legacy_vaults = home_dir.join(".kofl_vaults");
+    let mut vaults = vec![(DEFAULT_VAULT.to_string(), home_dir.to_path_buf())];
+    if let Ok(entries) = fs::read_dir(&legacy_vaults) {
+        for entry in entries.filter_map(|entry| entry.ok()) {
+            match entry.file_name().into_string() {
+                Ok(name) if validate_name(&name).is_ok() && entry.path().is_dir() => vaults.push((name, entry.path())),
+                _ => (),
+            }
+        }
+    }
+
+    for (name, dir) in &vaults {
+        let legacy_config = dir.join(".kofl");
+        if !legacy_config.exists() {
+            continue;
+        }
+        let paths = paths(name);
+        if paths.config.exists() {
+            warn!("{} is left alone, the {} vault is already in {}", legacy_config.display(), name, paths.config.display());
+            continue;
+        }
+        paths.create_dirs()?;
+        // the config goes last, as long as it is there the move is not done
+        let moves = [
+            (dir.join("kofl.sqlite"), paths.storage.clone()),
+            (dir.join(".kofl.checksum"), paths.config.with_extension("checksum")),
+            (legacy_config, paths.config.clone()),
+        ];
+        for (from, to) in &moves {
+            if from.exists() {
+                layout::move_path(from, to)?;
+            }
+        }
+        let _ = fs::remove_file(dir.join(".kofl_session"));
+        info!("Moved the {} vault from {} to {}", name, dir.display(), paths.config.display());
+        if name != DEFAULT_VAULT {
+            let _ = fs::remove_dir(dir);
+        }
+    }
+
+    let legacy_backups = home_dir.join(".kofl_backups");
+    let backups = paths(DEFAULT_VAULT).backups;
+    if legacy_backups.is_dir() {
+        if backups.exists() {
+            warn!("{} is left alone, backups are now kept in {}", legacy_backups.display(), backups.display());
+        } else {
+            layout::move_path(&legacy_backups, &backups)?;
+            info!("Moved the backups to {}", backups.display());
+        }
+    }
+
+    let legacy_current = legacy_vaults.join(CURRENT_FILE);
+    if legacy_current.exists() && !current_file().exists() {
+        layout::move_path(&legacy_current, &current_file())?;
+    }
+    let _ = fs::remove_dir(&legacy_vaults);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serial_test::serial;
+    use tempfile::TempDir;
+
+    // restores KOFL_HOME when dropped
+    struct KoflHomeGuard(Option<String>);
+
+    impl Drop for KoflHomeGuard {
+        fn drop(&mut self) {
+            match &self.0 {
+                Some(original) => env::set_var(layout::HOME_ENV, original),
+                None => env::remove_var(layout::HOME_ENV),
+            }
+        }
+    }
+
+    fn kofl_home(temp_dir: &TempDir) -> KoflHomeGuard {
+        let guard = KoflHomeGuard(env::var(layout::HOME_ENV).ok());
+        env::set_var(layout::HOME_ENV, temp_dir.path());
+        guard
+    }
+
+    #[test]
+    fn test_validate_name() {
+        assert!(validate_name("personal").is_ok());
+        assert!(validate_name("client_42-a").is_ok());
+        assert!(validate_name("").is_err());
+        assert!(validate_name("Team").is_err());
+        assert!(validate_name("../team").is_err());
+        assert!(validate_name("-team").is_err());
+        assert!(validate_name("current").is_err());
+        assert!(validate_name(&"a".repeat(33)).is_err());
+    }
+
+    #[test]
+    #[serial]
+    fn test_paths() {
+        let temp_dir = TempDir::new().unwrap();
+        let _guard = kofl_home(&temp_dir);
+        let default = paths(DEFAULT_VAULT);
+        assert_eq!(default.config, temp_dir.path().join("config.toml"));
+        assert_eq!(default.session, temp_dir.path().join("session.toml"));
+
+        let team = paths("team");
+        assert_eq!(team.config, temp_dir.path().join("vaults/team/config.toml"));
+        assert_eq!(team.storage, temp_dir.path().join("vaults/team/kofl.sqlite"));
+        assert_eq!(team.backups, temp_dir.path().join("backups/team"));
+        assert_ne!(team.session, default.session);
+    }
+
+    #[test]
+    #[serial]
+    fn test_check_new_makes_nothing() {
+        let temp_dir = TempDir::new().unwrap();
+        let _guard = kofl_home(&temp_dir);
+        assert!(check_new("team").is_ok());
+        assert!(!vaults_dir().exists());
+        assert!(check_new(DEFAULT_VAULT).is_err());
+        assert!(check_new("Team").is_err());
+
+        create("team").unwrap();
+        assert!(exists("team"));
+        assert!(check_new("team").is_err());
+    }
+
+    #[test]
+    #[serial]
+    fn test_migrate_from_home() {
+        let home = TempDir::new().unwrap();
+        let temp_dir = TempDir::new().unwrap();
+        let _guard = kofl_home(&temp_dir);
+        for (file, content) in [
+            (".kofl", "config"),
+            (".kofl.checksum", "checksum"),
+            ("kofl.sqlite", "database"),
+            (".kofl_session", "session"),
+            (".kofl_backups/kofl_backup_1.tar.gz", "archive"),
+            (".kofl_vaults/team/.kofl", "team config"),
+            (".kofl_vaults/current", "team\n"),
+        ] {
+            let path = home.path().join(file);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(path, content).unwrap();
+        }
+
+        migrate_from(home.path()).unwrap();
+        let default = paths(DEFAULT_VAULT);
+        assert_eq!(fs::read_to_string(&default.config).unwrap(), "config");
+        assert_eq!(fs::read_to_string(default.config.with_extension("checksum")).unwrap(), "checksum");
+        assert_eq!(fs::read_to_string(&default.storage).unwrap(), "database");
+        assert!(!default.session.exists(), "sessions are not carried over");
+        assert!(default.backups.join("kofl_backup_1.tar.gz").exists());
+        assert_eq!(fs::read_to_string(paths("team").config).unwrap(), "team config");
+        assert_eq!(current(), "team");
+        assert_eq!(fs::read_dir(home.path()).unwrap().count(), 0, "nothing is left in the home directory");
+
+        // a second run finds nothing to move
+        migrate_from(home.path()).unwrap();
+    }
+
+    #[test]
+    fn test_resolve_prefers_the_flag() {
+        assert_eq!(resolve(Some("team")), "team");
+    }
+}